@@ -1,10 +1,14 @@
-use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use tokio::process::Command;
 
 use crate::{
-    parse_stream_json_lines, process, ClaudeCodeError, ClaudeOutputFormat, ClaudePrintRequest,
-    CommandOutput, StreamJsonLineOutcome,
+    parse_stream_json_lines, process, stream_json::parse_stream_json_line, ClaudeCodeError,
+    ClaudeOutputFormat, ClaudePrintRequest, CommandOutput, StreamJsonLineOutcome,
 };
 
 #[derive(Debug, Clone)]
@@ -15,6 +19,7 @@ pub struct ClaudeClientBuilder {
     timeout: Option<Duration>,
     mirror_stdout: bool,
     mirror_stderr: bool,
+    validate_before_run: bool,
 }
 
 impl Default for ClaudeClientBuilder {
@@ -26,6 +31,7 @@ impl Default for ClaudeClientBuilder {
             timeout: Some(Duration::from_secs(120)),
             mirror_stdout: false,
             mirror_stderr: false,
+            validate_before_run: false,
         }
     }
 }
@@ -61,6 +67,12 @@ impl ClaudeClientBuilder {
         self
     }
 
+    /// Runs [`ClaudeClient::validate`] before every `print`/`print_stream` call.
+    pub fn validate_before_run(mut self, enabled: bool) -> Self {
+        self.validate_before_run = enabled;
+        self
+    }
+
     pub fn build(mut self) -> ClaudeClient {
         // Avoid any updater side effects by default; callers may override explicitly.
         self.env
@@ -74,6 +86,7 @@ impl ClaudeClientBuilder {
             timeout: self.timeout,
             mirror_stdout: self.mirror_stdout,
             mirror_stderr: self.mirror_stderr,
+            validate_before_run: self.validate_before_run,
         }
     }
 }
@@ -86,6 +99,7 @@ pub struct ClaudeClient {
     timeout: Option<Duration>,
     mirror_stdout: bool,
     mirror_stderr: bool,
+    validate_before_run: bool,
 }
 
 impl ClaudeClient {
@@ -93,6 +107,34 @@ impl ClaudeClient {
         ClaudeClientBuilder::default()
     }
 
+    /// Checks that the resolved binary is an executable file (directly or via `PATH`) and that
+    /// `working_dir`, when set, is an existing directory.
+    pub fn validate(&self) -> Result<(), ClaudeCodeError> {
+        let binary = self.resolve_binary();
+        if !binary_is_runnable(&binary) {
+            return Err(ClaudeCodeError::InvalidRequest(format!(
+                "claude binary `{}` is not an executable file or was not found on PATH",
+                binary.display()
+            )));
+        }
+
+        if let Some(dir) = self.working_dir.as_ref() {
+            if !dir.is_dir() {
+                let problem = if dir.exists() {
+                    "is not a directory"
+                } else {
+                    "does not exist"
+                };
+                return Err(ClaudeCodeError::InvalidRequest(format!(
+                    "working_dir `{}` {problem}",
+                    dir.display()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn print(
         &self,
         request: ClaudePrintRequest,
@@ -102,6 +144,9 @@ impl ClaudeClient {
                 "either prompt or stdin_bytes must be provided".to_string(),
             ));
         }
+        if self.validate_before_run {
+            self.validate()?;
+        }
 
         let binary = self.resolve_binary();
         let mut cmd = Command::new(&binary);
@@ -139,6 +184,65 @@ impl ClaudeClient {
         Ok(ClaudePrintResult { output, parsed })
     }
 
+    /// Runs a `--output-format stream-json` print request and invokes `on_line` for each parsed
+    /// line while the process is still running.
+    ///
+    /// The returned result carries the full [`CommandOutput`]; `parsed` is left as `None` since
+    /// every line has already been delivered to the callback.
+    pub async fn print_stream<F>(
+        &self,
+        request: ClaudePrintRequest,
+        mut on_line: F,
+    ) -> Result<ClaudePrintResult, ClaudeCodeError>
+    where
+        F: FnMut(StreamJsonLineOutcome),
+    {
+        if request.prompt.is_none() && request.stdin.is_none() {
+            return Err(ClaudeCodeError::InvalidRequest(
+                "either prompt or stdin_bytes must be provided".to_string(),
+            ));
+        }
+        if request.output_format != ClaudeOutputFormat::StreamJson {
+            return Err(ClaudeCodeError::InvalidRequest(
+                "print_stream requires output_format(StreamJson)".to_string(),
+            ));
+        }
+        if self.validate_before_run {
+            self.validate()?;
+        }
+
+        let binary = self.resolve_binary();
+        let mut cmd = Command::new(&binary);
+        cmd.args(request.argv());
+
+        if let Some(dir) = self.working_dir.as_ref() {
+            cmd.current_dir(dir);
+        }
+
+        process::apply_env(&mut cmd, &self.env);
+
+        let timeout = request.timeout.or(self.timeout);
+        let output = process::run_command_with_lines(
+            cmd,
+            &binary,
+            request.stdin.as_deref(),
+            timeout,
+            self.mirror_stdout,
+            self.mirror_stderr,
+            |line_number, raw| {
+                if let Some(outcome) = parse_stream_json_line(line_number, raw) {
+                    on_line(outcome);
+                }
+            },
+        )
+        .await?;
+
+        Ok(ClaudePrintResult {
+            output,
+            parsed: None,
+        })
+    }
+
     fn resolve_binary(&self) -> PathBuf {
         if let Some(b) = self.binary.as_ref() {
             return b.clone();
@@ -152,6 +256,33 @@ impl ClaudeClient {
     }
 }
 
+fn binary_is_runnable(binary: &Path) -> bool {
+    if binary.components().count() > 1 {
+        return is_executable_file(binary);
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| is_executable_file(&dir.join(binary))))
+        .unwrap_or(false)
+}
+
+fn is_executable_file(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ClaudePrintResult {
     pub output: CommandOutput,
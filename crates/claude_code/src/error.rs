@@ -35,6 +35,13 @@ pub enum ClaudeCodeError {
     JsonParse(#[from] serde_json::Error),
 }
 
+impl ClaudeCodeError {
+    /// True when the run hit its deadline, mirroring `CodexError::Timeout` for shared retry logic.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, ClaudeCodeError::Timeout { .. })
+    }
+}
+
 #[derive(Debug, Error, Clone)]
 #[error("stream-json line {line_number}: {message}")]
 pub struct StreamJsonLineError {
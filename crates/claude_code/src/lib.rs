@@ -14,6 +14,8 @@ pub mod wrapper_coverage_manifest;
 pub use client::{ClaudeClient, ClaudeClientBuilder};
 pub use error::{ClaudeCodeError, StreamJsonLineError};
 pub use request::{ClaudeInputFormat, ClaudeOutputFormat, ClaudePrintRequest};
-pub use stream_json::{parse_stream_json_lines, StreamJsonLine, StreamJsonLineOutcome};
+pub use stream_json::{
+    parse_stream_json_lines, StreamJsonContentBlock, StreamJsonLine, StreamJsonLineOutcome,
+};
 
 pub use process::CommandOutput;
@@ -7,7 +7,7 @@ use std::{
 };
 
 use tokio::{
-    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
     process::Command,
     task, time,
 };
@@ -103,6 +103,7 @@ pub(crate) async fn run_command(
     });
     command.stdout(std::process::Stdio::piped());
     command.stderr(std::process::Stdio::piped());
+    command.kill_on_drop(true);
 
     let mut child = spawn_with_retry(&mut command, binary)?;
 
@@ -147,6 +148,97 @@ pub(crate) async fn run_command(
     })
 }
 
+/// Like [`run_command`], but hands each stdout line to `on_line` as soon as it is read.
+///
+/// Stdout is still buffered in full so the returned [`CommandOutput`] matches `run_command`.
+pub(crate) async fn run_command_with_lines<F>(
+    mut command: Command,
+    binary: &Path,
+    stdin_bytes: Option<&[u8]>,
+    timeout: Option<Duration>,
+    mirror_stdout: bool,
+    mirror_stderr: bool,
+    mut on_line: F,
+) -> Result<CommandOutput, ClaudeCodeError>
+where
+    F: FnMut(usize, &str),
+{
+    command.stdin(if stdin_bytes.is_some() {
+        std::process::Stdio::piped()
+    } else {
+        std::process::Stdio::null()
+    });
+    command.stdout(std::process::Stdio::piped());
+    command.stderr(std::process::Stdio::piped());
+    command.kill_on_drop(true);
+
+    let mut child = spawn_with_retry(&mut command, binary)?;
+
+    if let Some(bytes) = stdin_bytes {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(bytes)
+                .await
+                .map_err(ClaudeCodeError::StdinWrite)?;
+        }
+    }
+
+    let stdout = child.stdout.take().ok_or(ClaudeCodeError::MissingStdout)?;
+    let stderr = child.stderr.take().ok_or(ClaudeCodeError::MissingStderr)?;
+
+    let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, mirror_stderr));
+
+    let run = async {
+        let mut reader = BufReader::new(stdout);
+        let mut stdout_bytes = Vec::new();
+        let mut line = Vec::new();
+        let mut line_number = 0;
+        loop {
+            line.clear();
+            let n = reader
+                .read_until(b'\n', &mut line)
+                .await
+                .map_err(ClaudeCodeError::StdoutRead)?;
+            if n == 0 {
+                break;
+            }
+            line_number += 1;
+            if mirror_stdout {
+                task::block_in_place(|| {
+                    let mut out = io::stdout();
+                    out.write_all(&line)?;
+                    out.flush()
+                })
+                .map_err(ClaudeCodeError::StdoutRead)?;
+            }
+            stdout_bytes.extend_from_slice(&line);
+            let text = String::from_utf8_lossy(&line);
+            on_line(line_number, text.trim_end_matches('\n'));
+        }
+        let status = child.wait().await.map_err(ClaudeCodeError::Wait)?;
+        Ok::<_, ClaudeCodeError>((status, stdout_bytes))
+    };
+
+    let (status, stdout) = if let Some(dur) = timeout {
+        time::timeout(dur, run)
+            .await
+            .map_err(|_| ClaudeCodeError::Timeout { timeout: dur })??
+    } else {
+        run.await?
+    };
+
+    let stderr = stderr_task
+        .await
+        .map_err(|e| ClaudeCodeError::Join(e.to_string()))?
+        .map_err(ClaudeCodeError::StderrRead)?;
+
+    Ok(CommandOutput {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
 pub(crate) fn apply_env(command: &mut Command, env: &BTreeMap<String, String>) {
     for (k, v) in env {
         command.env(k, v);
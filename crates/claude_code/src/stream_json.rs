@@ -1,4 +1,5 @@
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
 use crate::StreamJsonLineError;
 
@@ -21,27 +22,131 @@ pub enum StreamJsonLineOutcome {
 }
 
 pub fn parse_stream_json_lines(text: &str) -> Vec<StreamJsonLineOutcome> {
-    let mut out = Vec::new();
-    for (idx, raw) in text.lines().enumerate() {
-        let line_number = idx + 1;
-        let raw = raw.trim_end_matches('\r');
-        if raw.trim().is_empty() {
-            continue;
+    text.lines()
+        .enumerate()
+        .filter_map(|(idx, raw)| parse_stream_json_line(idx + 1, raw))
+        .collect()
+}
+
+/// Parses a single stream-json line; blank lines yield `None`.
+pub(crate) fn parse_stream_json_line(
+    line_number: usize,
+    raw: &str,
+) -> Option<StreamJsonLineOutcome> {
+    let raw = raw.trim_end_matches('\r');
+    if raw.trim().is_empty() {
+        return None;
+    }
+    let line = StreamJsonLine {
+        line_number,
+        raw: raw.to_string(),
+    };
+    Some(match serde_json::from_str::<Value>(&line.raw) {
+        Ok(value) => StreamJsonLineOutcome::Ok { line, value },
+        Err(err) => StreamJsonLineOutcome::Err {
+            line,
+            error: StreamJsonLineError {
+                line_number,
+                message: err.to_string(),
+            },
+        },
+    })
+}
+
+/// A typed content block from an `assistant`/`user` stream-json message.
+///
+/// Block types this crate does not model are preserved verbatim as [`Unknown`](Self::Unknown).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Value", into = "Value")]
+pub enum StreamJsonContentBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: Value,
+        is_error: bool,
+    },
+    Unknown(Value),
+}
+
+impl StreamJsonContentBlock {
+    /// Extracts the content blocks of a stream-json message line (`message.content`).
+    ///
+    /// Plain string content is surfaced as a single [`Text`](Self::Text) block; lines without a
+    /// message yield an empty list.
+    pub fn from_message(value: &Value) -> Vec<Self> {
+        match value
+            .get("message")
+            .and_then(|message| message.get("content"))
+        {
+            Some(Value::Array(blocks)) => blocks.iter().cloned().map(Self::from).collect(),
+            Some(Value::String(text)) => vec![Self::Text { text: text.clone() }],
+            _ => Vec::new(),
         }
-        let line = StreamJsonLine {
-            line_number,
-            raw: raw.to_string(),
+    }
+}
+
+impl From<Value> for StreamJsonContentBlock {
+    fn from(value: Value) -> Self {
+        let str_field = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+        let typed = match value.get("type").and_then(Value::as_str) {
+            Some("text") => str_field("text").map(|text| Self::Text { text }),
+            Some("tool_use") => match (str_field("id"), str_field("name")) {
+                (Some(id), Some(name)) => Some(Self::ToolUse {
+                    id,
+                    name,
+                    input: value.get("input").cloned().unwrap_or(Value::Null),
+                }),
+                _ => None,
+            },
+            Some("tool_result") => str_field("tool_use_id").map(|tool_use_id| Self::ToolResult {
+                tool_use_id,
+                content: value.get("content").cloned().unwrap_or(Value::Null),
+                is_error: value
+                    .get("is_error")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            }),
+            _ => None,
         };
-        match serde_json::from_str::<Value>(&line.raw) {
-            Ok(value) => out.push(StreamJsonLineOutcome::Ok { line, value }),
-            Err(err) => out.push(StreamJsonLineOutcome::Err {
-                line,
-                error: StreamJsonLineError {
-                    line_number,
-                    message: err.to_string(),
-                },
+        typed.unwrap_or(Self::Unknown(value))
+    }
+}
+
+impl From<StreamJsonContentBlock> for Value {
+    fn from(block: StreamJsonContentBlock) -> Self {
+        match block {
+            StreamJsonContentBlock::Text { text } => json!({ "type": "text", "text": text }),
+            StreamJsonContentBlock::ToolUse { id, name, input } => {
+                json!({ "type": "tool_use", "id": id, "name": name, "input": input })
+            }
+            StreamJsonContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } => json!({
+                "type": "tool_result",
+                "tool_use_id": tool_use_id,
+                "content": content,
+                "is_error": is_error,
             }),
+            StreamJsonContentBlock::Unknown(value) => value,
+        }
+    }
+}
+
+impl StreamJsonLineOutcome {
+    /// Typed content blocks for successfully parsed message lines; empty otherwise.
+    pub fn content_blocks(&self) -> Vec<StreamJsonContentBlock> {
+        match self {
+            Self::Ok { value, .. } => StreamJsonContentBlock::from_message(value),
+            Self::Err { .. } => Vec::new(),
         }
     }
-    out
 }
@@ -0,0 +1,180 @@
+#![cfg(unix)]
+
+use std::{fs, os::unix::fs::PermissionsExt, path::Path, time::Duration};
+
+use claude_code::{
+    ClaudeClient, ClaudeCodeError, ClaudeOutputFormat, ClaudePrintRequest, StreamJsonLineOutcome,
+};
+
+fn write_fake_claude(dir: &Path, script: &str) -> std::path::PathBuf {
+    let path = dir.join("claude");
+    fs::write(&path, script).unwrap();
+    let mut perms = fs::metadata(&path).unwrap().permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&path, perms).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn print_stream_delivers_lines_while_process_runs() {
+    let temp = tempfile::tempdir().unwrap();
+    let ack = temp.path().join("ack");
+    let script = format!(
+        r#"#!/usr/bin/env bash
+echo '{{"type":"system","n":1}}'
+for _ in $(seq 1 200); do
+  [[ -f "{ack}" ]] && break
+  sleep 0.01
+done
+[[ -f "{ack}" ]] || exit 9
+echo 'not json'
+echo '{{"type":"result","n":2}}'
+"#,
+        ack = ack.display()
+    );
+    let binary = write_fake_claude(temp.path(), &script);
+    let client = ClaudeClient::builder()
+        .binary(&binary)
+        .timeout(Some(Duration::from_secs(10)))
+        .build();
+
+    let mut seen = Vec::new();
+    let result = client
+        .print_stream(
+            ClaudePrintRequest::new("hi").output_format(ClaudeOutputFormat::StreamJson),
+            |outcome| {
+                if seen.is_empty() {
+                    fs::write(&ack, b"ok").unwrap();
+                }
+                seen.push(outcome);
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(result.output.status.success());
+    assert!(result.parsed.is_none());
+    assert_eq!(seen.len(), 3);
+    match &seen[0] {
+        StreamJsonLineOutcome::Ok { value, line } => {
+            assert_eq!(value["n"], 1);
+            assert_eq!(line.line_number, 1);
+        }
+        other => panic!("expected ok, got {other:?}"),
+    }
+    assert!(matches!(&seen[1], StreamJsonLineOutcome::Err { .. }));
+    match &seen[2] {
+        StreamJsonLineOutcome::Ok { value, .. } => assert_eq!(value["n"], 2),
+        other => panic!("expected ok, got {other:?}"),
+    }
+    let stdout = String::from_utf8(result.output.stdout).unwrap();
+    assert!(stdout.ends_with("{\"type\":\"result\",\"n\":2}\n"));
+}
+
+#[tokio::test]
+async fn print_stream_requires_stream_json_format() {
+    let client = ClaudeClient::builder()
+        .binary("/nonexistent/claude")
+        .build();
+    let err = client
+        .print_stream(ClaudePrintRequest::new("hi"), |_| {})
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClaudeCodeError::InvalidRequest(_)));
+}
+
+#[tokio::test]
+async fn validate_reports_missing_binary_and_working_dir() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_claude(temp.path(), "#!/usr/bin/env bash\necho ok\n");
+
+    let client = ClaudeClient::builder().binary(&binary).build();
+    client.validate().unwrap();
+
+    let missing_binary = ClaudeClient::builder()
+        .binary(temp.path().join("nope"))
+        .build();
+    match missing_binary.validate() {
+        Err(ClaudeCodeError::InvalidRequest(message)) => assert!(message.contains("nope")),
+        other => panic!("expected invalid request, got {other:?}"),
+    }
+
+    let not_executable = temp.path().join("plain");
+    fs::write(&not_executable, "data").unwrap();
+    assert!(ClaudeClient::builder()
+        .binary(&not_executable)
+        .build()
+        .validate()
+        .is_err());
+
+    let bad_dir = ClaudeClient::builder()
+        .binary(&binary)
+        .working_dir(temp.path().join("missing-dir"))
+        .build();
+    match bad_dir.validate() {
+        Err(ClaudeCodeError::InvalidRequest(message)) => {
+            assert!(message.contains("does not exist"))
+        }
+        other => panic!("expected invalid request, got {other:?}"),
+    }
+
+    let file_dir = ClaudeClient::builder()
+        .binary(&binary)
+        .working_dir(&binary)
+        .build();
+    match file_dir.validate() {
+        Err(ClaudeCodeError::InvalidRequest(message)) => {
+            assert!(message.contains("is not a directory"))
+        }
+        other => panic!("expected invalid request, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn validate_before_run_fails_print_early() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_claude(temp.path(), "#!/usr/bin/env bash\necho ok\n");
+    let client = ClaudeClient::builder()
+        .binary(&binary)
+        .working_dir(temp.path().join("missing-dir"))
+        .validate_before_run(true)
+        .build();
+
+    let err = client
+        .print(ClaudePrintRequest::new("hi"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClaudeCodeError::InvalidRequest(_)));
+}
+
+#[tokio::test]
+async fn print_and_print_stream_report_timeouts() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_claude(temp.path(), "#!/usr/bin/env bash\nsleep 5\n");
+    let client = ClaudeClient::builder()
+        .binary(&binary)
+        .timeout(Some(Duration::from_millis(200)))
+        .build();
+
+    let started = std::time::Instant::now();
+    let err = client
+        .print(ClaudePrintRequest::new("hi"))
+        .await
+        .unwrap_err();
+    assert!(err.is_timeout());
+    assert!(matches!(
+        err,
+        ClaudeCodeError::Timeout { timeout } if timeout == Duration::from_millis(200)
+    ));
+    assert!(started.elapsed() < Duration::from_secs(4));
+
+    let err = client
+        .print_stream(
+            ClaudePrintRequest::new("hi").output_format(ClaudeOutputFormat::StreamJson),
+            |_| {},
+        )
+        .await
+        .unwrap_err();
+    assert!(err.is_timeout());
+    assert!(!ClaudeCodeError::MissingStdout.is_timeout());
+}
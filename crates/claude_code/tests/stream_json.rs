@@ -1,4 +1,4 @@
-use claude_code::{parse_stream_json_lines, StreamJsonLineOutcome};
+use claude_code::{parse_stream_json_lines, StreamJsonContentBlock, StreamJsonLineOutcome};
 
 #[test]
 fn parse_stream_json_lines_is_tolerant() {
@@ -27,3 +27,62 @@ not json
         _ => panic!("expected ok"),
     }
 }
+
+#[test]
+fn content_blocks_are_typed_and_round_trip() {
+    let fixture = include_str!("fixtures/stream_json_tool_use.jsonl");
+    let lines = parse_stream_json_lines(fixture);
+    assert_eq!(lines.len(), 5);
+
+    assert!(lines[0].content_blocks().is_empty());
+    assert!(lines[4].content_blocks().is_empty());
+
+    let assistant = lines[1].content_blocks();
+    assert_eq!(
+        assistant[0],
+        StreamJsonContentBlock::Text {
+            text: "Let me list the files.".to_string()
+        }
+    );
+    match &assistant[1] {
+        StreamJsonContentBlock::ToolUse { id, name, input } => {
+            assert_eq!(id, "toolu_01");
+            assert_eq!(name, "Bash");
+            assert_eq!(input["command"], "ls");
+        }
+        other => panic!("expected tool_use, got {other:?}"),
+    }
+
+    match &lines[2].content_blocks()[0] {
+        StreamJsonContentBlock::ToolResult {
+            tool_use_id,
+            content,
+            is_error,
+        } => {
+            assert_eq!(tool_use_id, "toolu_01");
+            assert_eq!(content, "Cargo.toml\nsrc");
+            assert!(!is_error);
+        }
+        other => panic!("expected tool_result, got {other:?}"),
+    }
+
+    let final_turn = lines[3].content_blocks();
+    assert!(
+        matches!(&final_turn[0], StreamJsonContentBlock::Unknown(value) if value["type"] == "thinking")
+    );
+
+    for outcome in &lines {
+        let StreamJsonLineOutcome::Ok { value, .. } = outcome else {
+            panic!("fixture lines should parse");
+        };
+        let original = &value["message"]["content"];
+        if original.is_null() {
+            continue;
+        }
+        let blocks = outcome.content_blocks();
+        let reencoded = serde_json::to_value(&blocks).unwrap();
+        assert_eq!(&reencoded, original);
+        let decoded: Vec<StreamJsonContentBlock> = serde_json::from_value(reencoded).unwrap();
+        assert_eq!(decoded, blocks);
+    }
+}
@@ -90,9 +90,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .quiet(true)
         .build();
 
-    let output = client.generate_app_server_bindings(request).await?;
+    let output = client.app_server_codegen(request).await?;
     println!("app-server exit code: {:?}", output.status.code());
     println!("output dir: {}", output.out_dir.display());
+    println!("generated files: {}", output.files.len());
 
     if !output.stdout.is_empty() {
         println!("stdout:\n{}", output.stdout);
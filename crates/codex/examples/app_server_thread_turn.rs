@@ -76,7 +76,7 @@ async fn demo_app_server(binary: &Path, prompt: &str) -> Result<(), Box<dyn Erro
         "id": 0,
         "method": "initialize",
         "params": {
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": codex::mcp::MCP_PROTOCOL_VERSION,
             "capabilities": {},
             "client": {
                 "name": "codex-app-thread-turn-example",
@@ -12,8 +12,8 @@
 use std::{collections::BTreeMap, env, path::PathBuf, time::Duration};
 
 use codex::mcp::{
-    AppNotification, ClientInfo, CodexAppServer, McpError, StdioServerConfig, ThreadResumeParams,
-    ThreadStartParams, TurnInput, TurnInterruptParams, TurnStartParams,
+    AppNotification, ClientInfo, CodexAppServer, McpError, McpRequestTimeouts, StdioServerConfig,
+    ThreadResumeParams, ThreadStartParams, TurnInput, TurnInterruptParams, TurnStartParams,
 };
 use serde_json::Value;
 use tokio::time;
@@ -177,6 +177,7 @@ fn config_from_env() -> StdioServerConfig {
         app_server_analytics_default_enabled: false,
         mirror_stdio: true,
         startup_timeout: Duration::from_secs(10),
+        request_timeouts: McpRequestTimeouts::default(),
     }
 }
 
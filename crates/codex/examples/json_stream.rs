@@ -19,15 +19,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .mirror_stdout(false)
         .build();
 
-    let mut stream = client
-        .stream_exec(ExecStreamRequest {
-            prompt,
-            idle_timeout: Some(Duration::from_secs(30)),
-            output_last_message,
-            output_schema: None,
-            json_event_log,
-        })
-        .await?;
+    let mut request = ExecStreamRequest::new(prompt).idle_timeout(Duration::from_secs(30));
+    request.output_last_message = output_last_message;
+    request.json_event_log = json_event_log;
+
+    let mut stream = client.stream_exec(request).await?;
 
     println!("Streaming Codex events...");
     while let Some(event) = stream.events.next().await {
@@ -113,6 +109,17 @@ fn summarize_event(event: &ThreadEvent) -> String {
             )
         }
         ThreadEvent::Error(err) => format!("stream error: {}", err.message),
+        ThreadEvent::ThreadCompleted(summary) => format!(
+            "thread.completed turns={} items={} failed={}",
+            summary.turns, summary.items, summary.any_turn_failed
+        ),
+        ThreadEvent::Heartbeat { elapsed } => {
+            format!("still working ({}s since last event)", elapsed.as_secs())
+        }
+        ThreadEvent::Resumed { thread_id, attempt } => {
+            format!("resumed {thread_id} (attempt {attempt})")
+        }
+        _ => "unrecognized event".to_string(),
     }
 }
 
@@ -12,7 +12,7 @@ use std::{collections::BTreeMap, env, path::PathBuf, time::Duration};
 
 use codex::mcp::{
     ClientInfo, CodexCallParams, CodexEvent, CodexMcpServer, CodexReplyParams, EventStream,
-    McpError, StdioServerConfig,
+    McpError, McpRequestTimeouts, StdioServerConfig,
 };
 use tokio::time;
 
@@ -162,6 +162,7 @@ fn config_from_env() -> StdioServerConfig {
         app_server_analytics_default_enabled: false,
         mirror_stdio: true,
         startup_timeout: Duration::from_secs(10),
+        request_timeouts: McpRequestTimeouts::default(),
     }
 }
 
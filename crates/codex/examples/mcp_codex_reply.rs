@@ -106,7 +106,7 @@ async fn demo_codex_reply(
         "id": 0,
         "method": "initialize",
         "params": {
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": codex::mcp::MCP_PROTOCOL_VERSION,
             "capabilities": {},
             "client": {
                 "name": "codex-mcp-reply-example",
@@ -1,4 +1,4 @@
-//! Demonstrates `codex resume` and `codex fork` wrapper APIs.
+//! Demonstrates `codex exec resume` (streamed) and `codex fork` wrapper APIs.
 //!
 //! These commands may contact the network / model provider and are only run when explicitly
 //! requested.
@@ -15,6 +15,8 @@
 
 use std::env;
 
+use futures_util::StreamExt;
+
 use codex::{ForkSessionRequest, HelpCommandRequest, HelpScope, ResumeSessionRequest};
 
 #[path = "support/real_cli.rs"]
@@ -54,8 +56,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 if let Some(prompt) = prompt {
                     req = req.prompt(prompt);
                 }
-                let out = client.resume_session(req).await?;
-                print!("{}", out.stdout);
+                let mut stream = client.resume_session(req).await?;
+                while let Some(event) = stream.events.next().await {
+                    println!("{:?}", event?);
+                }
+                stream.completion.await?;
             } else {
                 let mut req = ForkSessionRequest::new().last(last).all(all);
                 if let Some(id) = session_id {
@@ -66,6 +71,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
                 let out = client.fork_session(req).await?;
                 print!("{}", out.stdout);
+                if let Some(thread_id) = out.thread_id {
+                    println!("forked thread: {thread_id}");
+                }
             }
         }
         _ => {
@@ -0,0 +1,59 @@
+use std::borrow::Cow;
+
+const ESC: char = '\u{1b}';
+const BEL: char = '\u{7}';
+
+/// Removes ANSI escape sequences (CSI such as `\x1b[1;32m`, OSC such as hyperlinks and
+/// window titles, and two-byte escapes), borrowing when the input contains none.
+pub(crate) fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains(ESC) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameter/intermediate bytes terminated by a final byte in `@..=~`.
+            Some('[') => {
+                for next in chars.by_ref() {
+                    if ('@'..='~').contains(&next) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or the string terminator `ESC \`.
+            Some(']') => {
+                while let Some(next) = chars.next() {
+                    if next == BEL {
+                        break;
+                    }
+                    if next == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Character set designations such as `ESC (B` carry one extra byte.
+            Some('(' | ')') => {
+                chars.next();
+            }
+            // Two-byte escapes (`ESC =`, `ESC M`) or a trailing lone ESC.
+            Some(_) | None => {}
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Stderr text to store in [`crate::CodexError::NonZeroExit`], with escape sequences removed
+/// when [`crate::CodexClientBuilder::strip_ansi_errors`] is enabled.
+pub(crate) fn error_stderr(stderr: String, strip: bool) -> String {
+    match strip_ansi(&stderr) {
+        Cow::Owned(clean) if strip => clean,
+        _ => stderr,
+    }
+}
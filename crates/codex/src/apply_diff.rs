@@ -1,17 +1,272 @@
-use std::process::ExitStatus;
+use std::{
+    path::{Path, PathBuf},
+    process::ExitStatus,
+};
 
 use super::CliOverridesPatch;
+use crate::{ItemPayload, JsonlThreadEventParser, ThreadEvent};
 
 /// Captured output from task-oriented subcommands such as `codex apply <TASK_ID>` or
 /// `codex cloud diff <TASK_ID>`.
 #[derive(Clone, Debug)]
 pub struct ApplyDiffArtifacts {
+    /// Command line that ran, with credentials redacted.
+    pub command: String,
     /// Exit status returned by the subcommand.
     pub status: ExitStatus,
     /// Captured stdout (mirrored to the console when `mirror_stdout` is true).
     pub stdout: String,
     /// Captured stderr (mirrored unless `quiet` is set).
     pub stderr: String,
+    /// True when stdout or stderr exceeded [`crate::CodexClientBuilder::max_output_bytes`] and
+    /// the captured text was cut off.
+    pub truncated: bool,
+    /// Files that failed to patch, parsed from `codex apply` output. Empty for other
+    /// subcommands and when the output is not recognized.
+    pub conflicts: Vec<ApplyConflict>,
+}
+
+impl ApplyDiffArtifacts {
+    /// True when `codex apply` reported at least one file that did not patch cleanly.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// Counts changed files and `+`/`-` lines in the captured diff without building hunks.
+    ///
+    /// Plain unified diffs (`codex cloud diff`, `git diff`) are scanned line by line: each
+    /// `diff --git` block or `---`/`+++` header pair is one file. JSONL output such as a saved
+    /// `codex exec --json` log counts one file per `file_change` item (latest state wins) and
+    /// scans each item's `diff` text. Unrecognized lines are ignored.
+    pub fn change_summary(&self) -> DiffSummary {
+        if self.stdout.trim_start().starts_with('{') {
+            DiffSummary::from_jsonl(&self.stdout)
+        } else {
+            DiffSummary::from_unified(&self.stdout)
+        }
+    }
+
+    /// Fills [`Self::conflicts`] from the captured stdout and stderr.
+    pub(crate) fn with_detected_conflicts(mut self) -> Self {
+        self.conflicts = ApplyConflict::parse_all(self.stderr.lines().chain(self.stdout.lines()));
+        self
+    }
+}
+
+/// Line counts returned by [`ApplyDiffArtifacts::change_summary`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DiffSummary {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl DiffSummary {
+    fn from_unified(diff: &str) -> Self {
+        let mut summary = DiffSummary::default();
+        let mut git_header_pending = false;
+        let mut lines = diff.lines().peekable();
+        while let Some(line) = lines.next() {
+            if line.starts_with("diff --git ") {
+                summary.files_changed += 1;
+                git_header_pending = true;
+            } else if line.starts_with("--- ")
+                && lines.peek().is_some_and(|next| next.starts_with("+++ "))
+            {
+                lines.next();
+                if !git_header_pending {
+                    summary.files_changed += 1;
+                }
+                git_header_pending = false;
+            } else if line.starts_with("@@") || line.starts_with("Binary files ") {
+                git_header_pending = false;
+            } else if line.starts_with('+') {
+                summary.insertions += 1;
+            } else if line.starts_with('-') {
+                summary.deletions += 1;
+            }
+        }
+        summary
+    }
+
+    fn from_jsonl(stdout: &str) -> Self {
+        let mut parser = JsonlThreadEventParser::new();
+        let mut diffs: Vec<(String, Option<String>)> = Vec::new();
+        for line in stdout.lines() {
+            let Ok(Some(ThreadEvent::ItemStarted(envelope) | ThreadEvent::ItemCompleted(envelope))) =
+                parser.parse_line(line)
+            else {
+                continue;
+            };
+            let ItemPayload::FileChange(change) = envelope.item.payload else {
+                continue;
+            };
+            match diffs
+                .iter_mut()
+                .find(|(id, _)| *id == envelope.item.item_id)
+            {
+                Some((_, diff)) => *diff = change.diff,
+                None => diffs.push((envelope.item.item_id, change.diff)),
+            }
+        }
+
+        let mut summary = DiffSummary {
+            files_changed: diffs.len(),
+            ..DiffSummary::default()
+        };
+        for diff in diffs.iter().filter_map(|(_, diff)| diff.as_deref()) {
+            let lines = DiffSummary::from_unified(diff);
+            summary.insertions += lines.insertions;
+            summary.deletions += lines.deletions;
+        }
+        summary
+    }
+}
+
+/// One file section of a unified diff.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct UnifiedDiffFile {
+    /// Pre-image path without the `a/` prefix; `None` for created files.
+    pub(crate) old_path: Option<PathBuf>,
+    /// Post-image path without the `b/` prefix; `None` for deleted files.
+    pub(crate) new_path: Option<PathBuf>,
+    /// The section text, headers included.
+    pub(crate) text: String,
+}
+
+/// Splits a unified diff into per-file sections, starting a section at each `diff --git` line
+/// or at a `---`/`+++` header pair outside a git header.
+pub(crate) fn split_unified_diff(diff: &str) -> Vec<UnifiedDiffFile> {
+    let mut files: Vec<UnifiedDiffFile> = Vec::new();
+    let mut headers_seen = true;
+    let mut lines = diff.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let (old_path, new_path) = match header.split_once(" b/") {
+                Some((old, new)) => (diff_path(old), diff_path(&format!("b/{new}"))),
+                None => (None, None),
+            };
+            files.push(UnifiedDiffFile {
+                old_path,
+                new_path,
+                text: String::new(),
+            });
+            headers_seen = false;
+        } else if line.starts_with("--- ")
+            && lines.peek().is_some_and(|next| next.starts_with("+++ "))
+        {
+            let next = lines.next().unwrap_or_default();
+            if headers_seen || files.is_empty() {
+                files.push(UnifiedDiffFile::default());
+            }
+            headers_seen = true;
+            if let Some(file) = files.last_mut() {
+                file.old_path = diff_path(&line[4..]);
+                file.new_path = diff_path(&next[4..]);
+                file.text.push_str(line);
+                file.text.push('\n');
+                file.text.push_str(next);
+                file.text.push('\n');
+            }
+            continue;
+        } else if line.starts_with("@@") {
+            headers_seen = true;
+        }
+        if let Some(file) = files.last_mut() {
+            file.text.push_str(line);
+            file.text.push('\n');
+        }
+    }
+    files
+}
+
+fn diff_path(raw: &str) -> Option<PathBuf> {
+    let raw = raw.split('\t').next().unwrap_or_default().trim();
+    if raw.is_empty() || raw == "/dev/null" {
+        return None;
+    }
+    let stripped = raw
+        .strip_prefix("a/")
+        .or_else(|| raw.strip_prefix("b/"))
+        .unwrap_or(raw);
+    Some(Path::new(stripped).to_path_buf())
+}
+
+/// Result of [`crate::CodexClient::apply_with_backup`].
+#[derive(Clone, Debug)]
+pub struct ApplyReport {
+    /// Output of the `codex apply` run.
+    pub artifacts: ApplyDiffArtifacts,
+    /// Backup copies written before applying, in diff order. Files the diff creates
+    /// have nothing to back up and are not listed.
+    pub backed_up: Vec<PathBuf>,
+}
+
+/// A file that `codex apply` could not patch cleanly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApplyConflict {
+    /// Path as printed by the CLI, usually relative to the repository root.
+    pub file: String,
+    /// Why the patch was rejected (e.g. `patch does not apply`).
+    pub reason: String,
+}
+
+impl ApplyConflict {
+    /// Parses `git apply`-style failure lines, keeping the first reason reported per file:
+    ///
+    /// - `error: patch failed: <file>:<line>`
+    /// - `error: <file>: <reason>`
+    /// - `Applied patch to '<file>' with conflicts.`
+    ///
+    /// Unrecognized lines are ignored.
+    pub(crate) fn parse_all<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<Self> {
+        let mut conflicts: Vec<Self> = Vec::new();
+        for line in lines {
+            let Some(conflict) = Self::parse_line(line.trim()) else {
+                continue;
+            };
+            if !conflicts
+                .iter()
+                .any(|existing| existing.file == conflict.file)
+            {
+                conflicts.push(conflict);
+            }
+        }
+        conflicts
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        if let Some(rest) = line.strip_prefix("error: patch failed: ") {
+            let (file, reason) = match rest.rsplit_once(':') {
+                Some((file, hunk_line)) if hunk_line.parse::<u64>().is_ok() => {
+                    (file, format!("patch failed at line {hunk_line}"))
+                }
+                _ => (rest, "patch failed".to_string()),
+            };
+            return Self::new(file, reason);
+        }
+        if let Some(rest) = line.strip_prefix("error: ") {
+            let (file, reason) = rest.split_once(": ")?;
+            return Self::new(file, reason);
+        }
+        if let Some(rest) = line.strip_prefix("Applied patch to '") {
+            let (file, _) = rest.split_once("' with conflicts")?;
+            return Self::new(file, "applied with conflicts");
+        }
+        None
+    }
+
+    fn new(file: &str, reason: impl Into<String>) -> Option<Self> {
+        let file = file.trim();
+        let reason = reason.into().trim().to_string();
+        if file.is_empty() || file.contains(char::is_whitespace) || reason.is_empty() {
+            return None;
+        }
+        Some(Self {
+            file: file.to_string(),
+            reason,
+        })
+    }
 }
 
 /// Request for `codex cloud diff [--attempt N] <TASK_ID>`.
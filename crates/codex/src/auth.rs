@@ -1,13 +1,30 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
-use tokio::process::Command;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process::Command,
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
 
 use crate::{
-    capabilities::{guard_is_supported, log_guard_skip},
+    ansi::strip_ansi,
+    capabilities::guard_is_supported,
     process::{preferred_output_channel, spawn_with_retry},
     CodexClient, CodexError,
 };
 
+/// Last `codex login status` result for [`CodexClient::login_status_cached`].
+#[derive(Debug, Default)]
+pub(crate) struct LoginStatusCache {
+    /// Bumped on every invalidation so in-flight checks can tell their result is stale.
+    generation: u64,
+    entry: Option<(Instant, CodexAuthStatus)>,
+}
+
 /// Current authentication state reported by `codex login status`.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum CodexAuthStatus {
@@ -37,6 +54,43 @@ pub enum CodexLogoutStatus {
     AlreadyLoggedOut,
 }
 
+/// Structured progress emitted by [`LoginSession::next_event`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LoginEvent {
+    /// URL the user should open to authorize the login.
+    VerificationUrl(String),
+    /// One-time code the user should enter on the verification page.
+    Code(String),
+    /// `codex login` exited successfully.
+    Completed,
+    /// `codex login` exited with an error or was cancelled.
+    Failed(String),
+}
+
+/// Running `codex login` session started by [`CodexClient::login_interactive`].
+///
+/// Output lines are parsed into [`LoginEvent`]s; the stream ends after a terminal
+/// `Completed`/`Failed` event. Dropping the session kills the login helper.
+#[derive(Debug)]
+pub struct LoginSession {
+    events: mpsc::UnboundedReceiver<LoginEvent>,
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+impl LoginSession {
+    /// Waits for the next login event; returns `None` once the session has finished.
+    pub async fn next_event(&mut self) -> Option<LoginEvent> {
+        self.events.recv().await
+    }
+
+    /// Kills the login helper. A final `LoginEvent::Failed` is still emitted.
+    pub fn cancel(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            let _ = cancel.send(());
+        }
+    }
+}
+
 /// Helper for checking Codex auth state and triggering login flows with an app-scoped `CODEX_HOME`.
 ///
 /// All commands run with per-process env overrides; the parent process env is never mutated.
@@ -123,6 +177,48 @@ impl CodexClient {
         spawn_with_retry(&mut command, self.command_env.binary_path())
     }
 
+    /// Starts the ChatGPT OAuth login flow and parses its output into [`LoginEvent`]s.
+    ///
+    /// Use [`CodexClient::spawn_login_process`] when you need the raw child instead.
+    pub fn login_interactive(&self) -> Result<LoginSession, CodexError> {
+        let mut child = self.spawn_login_process()?;
+        let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
+        let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
+
+        let (tx, events) = mpsc::unbounded_channel();
+        let (cancel, cancel_rx) = oneshot::channel();
+        let stdout_task = forward_login_output(stdout, tx.clone());
+        let stderr_task = forward_login_output(stderr, tx.clone());
+
+        tokio::spawn(async move {
+            // A dropped session also resolves `cancel_rx`, so abandoned logins are killed.
+            let status = tokio::select! {
+                status = child.wait() => Some(status),
+                _ = cancel_rx => None,
+            };
+            if status.is_none() {
+                let _ = child.kill().await;
+            }
+            let _ = stdout_task.await;
+            let last_stderr = stderr_task.await.ok().flatten();
+
+            let event = match status {
+                Some(Ok(status)) if status.success() => LoginEvent::Completed,
+                Some(Ok(status)) => LoginEvent::Failed(
+                    last_stderr.unwrap_or_else(|| format!("codex login exited with {status}")),
+                ),
+                Some(Err(err)) => LoginEvent::Failed(err.to_string()),
+                None => LoginEvent::Failed("login cancelled".to_string()),
+            };
+            let _ = tx.send(event);
+        });
+
+        Ok(LoginSession {
+            events,
+            cancel: Some(cancel),
+        })
+    }
+
     /// Spawns a `codex login --device-auth` session.
     ///
     /// The returned child inherits `kill_on_drop` so abandoning the handle cleans up the login helper.
@@ -167,7 +263,7 @@ impl CodexClient {
         let capabilities = self.probe_capabilities().await;
         let guard = capabilities.guard_mcp_login();
         if !guard_is_supported(&guard) {
-            log_guard_skip(&guard);
+            self.guard_skip.report(&guard);
             return Ok(None);
         }
 
@@ -202,6 +298,7 @@ impl CodexClient {
         let combined = preferred_output_channel(&output);
 
         if output.status.success() {
+            self.invalidate_login_status_cache();
             Ok(parse_login_success(&combined).unwrap_or_else(|| {
                 CodexAuthStatus::LoggedIn(CodexAuthMethod::Unknown {
                     raw: combined.clone(),
@@ -209,8 +306,11 @@ impl CodexClient {
             }))
         } else {
             Err(CodexError::NonZeroExit {
+                command: output.command,
+                exit_code: output.status.code(),
                 status: output.status,
                 stderr: combined,
+                truncated: false,
             })
         }
     }
@@ -230,12 +330,57 @@ impl CodexClient {
             Ok(CodexAuthStatus::LoggedOut)
         } else {
             Err(CodexError::NonZeroExit {
+                command: output.command,
+                exit_code: output.status.code(),
                 status: output.status,
                 stderr: combined,
+                truncated: false,
             })
         }
     }
 
+    /// Like [`CodexClient::login_status`], but reuses the last successful result while it is
+    /// younger than `ttl` instead of spawning `codex login status` again.
+    ///
+    /// The cache is shared by clones of this client and cleared by a successful
+    /// [`CodexClient::logout`] or [`CodexClient::login_with_api_key`]. A result is aged from when
+    /// its check started, and is not cached when it already exceeds `ttl` or when a login or
+    /// logout completed while it was in flight. Errors are never cached.
+    pub async fn login_status_cached(&self, ttl: Duration) -> Result<CodexAuthStatus, CodexError> {
+        let generation = {
+            let cache = self
+                .login_status_cache
+                .lock()
+                .unwrap_or_else(|err| err.into_inner());
+            if let Some((checked_at, status)) = cache.entry.as_ref() {
+                if checked_at.elapsed() < ttl {
+                    return Ok(status.clone());
+                }
+            }
+            cache.generation
+        };
+
+        let started = Instant::now();
+        let status = self.login_status().await?;
+        let mut cache = self
+            .login_status_cache
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        if cache.generation == generation && started.elapsed() < ttl {
+            cache.entry = Some((started, status.clone()));
+        }
+        Ok(status)
+    }
+
+    fn invalidate_login_status_cache(&self) {
+        let mut cache = self
+            .login_status_cache
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        cache.generation = cache.generation.wrapping_add(1);
+        cache.entry = None;
+    }
+
     /// Removes cached credentials via `codex logout`.
     pub async fn logout(&self) -> Result<CodexLogoutStatus, CodexError> {
         let output = self.run_basic_command(["logout"]).await?;
@@ -243,10 +388,14 @@ impl CodexClient {
 
         if !output.status.success() {
             return Err(CodexError::NonZeroExit {
+                command: output.command,
+                exit_code: output.status.code(),
                 status: output.status,
                 stderr: combined,
+                truncated: false,
             });
         }
+        self.invalidate_login_status_cache();
 
         let normalized = combined.to_lowercase();
         if normalized.contains("successfully logged out") {
@@ -259,6 +408,64 @@ impl CodexClient {
     }
 }
 
+/// Forwards parsed login events from one output stream; returns the last non-empty line.
+fn forward_login_output<R>(
+    reader: R,
+    tx: mpsc::UnboundedSender<LoginEvent>,
+) -> JoinHandle<Option<String>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        let mut last = None;
+        while let Ok(Some(line)) = lines.next_line().await {
+            let line = strip_ansi(&line);
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            for event in parse_login_line(trimmed) {
+                let _ = tx.send(event);
+            }
+            last = Some(trimmed.to_string());
+        }
+        last
+    })
+}
+
+pub(crate) fn parse_login_line(line: &str) -> Vec<LoginEvent> {
+    let urls: Vec<LoginEvent> = line
+        .split_whitespace()
+        .filter(|token| token.starts_with("https://") || token.starts_with("http://"))
+        .map(|token| token.trim_end_matches(['.', ',', ')', '>']))
+        .filter(|url| !is_local_callback(url))
+        .map(|url| LoginEvent::VerificationUrl(url.to_string()))
+        .collect();
+    if !urls.is_empty() {
+        return urls;
+    }
+
+    if line.to_lowercase().contains("code") {
+        if let Some((_, code)) = line.rsplit_once(':') {
+            let code = code.trim();
+            if !code.is_empty()
+                && !code.contains(char::is_whitespace)
+                && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            {
+                return vec![LoginEvent::Code(code.to_string())];
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// The OAuth flow also prints the loopback server it listens on; that is not for the user.
+fn is_local_callback(url: &str) -> bool {
+    let host = url.split("://").nth(1).unwrap_or_default();
+    host.starts_with("localhost") || host.starts_with("127.0.0.1")
+}
+
 pub(crate) fn parse_login_success(output: &str) -> Option<CodexAuthStatus> {
     let lower = output.to_lowercase();
     if lower.contains("chatgpt") {
@@ -0,0 +1,132 @@
+use std::{ffi::OsString, path::PathBuf, time::Duration};
+
+use thiserror::Error;
+
+use super::{ApprovalPolicy, CodexClientBuilder, SandboxMode};
+
+/// Model passed to `--model`.
+const CODEX_MODEL_ENV: &str = "CODEX_MODEL";
+/// Profile passed to `--profile`.
+const CODEX_PROFILE_ENV: &str = "CODEX_PROFILE";
+/// Whole seconds for [`CodexClientBuilder::timeout`]; `0` disables the timeout.
+const CODEX_TIMEOUT_SECS_ENV: &str = "CODEX_TIMEOUT_SECS";
+/// Whole seconds for [`CodexClientBuilder::idle_timeout`]; `0` disables idle timing.
+const CODEX_IDLE_TIMEOUT_SECS_ENV: &str = "CODEX_IDLE_TIMEOUT_SECS";
+/// Directory for [`CodexClientBuilder::working_dir`].
+const CODEX_WORKING_DIR_ENV: &str = "CODEX_WORKING_DIR";
+/// `untrusted`, `on-failure`, `on-request`, or `never`.
+const CODEX_APPROVAL_POLICY_ENV: &str = "CODEX_APPROVAL_POLICY";
+/// `read-only`, `workspace-write`, or `danger-full-access`.
+const CODEX_SANDBOX_MODE_ENV: &str = "CODEX_SANDBOX_MODE";
+
+/// Malformed environment variables rejected by [`CodexClientBuilder::from_env`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BuilderEnvError {
+    #[error("{var} is not valid UTF-8")]
+    NotUnicode { var: &'static str },
+    #[error("{var}=`{value}` is invalid: expected {expected}")]
+    InvalidValue {
+        var: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl CodexClientBuilder {
+    /// Seeds a builder from `CODEX_*` environment variables for 12-factor style configuration.
+    ///
+    /// Recognized variables (unset or empty values keep the builder defaults):
+    /// - `CODEX_BINARY`: binary path (already honored by [`Self::new`]).
+    /// - `CODEX_MODEL`: [`Self::model`].
+    /// - `CODEX_PROFILE`: [`Self::profile`].
+    /// - `CODEX_TIMEOUT_SECS`: [`Self::timeout`] in whole seconds; `0` disables it.
+    /// - `CODEX_IDLE_TIMEOUT_SECS`: [`Self::idle_timeout`] in whole seconds; `0` disables it.
+    /// - `CODEX_WORKING_DIR`: [`Self::working_dir`].
+    /// - `CODEX_APPROVAL_POLICY`: [`Self::approval_policy`] (`untrusted`, `on-failure`,
+    ///   `on-request`, `never`).
+    /// - `CODEX_SANDBOX_MODE`: [`Self::sandbox_mode`] (`read-only`, `workspace-write`,
+    ///   `danger-full-access`).
+    ///
+    /// `CODEX_HOME` is left to the CLI, which reads it directly. Malformed values return a
+    /// [`BuilderEnvError`] naming the variable; the returned builder can be chained as usual.
+    pub fn from_env() -> Result<Self, BuilderEnvError> {
+        Self::from_env_with(|name| std::env::var_os(name))
+    }
+
+    pub(crate) fn from_env_with(
+        lookup: impl Fn(&str) -> Option<OsString>,
+    ) -> Result<Self, BuilderEnvError> {
+        let read = |var: &'static str| -> Result<Option<String>, BuilderEnvError> {
+            match lookup(var) {
+                None => Ok(None),
+                Some(value) => {
+                    let value = value
+                        .into_string()
+                        .map_err(|_| BuilderEnvError::NotUnicode { var })?;
+                    let value = value.trim();
+                    Ok((!value.is_empty()).then(|| value.to_string()))
+                }
+            }
+        };
+
+        let mut builder = Self::default();
+        if let Some(model) = read(CODEX_MODEL_ENV)? {
+            builder = builder.model(model);
+        }
+        if let Some(profile) = read(CODEX_PROFILE_ENV)? {
+            builder = builder.profile(profile);
+        }
+        if let Some(value) = read(CODEX_TIMEOUT_SECS_ENV)? {
+            builder = builder.timeout(parse_secs(CODEX_TIMEOUT_SECS_ENV, value)?);
+        }
+        if let Some(value) = read(CODEX_IDLE_TIMEOUT_SECS_ENV)? {
+            builder = builder.idle_timeout(parse_secs(CODEX_IDLE_TIMEOUT_SECS_ENV, value)?);
+        }
+        if let Some(dir) = lookup(CODEX_WORKING_DIR_ENV).filter(|dir| !dir.is_empty()) {
+            builder = builder.working_dir(PathBuf::from(dir));
+        }
+        if let Some(value) = read(CODEX_APPROVAL_POLICY_ENV)? {
+            let policy = [
+                ApprovalPolicy::Untrusted,
+                ApprovalPolicy::OnFailure,
+                ApprovalPolicy::OnRequest,
+                ApprovalPolicy::Never,
+            ]
+            .into_iter()
+            .find(|policy| policy.as_str() == value)
+            .ok_or(BuilderEnvError::InvalidValue {
+                var: CODEX_APPROVAL_POLICY_ENV,
+                value,
+                expected: "untrusted, on-failure, on-request, or never",
+            })?;
+            builder = builder.approval_policy(policy);
+        }
+        if let Some(value) = read(CODEX_SANDBOX_MODE_ENV)? {
+            let mode = [
+                SandboxMode::ReadOnly,
+                SandboxMode::WorkspaceWrite,
+                SandboxMode::DangerFullAccess,
+            ]
+            .into_iter()
+            .find(|mode| mode.as_str() == value)
+            .ok_or(BuilderEnvError::InvalidValue {
+                var: CODEX_SANDBOX_MODE_ENV,
+                value,
+                expected: "read-only, workspace-write, or danger-full-access",
+            })?;
+            builder = builder.sandbox_mode(mode);
+        }
+        Ok(builder)
+    }
+}
+
+fn parse_secs(var: &'static str, value: String) -> Result<Duration, BuilderEnvError> {
+    value
+        .parse::<u64>()
+        .map(Duration::from_secs)
+        .map_err(|_| BuilderEnvError::InvalidValue {
+            var,
+            value,
+            expected: "a non-negative integer number of seconds",
+        })
+}
@@ -1,4 +1,7 @@
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 #[cfg(test)]
 use std::ffi::OsString;
@@ -7,13 +10,17 @@ use crate::home::CommandEnvironment;
 use tokio::process::Command;
 
 mod cli_overrides;
+mod env;
 mod types;
+mod validate;
 
+pub use env::BuilderEnvError;
 pub use types::{
-    ApprovalPolicy, CliOverrides, CliOverridesPatch, ColorMode, ConfigOverride, FeatureToggles,
-    FlagState, LocalProvider, ModelVerbosity, ReasoningEffort, ReasoningOverrides,
+    AddDir, ApprovalPolicy, CliOverrides, CliOverridesPatch, ColorMode, ConfigOverride, DirAccess,
+    FeatureToggles, FlagState, LocalProvider, ModelVerbosity, ReasoningEffort, ReasoningOverrides,
     ReasoningSummary, ReasoningSummaryFormat, SafetyOverride, SandboxMode,
 };
+pub use validate::BuilderError;
 
 pub(super) type ResolvedCliOverrides = cli_overrides::ResolvedCliOverrides;
 
@@ -68,19 +75,38 @@ pub struct CodexClientBuilder {
     pub(super) codex_home: Option<PathBuf>,
     pub(super) create_home_dirs: bool,
     pub(super) model: Option<String>,
+    pub(super) validate_model: bool,
     pub(super) timeout: Duration,
+    pub(super) graceful_shutdown: Option<Duration>,
+    pub(super) idle_timeout: Option<Duration>,
     pub(super) color_mode: ColorMode,
     pub(super) working_dir: Option<PathBuf>,
-    pub(super) add_dirs: Vec<PathBuf>,
+    pub(super) temp_dir_base: Option<PathBuf>,
+    pub(super) artifact_dir: Option<PathBuf>,
+    pub(super) keep_artifacts: bool,
+    pub(super) io_chunk_size: usize,
+    pub(super) max_output_bytes: Option<usize>,
+    pub(super) max_concurrent: Option<usize>,
+    pub(super) max_attachment_bytes: u64,
+    pub(super) serialize_console_output: bool,
+    pub(super) strip_ansi_errors: bool,
+    pub(super) auto_git_init: bool,
+    pub(super) prompt_trailing_newline: bool,
+    pub(super) add_dirs: Vec<AddDir>,
     pub(super) images: Vec<PathBuf>,
     pub(super) json_output: bool,
     pub(super) output_schema: bool,
     pub(super) quiet: bool,
     pub(super) mirror_stdout: bool,
     pub(super) json_event_log: Option<PathBuf>,
+    pub(super) json_event_log_compression: Option<crate::JsonLogCompression>,
     pub(super) cli_overrides: CliOverrides,
     pub(super) capability_overrides: crate::CapabilityOverrides,
     pub(super) capability_cache_policy: crate::CapabilityCachePolicy,
+    pub(super) capabilities_file: Option<PathBuf>,
+    pub(super) fingerprint_by_hash: bool,
+    pub(super) guard_skip: crate::capabilities::GuardSkipNotifier,
+    pub(super) metrics: crate::metrics::MetricsRecorder,
 }
 
 impl CodexClientBuilder {
@@ -120,12 +146,40 @@ impl CodexClientBuilder {
         self
     }
 
+    /// Checks [`Self::model`] against a curated list of known model families in
+    /// [`Self::try_build`], rejecting typos like `gpt5-codex` with
+    /// [`BuilderError::UnknownModel`]. Defaults to `false` because the list cannot keep up with
+    /// every release; leave it off when targeting new or private models.
+    pub fn validate_model(mut self, enable: bool) -> Self {
+        self.validate_model = enable;
+        self
+    }
+
     /// Overrides the maximum amount of time to wait for Codex to respond.
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
     }
 
+    /// Sends SIGTERM when [`CodexClientBuilder::timeout`] expires and waits up to `grace`
+    /// for Codex to exit (flushing transcripts and `conversations/` files) before force-killing.
+    ///
+    /// By default the child is killed immediately. On non-unix targets there is no SIGTERM,
+    /// so the child is still killed immediately. Dropping an in-flight stream also kills
+    /// immediately.
+    pub fn graceful_shutdown(mut self, grace: Duration) -> Self {
+        self.graceful_shutdown = Some(grace);
+        self
+    }
+
+    /// Sets the default idle timeout for streaming calls (`stream_exec`, `stream_resume`).
+    /// Applied when [`crate::ExecStreamRequest::idle_timeout`] is `None`; per-request values
+    /// still win. Use `Duration::ZERO` to disable idle timing.
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
     /// Controls whether Codex may emit ANSI colors (`--color`). Defaults to [`ColorMode::Never`].
     pub fn color_mode(mut self, color_mode: ColorMode) -> Self {
         self.color_mode = color_mode;
@@ -138,21 +192,136 @@ impl CodexClientBuilder {
         self
     }
 
+    /// Creates ephemeral working directories under `base` instead of the OS temp dir.
+    /// Ignored when [`CodexClientBuilder::working_dir`] is set.
+    pub fn temp_dir_base(mut self, base: impl Into<PathBuf>) -> Self {
+        self.temp_dir_base = Some(base.into());
+        self
+    }
+
+    /// Runs `git init -q` in each ephemeral working directory before `exec` so the run happens
+    /// inside a repository. Defaults to `false`. A configured
+    /// [`CodexClientBuilder::working_dir`] is never touched. Fails with
+    /// [`crate::CodexError::GitInit`] when `git` is missing or the init fails.
+    pub fn auto_git_init(mut self, enable: bool) -> Self {
+        self.auto_git_init = enable;
+        self
+    }
+
+    /// Creates wrapper-managed artifacts (the default `--output-last-message` file) under `dir`
+    /// instead of the OS temp dir, named `<prefix><pid>_<counter>` with a per-process counter.
+    /// The directory is created on first use.
+    pub fn artifact_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.artifact_dir = Some(dir.into());
+        self
+    }
+
+    /// Keeps wrapper-managed artifacts after a streaming run instead of deleting them once
+    /// their contents are read into [`crate::ExecCompletion::last_message`]. Defaults to `false`.
+    /// Paths supplied via [`crate::ExecStreamRequest::output_last_message`] are never removed.
+    pub fn keep_artifacts(mut self, keep: bool) -> Self {
+        self.keep_artifacts = keep;
+        self
+    }
+
+    /// Sets the read buffer size used when capturing stdout/stderr and JSONL events
+    /// (default 4 KiB, clamped to at least 1 KiB).
+    ///
+    /// Each capture task holds one buffer of this size for the lifetime of the child, so
+    /// larger values trade per-process memory for fewer reads on large outputs.
+    pub fn io_chunk_size(mut self, bytes: usize) -> Self {
+        self.io_chunk_size = bytes.max(crate::defaults::MIN_IO_CHUNK_SIZE);
+        self
+    }
+
+    /// Caps how many bytes are captured per stdout/stderr stream (unbounded by default).
+    ///
+    /// Output past the cap is discarded but the pipe keeps draining, so the child never blocks
+    /// and console mirroring continues. Captures are cut on a UTF-8 character boundary.
+    /// Truncation is reported via [`crate::ApplyDiffArtifacts::truncated`] and
+    /// [`crate::CodexError::NonZeroExit`]; a successful `codex exec` whose stdout was cut fails
+    /// with [`crate::CodexError::OutputTruncated`] rather than returning a partial response.
+    pub fn max_output_bytes(mut self, limit: Option<usize>) -> Self {
+        self.max_output_bytes = limit;
+        self
+    }
+
+    /// Caps how many `codex exec`, streaming, resume, `apply`, and `diff` subprocesses the built
+    /// client runs at once (unlimited by default; `0` also means unlimited).
+    ///
+    /// Calls past the limit wait for a free slot before spawning; streams hold theirs until the
+    /// completion future resolves or is dropped. Clones of the client share the limit, but each
+    /// [`Self::build`] starts a fresh one.
+    pub fn max_concurrent(mut self, limit: usize) -> Self {
+        self.max_concurrent = (limit > 0).then_some(limit);
+        self
+    }
+
+    /// Caps the size of each [`crate::Attachment::TextFile`] passed to
+    /// [`crate::CodexClient::prompt_with_attachments`] (default 256 KiB). Larger files fail with
+    /// [`crate::CodexError::AttachmentTooLarge`] before Codex is spawned.
+    pub fn max_attachment_bytes(mut self, limit: u64) -> Self {
+        self.max_attachment_bytes = limit;
+        self
+    }
+
+    /// Routes mirrored stdout and stderr through one process-wide lock and writes only whole
+    /// lines, so front-ends that show both streams do not see torn, interleaved output.
+    /// Defaults to `false`; enabling it adds lock contention and holds back partial lines
+    /// until their newline (or EOF) arrives.
+    pub fn serialize_console_output(mut self, enable: bool) -> Self {
+        self.serialize_console_output = enable;
+        self
+    }
+
+    /// Strips ANSI escape sequences from the stderr stored in [`crate::CodexError::NonZeroExit`]
+    /// (including streamed runs' [`crate::ExecStreamError::Codex`]) so error logs stay plain
+    /// text. Defaults to `false`; the CLI already runs with `--color never`, but tools it spawns
+    /// may still color their output. Mirrored console output is left untouched.
+    pub fn strip_ansi_errors(mut self, enable: bool) -> Self {
+        self.strip_ansi_errors = enable;
+        self
+    }
+
+    /// Appends a `\n` after prompts written to stdin (default `true`). Prompts that already end
+    /// in a newline are sent unchanged, so multi-line prompts never arrive with a doubled
+    /// terminator; disable to pass the prompt through byte for byte.
+    pub fn prompt_trailing_newline(mut self, enable: bool) -> Self {
+        self.prompt_trailing_newline = enable;
+        self
+    }
+
     /// Requests that `codex exec` include one or more `--add-dir` flags when the
     /// probed binary supports them. Unsupported or unknown capability results
-    /// skip the flag to avoid CLI errors.
+    /// skip the flag to avoid CLI errors. Directories are writable; see
+    /// [`Self::add_dir_with_access`] for read-only access.
     pub fn add_dir(mut self, path: impl Into<PathBuf>) -> Self {
-        self.add_dirs.push(path.into());
+        self.add_dirs.push(AddDir::new(path, DirAccess::ReadWrite));
         self
     }
 
-    /// Replaces the current add-dir list with the provided collection.
+    /// Adds an extra directory with explicit [`DirAccess`].
+    ///
+    /// A [`DirAccess::ReadOnly`] entry is passed as `--add-dir-read-only` when
+    /// [`crate::CapabilityFeature::AddDirReadOnly`] is confirmed. Otherwise `exec`, streaming,
+    /// and resume calls fail with [`crate::CodexError::UnsupportedCapability`]; this
+    /// deliberately departs from falling back to a plain `--add-dir`, which would silently
+    /// grant write access.
+    pub fn add_dir_with_access(mut self, path: impl Into<PathBuf>, access: DirAccess) -> Self {
+        self.add_dirs.push(AddDir::new(path, access));
+        self
+    }
+
+    /// Replaces the current add-dir list with the provided (writable) collection.
     pub fn add_dirs<I, P>(mut self, dirs: I) -> Self
     where
         I: IntoIterator<Item = P>,
         P: Into<PathBuf>,
     {
-        self.add_dirs = dirs.into_iter().map(Into::into).collect();
+        self.add_dirs = dirs
+            .into_iter()
+            .map(|dir| AddDir::from(dir.into()))
+            .collect();
         self
     }
 
@@ -178,6 +347,7 @@ impl CodexClientBuilder {
     /// (or `thread.resumed` when continuing), `turn.started`/`turn.completed`/`turn.failed`,
     /// and `item.created`/`item.updated` with `item.type` such as `agent_message` or `reasoning`.
     /// Pair with `.mirror_stdout(false)` if you plan to parse the stream instead of just mirroring it.
+    /// See [`crate::CodexClient::send_prompt`] for how binaries without JSONL support are reported.
     pub fn json(mut self, enable: bool) -> Self {
         self.json_output = enable;
         self
@@ -213,6 +383,14 @@ impl CodexClientBuilder {
         self
     }
 
+    /// Sets the compression used for JSON event logs. When unset, logs whose path ends in `.gz`
+    /// are gzip-compressed and everything else is written as plain JSONL. See
+    /// [`crate::JsonLogCompression`] for the durability tradeoff of compressed logs.
+    pub fn json_event_log_compression(mut self, compression: crate::JsonLogCompression) -> Self {
+        self.json_event_log_compression = Some(compression);
+        self
+    }
+
     /// Adds a `--config key=value` override that will be applied to every Codex invocation.
     pub fn config_override(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.cli_overrides
@@ -298,7 +476,10 @@ impl CodexClientBuilder {
         self
     }
 
-    /// Applies the `--full-auto` safety override unless explicit sandbox/approval options are set.
+    /// Applies the `--full-auto` safety override.
+    ///
+    /// [`Self::try_build`] rejects combining this with [`Self::sandbox_mode`] or
+    /// [`Self::approval_policy`]; per-request sandbox/approval overrides still take precedence.
     pub fn full_auto(mut self, enable: bool) -> Self {
         self.cli_overrides.safety_override = if enable {
             SafetyOverride::FullAuto
@@ -391,6 +572,18 @@ impl CodexClientBuilder {
         self
     }
 
+    /// Loads a capability snapshot shipped next to the binary when the client is built.
+    ///
+    /// The snapshot (read via [`crate::read_capabilities_snapshot`], format inferred from the
+    /// extension) replaces any [`Self::capability_snapshot`] only when
+    /// [`crate::capability_snapshot_matches_binary`] confirms it was captured for the
+    /// configured binary. A stale or unreadable file is logged and ignored, so upgrades fall
+    /// back to live probes instead of reusing outdated capabilities.
+    pub fn capabilities_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.capabilities_file = Some(path.into());
+        self
+    }
+
     /// Overrides the probed version data with caller-provided metadata.
     pub fn capability_version_override(mut self, version: crate::CodexVersionInfo) -> Self {
         self.capability_overrides.version = Some(version);
@@ -416,16 +609,85 @@ impl CodexClientBuilder {
         self
     }
 
+    /// Adds a SHA-256 of the binary contents to capability fingerprints.
+    ///
+    /// Modification time and length collide when a binary is rebuilt to the same size with a
+    /// preserved mtime (reproducible builds, `touch -r`); hashing catches those rebuilds so
+    /// cached capabilities are not reused. Digests are memoized per binary until its metadata
+    /// changes, so repeated probes do not reread the file. Defaults to `false`.
+    pub fn fingerprint_by_hash(mut self, enable: bool) -> Self {
+        self.fingerprint_by_hash = enable;
+        self
+    }
+
+    /// Registers a callback invoked whenever a capability guard drops a requested flag
+    /// (`--output-schema`, `--add-dir`, `login --mcp`) because support was not confirmed.
+    ///
+    /// The existing `warn!` log is still emitted; the hook lets UIs tell users why an option
+    /// had no effect.
+    pub fn on_guard_skip(
+        mut self,
+        hook: impl Fn(&crate::CapabilityGuard) + Send + Sync + 'static,
+    ) -> Self {
+        self.guard_skip = crate::capabilities::GuardSkipNotifier(Some(std::sync::Arc::new(hook)));
+        self
+    }
+
+    /// Registers a [`crate::Metrics`] sink for exec latency, token usage, and error counts.
+    ///
+    /// The client reports each `codex exec` run (buffered or streamed) once the process exits,
+    /// token usage from every `turn.completed` event, and the category of failed runs. Defaults
+    /// to [`crate::NoopMetrics`].
+    pub fn metrics(mut self, metrics: std::sync::Arc<dyn crate::Metrics + Send + Sync>) -> Self {
+        self.metrics = crate::metrics::MetricsRecorder(metrics);
+        self
+    }
+
     /// Builds the [`crate::CodexClient`].
+    ///
+    /// # Panics
+    ///
+    /// Panics when the configuration is rejected by [`Self::try_build`]; prefer that method when
+    /// builder inputs come from user configuration.
     pub fn build(self) -> crate::CodexClient {
+        self.try_build()
+            .expect("invalid CodexClientBuilder configuration")
+    }
+
+    /// Validates the configuration and builds the [`crate::CodexClient`].
+    ///
+    /// Rejects an empty binary path, `full_auto` combined with an explicit sandbox mode or
+    /// approval policy, a relative `cd` without a `working_dir` to anchor it, features that are
+    /// both enabled and disabled, and (with [`Self::validate_model`]) unrecognized models.
+    pub fn try_build(self) -> Result<crate::CodexClient, BuilderError> {
+        validate::validate(&self)?;
+        let mut capability_overrides = self.capability_overrides;
+        if let Some(path) = &self.capabilities_file {
+            load_capabilities_file(&mut capability_overrides, path, &self.binary);
+        }
         let command_env =
             CommandEnvironment::new(self.binary, self.codex_home, self.create_home_dirs);
-        crate::CodexClient {
+        Ok(crate::CodexClient {
             command_env,
             model: self.model,
             timeout: self.timeout,
+            graceful_shutdown: self.graceful_shutdown,
+            idle_timeout: self.idle_timeout,
             color_mode: self.color_mode,
             working_dir: self.working_dir,
+            temp_dir_base: self.temp_dir_base,
+            artifact_dir: self.artifact_dir,
+            keep_artifacts: self.keep_artifacts,
+            io_chunk_size: self.io_chunk_size,
+            max_output_bytes: self.max_output_bytes,
+            process_slots: self
+                .max_concurrent
+                .map(|limit| std::sync::Arc::new(tokio::sync::Semaphore::new(limit))),
+            max_attachment_bytes: self.max_attachment_bytes,
+            serialize_console_output: self.serialize_console_output,
+            strip_ansi_errors: self.strip_ansi_errors,
+            auto_git_init: self.auto_git_init,
+            prompt_trailing_newline: self.prompt_trailing_newline,
             add_dirs: self.add_dirs,
             images: self.images,
             json_output: self.json_output,
@@ -433,10 +695,36 @@ impl CodexClientBuilder {
             quiet: self.quiet,
             mirror_stdout: self.mirror_stdout,
             json_event_log: self.json_event_log,
+            json_event_log_compression: self.json_event_log_compression,
             cli_overrides: self.cli_overrides,
-            capability_overrides: self.capability_overrides,
+            capability_overrides,
             capability_cache_policy: self.capability_cache_policy,
+            fingerprint_by_hash: self.fingerprint_by_hash,
+            loaded_capability_snapshot: Default::default(),
+            login_status_cache: Default::default(),
+            guard_skip: self.guard_skip,
+            metrics: self.metrics,
+        })
+    }
+}
+
+/// Installs the snapshot at `path` into `overrides` when it still describes `binary`.
+fn load_capabilities_file(overrides: &mut crate::CapabilityOverrides, path: &Path, binary: &Path) {
+    match crate::read_capabilities_snapshot(path, None) {
+        Ok(snapshot) if crate::capability_snapshot_matches_binary(&snapshot, binary) => {
+            tracing::info!(path = ?path, "using capabilities file instead of probing");
+            overrides.snapshot = Some(snapshot);
         }
+        Ok(_) => tracing::info!(
+            path = ?path,
+            binary = ?binary,
+            "capabilities file does not match the binary fingerprint; probing instead"
+        ),
+        Err(error) => tracing::warn!(
+            ?error,
+            path = ?path,
+            "failed to read capabilities file; probing instead"
+        ),
     }
 }
 
@@ -447,9 +735,23 @@ impl Default for CodexClientBuilder {
             codex_home: None,
             create_home_dirs: true,
             model: None,
+            validate_model: false,
             timeout: crate::defaults::DEFAULT_TIMEOUT,
+            graceful_shutdown: None,
+            idle_timeout: None,
             color_mode: ColorMode::Never,
             working_dir: None,
+            temp_dir_base: None,
+            artifact_dir: None,
+            keep_artifacts: false,
+            io_chunk_size: crate::defaults::DEFAULT_IO_CHUNK_SIZE,
+            max_output_bytes: None,
+            max_concurrent: None,
+            max_attachment_bytes: crate::defaults::DEFAULT_MAX_ATTACHMENT_BYTES,
+            serialize_console_output: false,
+            strip_ansi_errors: false,
+            auto_git_init: false,
+            prompt_trailing_newline: true,
             add_dirs: Vec::new(),
             images: Vec::new(),
             json_output: false,
@@ -457,9 +759,14 @@ impl Default for CodexClientBuilder {
             quiet: false,
             mirror_stdout: true,
             json_event_log: None,
+            json_event_log_compression: None,
             cli_overrides: CliOverrides::default(),
             capability_overrides: crate::CapabilityOverrides::default(),
             capability_cache_policy: crate::CapabilityCachePolicy::default(),
+            capabilities_file: None,
+            fingerprint_by_hash: false,
+            guard_skip: Default::default(),
+            metrics: Default::default(),
         }
     }
 }
@@ -94,6 +94,40 @@ impl LocalProvider {
     }
 }
 
+/// Access granted to an extra directory passed via `--add-dir`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DirAccess {
+    /// Codex may only read the directory (`--add-dir-read-only`). Runs fail with
+    /// [`crate::CodexError::UnsupportedCapability`] when the probed binary does not declare
+    /// that flag, instead of granting write access.
+    ReadOnly,
+    /// Codex may read and write the directory (`--add-dir`).
+    #[default]
+    ReadWrite,
+}
+
+/// Extra directory exposed to `codex exec` alongside the working directory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddDir {
+    pub path: PathBuf,
+    pub access: DirAccess,
+}
+
+impl AddDir {
+    pub fn new(path: impl Into<PathBuf>, access: DirAccess) -> Self {
+        Self {
+            path: path.into(),
+            access,
+        }
+    }
+}
+
+impl From<PathBuf> for AddDir {
+    fn from(path: PathBuf) -> Self {
+        Self::new(path, DirAccess::ReadWrite)
+    }
+}
+
 /// Three-state flag used when requests can override builder defaults.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub enum FlagState {
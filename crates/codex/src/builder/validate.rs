@@ -0,0 +1,150 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use super::{CodexClientBuilder, SafetyOverride};
+
+/// Misconfigurations rejected by [`CodexClientBuilder::try_build`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BuilderError {
+    #[error("codex binary path is empty")]
+    EmptyBinary,
+    #[error("full_auto cannot be combined with an explicit {field}")]
+    FullAutoConflict { field: &'static str },
+    #[error(
+        "relative cd `{cd}` requires working_dir; Codex would otherwise resolve it inside a temporary directory"
+    )]
+    RelativeCdWithoutWorkingDir { cd: PathBuf },
+    #[error("feature `{name}` is both enabled and disabled")]
+    ConflictingFeatureToggle { name: String },
+    #[error("unknown model `{model}`{}", suggestion_hint(suggestions))]
+    UnknownModel {
+        model: String,
+        /// Known models closest to `model`, best match first; empty when nothing is close.
+        suggestions: Vec<String>,
+    },
+}
+
+/// Model families accepted by [`CodexClientBuilder::validate_model`]. A model matches when it
+/// equals a family or extends it after a `-` or `.` (`gpt-5-mini`, `gpt-5.1-codex-max`).
+const KNOWN_MODEL_FAMILIES: &[&str] = &[
+    "gpt-5",
+    "gpt-5.1",
+    "gpt-4.1",
+    "gpt-4o",
+    "o1",
+    "o3",
+    "o4-mini",
+    "codex-mini",
+];
+
+/// Concrete model names offered as typo suggestions.
+const SUGGESTED_MODELS: &[&str] = &[
+    "gpt-5",
+    "gpt-5-codex",
+    "gpt-5-mini",
+    "gpt-5.1",
+    "gpt-5.1-codex",
+    "gpt-5.1-codex-mini",
+    "gpt-5.1-codex-max",
+    "gpt-4.1",
+    "gpt-4o",
+    "o3",
+    "o4-mini",
+    "codex-mini-latest",
+];
+
+pub(super) fn validate(builder: &CodexClientBuilder) -> Result<(), BuilderError> {
+    if builder.binary.as_os_str().is_empty() {
+        return Err(BuilderError::EmptyBinary);
+    }
+
+    let overrides = &builder.cli_overrides;
+    if overrides.safety_override == SafetyOverride::FullAuto {
+        if overrides.sandbox_mode.is_some() {
+            return Err(BuilderError::FullAutoConflict {
+                field: "sandbox_mode",
+            });
+        }
+        if overrides.approval_policy.is_some() {
+            return Err(BuilderError::FullAutoConflict {
+                field: "approval_policy",
+            });
+        }
+    }
+
+    if let Some(cd) = overrides.cd.as_ref() {
+        if cd.is_relative() && builder.working_dir.is_none() {
+            return Err(BuilderError::RelativeCdWithoutWorkingDir { cd: cd.clone() });
+        }
+    }
+
+    let toggles = &overrides.feature_toggles;
+    if let Some(name) = toggles
+        .enable
+        .iter()
+        .find(|name| toggles.disable.contains(name))
+    {
+        return Err(BuilderError::ConflictingFeatureToggle { name: name.clone() });
+    }
+
+    if builder.validate_model {
+        if let Some(model) = builder.model.as_deref() {
+            if !is_known_model(model) {
+                return Err(BuilderError::UnknownModel {
+                    model: model.to_string(),
+                    suggestions: suggest_models(model),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn is_known_model(model: &str) -> bool {
+    KNOWN_MODEL_FAMILIES.iter().any(|family| {
+        model
+            .strip_prefix(family)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '.']))
+    })
+}
+
+/// Up to three [`SUGGESTED_MODELS`] within a small edit distance of `model`, closest first.
+fn suggest_models(model: &str) -> Vec<String> {
+    let model = model.trim().to_ascii_lowercase();
+    let max_distance = (model.chars().count() / 3).max(2);
+    let mut candidates: Vec<(usize, &str)> = SUGGESTED_MODELS
+        .iter()
+        .map(|known| (edit_distance(&model, known), *known))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort_by_key(|(distance, _)| *distance);
+    candidates
+        .into_iter()
+        .take(3)
+        .map(|(_, known)| known.to_string())
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn suggestion_hint(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!("; did you mean {}?", suggestions.join(", "))
+    }
+}
@@ -1,9 +1,11 @@
+use futures_util::future::{BoxFuture, Shared};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs as std_fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 use std::time::SystemTime;
+use tokio::sync::Mutex as AsyncMutex;
 
 use super::{
     CapabilityFeatureOverrides, CapabilityOverrides, CapabilityProbeStep, CodexCapabilities,
@@ -21,6 +23,13 @@ pub enum CapabilityCachePolicy {
     Refresh,
     /// Skip cache reads and writes to force an isolated snapshot.
     Bypass,
+    /// Never spawn the binary: use the override snapshot when one is configured, otherwise a
+    /// conservative snapshot with no version and every feature unsupported. The cache is
+    /// neither read nor written; the result records [`CapabilityProbeStep::ManualOverride`].
+    ///
+    /// Meant for air-gapped hosts. Without a snapshot, guards report `Unknown` or
+    /// `Unsupported` and optional flags are skipped.
+    OfflineSnapshotOnly,
 }
 
 /// Cache key for capability snapshots derived from a specific Codex binary path.
@@ -42,6 +51,11 @@ pub struct BinaryFingerprint {
     pub modified: Option<SystemTime>,
     /// File length from `metadata().len()`, useful for cheap change detection.
     pub len: Option<u64>,
+    /// Hex-encoded SHA-256 of the binary contents when hashing is enabled via
+    /// [`crate::CodexClientBuilder::fingerprint_by_hash`]; catches same-size rebuilds that
+    /// preserve the modification time.
+    #[serde(default)]
+    pub sha256: Option<String>,
 }
 
 pub(crate) fn capability_cache() -> &'static Mutex<HashMap<CapabilityCacheKey, CodexCapabilities>> {
@@ -49,6 +63,17 @@ pub(crate) fn capability_cache() -> &'static Mutex<HashMap<CapabilityCacheKey, C
     CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+/// Probe future shared between concurrent callers targeting the same binary.
+pub(crate) type InFlightProbe = Shared<BoxFuture<'static, CodexCapabilities>>;
+
+/// In-progress probes keyed by binary so concurrent cold probes collapse into one.
+pub(crate) fn in_flight_probes() -> &'static AsyncMutex<HashMap<CapabilityCacheKey, InFlightProbe>>
+{
+    static IN_FLIGHT: OnceLock<AsyncMutex<HashMap<CapabilityCacheKey, InFlightProbe>>> =
+        OnceLock::new();
+    IN_FLIGHT.get_or_init(|| AsyncMutex::new(HashMap::new()))
+}
+
 pub(crate) fn capability_cache_key(binary: &Path) -> CapabilityCacheKey {
     let canonical = std_fs::canonicalize(binary).unwrap_or_else(|_| binary.to_path_buf());
     CapabilityCacheKey {
@@ -119,22 +144,113 @@ pub fn clear_capability_cache() {
     }
 }
 
-pub(crate) fn current_fingerprint(key: &CapabilityCacheKey) -> Option<BinaryFingerprint> {
+pub(crate) fn current_fingerprint(
+    key: &CapabilityCacheKey,
+    hash_contents: bool,
+) -> Option<BinaryFingerprint> {
     let canonical = std_fs::canonicalize(&key.binary_path).ok();
     let metadata_path = canonical.as_deref().unwrap_or(key.binary_path.as_path());
     let metadata = std_fs::metadata(metadata_path).ok()?;
+    let sha256 = if hash_contents {
+        binary_sha256(metadata_path, &metadata)
+    } else {
+        None
+    };
     Some(BinaryFingerprint {
         canonical_path: canonical,
         modified: metadata.modified().ok(),
         len: Some(metadata.len()),
+        sha256,
     })
 }
 
+/// Metadata a cached digest was computed against.
+///
+/// Includes the inode change time on Unix, which `touch -r` and same-size rewrites cannot
+/// preserve, so a memoized hash is never reused for modified contents. Elsewhere the stamp
+/// relies on path, modification time, and length.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct HashStamp {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    len: u64,
+    changed: Option<(u64, i64, i64)>,
+}
+
+impl HashStamp {
+    fn new(path: &Path, metadata: &std_fs::Metadata) -> Self {
+        #[cfg(unix)]
+        let changed = {
+            use std::os::unix::fs::MetadataExt;
+            Some((metadata.ino(), metadata.ctime(), metadata.ctime_nsec()))
+        };
+        #[cfg(not(unix))]
+        let changed = None;
+        Self {
+            path: path.to_path_buf(),
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+            changed,
+        }
+    }
+}
+
+fn binary_hash_cache() -> &'static Mutex<HashMap<PathBuf, (HashStamp, String)>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, (HashStamp, String)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Hashes the binary, reusing the previous digest while its metadata stamp is unchanged.
+fn binary_sha256(path: &Path, metadata: &std_fs::Metadata) -> Option<String> {
+    use sha2::{Digest, Sha256};
+
+    let stamp = HashStamp::new(path, metadata);
+    let memoize = stamp.modified.is_some();
+    if memoize {
+        if let Ok(cache) = binary_hash_cache().lock() {
+            if let Some((cached_stamp, digest)) = cache.get(path) {
+                if *cached_stamp == stamp {
+                    return Some(digest.clone());
+                }
+            }
+        }
+    }
+
+    let mut file = std_fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    if memoize {
+        if let Ok(mut cache) = binary_hash_cache().lock() {
+            cache.insert(path.to_path_buf(), (stamp, digest.clone()));
+        }
+    }
+    Some(digest)
+}
+
+/// Compares path/mtime/length, plus content hashes when both fingerprints carry one.
 pub(crate) fn fingerprints_match(
     cached: &Option<BinaryFingerprint>,
     fresh: &Option<BinaryFingerprint>,
 ) -> bool {
-    cached == fresh
+    match (cached, fresh) {
+        (Some(cached), Some(fresh)) => {
+            cached.canonical_path == fresh.canonical_path
+                && cached.modified == fresh.modified
+                && cached.len == fresh.len
+                && match (&cached.sha256, &fresh.sha256) {
+                    (Some(cached), Some(fresh)) => cached == fresh,
+                    _ => true,
+                }
+        }
+        (None, None) => true,
+        _ => false,
+    }
 }
 
 pub(crate) fn finalize_capabilities_with_overrides(
@@ -199,5 +315,15 @@ fn apply_feature_overrides(
         applied = true;
     }
 
+    if let Some(value) = overrides.supports_json_output {
+        features.supports_json_output = value;
+        applied = true;
+    }
+
+    if let Some(value) = overrides.supports_add_dir_read_only {
+        features.supports_add_dir_read_only = value;
+        applied = true;
+    }
+
     applied
 }
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    CapabilityFeature, CodexCapabilities, CodexLatestReleases, CodexReleaseChannel,
+    CodexUpdateAdvisory,
+};
+use crate::version::{codex_release_from_info, update_advisory_from_capabilities};
+
+/// Differences between two capability snapshots, typically taken before and after a binary
+/// upgrade.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityDiff {
+    /// Version comparison that treats the newer snapshot as the latest known release:
+    /// `UpdateRecommended` means the version went up, `LocalNewerThanKnown` means it went down.
+    pub version: CodexUpdateAdvisory,
+    /// Features supported by the newer snapshot but not the older one.
+    pub gained: Vec<CapabilityFeature>,
+    /// Features supported by the older snapshot but not the newer one.
+    pub lost: Vec<CapabilityFeature>,
+}
+
+impl CapabilityDiff {
+    /// True when no feature flipped in either direction.
+    pub fn features_unchanged(&self) -> bool {
+        self.gained.is_empty() && self.lost.is_empty()
+    }
+}
+
+impl CodexCapabilities {
+    /// Compares this snapshot against `other` (the snapshot taken after an upgrade).
+    ///
+    /// Pure function: no probes run. The version delta reuses
+    /// [`update_advisory_from_capabilities`] with `other`'s release as the latest table, so
+    /// missing or unparsable versions surface as the usual `Unknown*` statuses.
+    pub fn diff(&self, other: &CodexCapabilities) -> CapabilityDiff {
+        let mut latest = CodexLatestReleases::default();
+        if let Some(release) = other.version.as_ref().and_then(codex_release_from_info) {
+            let slot = match release.channel {
+                CodexReleaseChannel::Stable | CodexReleaseChannel::Custom => &mut latest.stable,
+                CodexReleaseChannel::Beta => &mut latest.beta,
+                CodexReleaseChannel::Nightly => &mut latest.nightly,
+                CodexReleaseChannel::ReleaseCandidate => &mut latest.release_candidate,
+                CodexReleaseChannel::Alpha => &mut latest.alpha,
+            };
+            *slot = Some(release.version);
+        }
+
+        let gained = CapabilityFeature::ALL
+            .into_iter()
+            .filter(|feature| {
+                !feature.is_enabled_in(&self.features) && feature.is_enabled_in(&other.features)
+            })
+            .collect();
+        let lost = CapabilityFeature::ALL
+            .into_iter()
+            .filter(|feature| {
+                feature.is_enabled_in(&self.features) && !feature.is_enabled_in(&other.features)
+            })
+            .collect();
+
+        CapabilityDiff {
+            version: update_advisory_from_capabilities(self, &latest),
+            gained,
+            lost,
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, SystemTime};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 use tracing::warn;
 
 use super::{CapabilityCachePolicy, CodexCapabilities, CodexFeatureFlags, CodexVersionInfo};
@@ -37,12 +41,7 @@ pub fn capability_cache_ttl_decision(
         };
     };
 
-    let expired = now
-        .duration_since(snapshot.collected_at)
-        .map(|elapsed| elapsed >= ttl)
-        .unwrap_or(true);
-
-    if !expired {
+    if !snapshot.is_stale(ttl, now) {
         return CapabilityTtlDecision {
             should_probe: false,
             policy: default_policy,
@@ -82,21 +81,47 @@ impl CapabilitySupport {
 }
 
 /// Feature/flag tokens that can be guarded based on probed capabilities.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum CapabilityFeature {
     OutputSchema,
     AddDir,
     McpLogin,
     FeaturesList,
+    JsonOutput,
+    AddDirReadOnly,
 }
 
 impl CapabilityFeature {
-    fn label(self) -> &'static str {
+    /// Every feature tracked by [`CodexFeatureFlags`], in field order.
+    pub const ALL: [CapabilityFeature; 6] = [
+        CapabilityFeature::FeaturesList,
+        CapabilityFeature::OutputSchema,
+        CapabilityFeature::AddDir,
+        CapabilityFeature::McpLogin,
+        CapabilityFeature::JsonOutput,
+        CapabilityFeature::AddDirReadOnly,
+    ];
+
+    /// CLI surface gated by the feature (e.g. `--output-schema`), suitable for user-facing text.
+    pub fn label(self) -> &'static str {
         match self {
             CapabilityFeature::OutputSchema => "--output-schema",
             CapabilityFeature::AddDir => "codex add-dir",
             CapabilityFeature::McpLogin => "codex login --mcp",
             CapabilityFeature::FeaturesList => "codex features list",
+            CapabilityFeature::JsonOutput => "codex exec --json",
+            CapabilityFeature::AddDirReadOnly => "--add-dir-read-only",
+        }
+    }
+
+    pub(crate) fn is_enabled_in(self, flags: &CodexFeatureFlags) -> bool {
+        match self {
+            CapabilityFeature::OutputSchema => flags.supports_output_schema,
+            CapabilityFeature::AddDir => flags.supports_add_dir,
+            CapabilityFeature::McpLogin => flags.supports_mcp_login,
+            CapabilityFeature::FeaturesList => flags.supports_features_list,
+            CapabilityFeature::JsonOutput => flags.supports_json_output,
+            CapabilityFeature::AddDirReadOnly => flags.supports_add_dir_read_only,
         }
     }
 }
@@ -170,11 +195,78 @@ pub enum CapabilityProbeStep {
     FeaturesListText,
     /// Parse `codex --help` to spot known flags (e.g., `--output-schema`, `add-dir`, `login --mcp`) when the features list is missing.
     HelpFallback,
+    /// Parse the `codex exec --help` options table for exec-only flags such as `--json`.
+    ExecHelp,
+    /// List the subcommands in `codex app-server --help` (codegen targets).
+    AppServerHelp,
     /// Caller-supplied capability overrides were applied to the snapshot.
     ManualOverride,
 }
 
+impl CapabilityProbeStep {
+    /// One-line, past-tense description of the step for logs and support bundles.
+    pub fn describe(self) -> &'static str {
+        match self {
+            CapabilityProbeStep::VersionFlag => "Ran `codex --version`",
+            CapabilityProbeStep::FeaturesListJson => "Parsed `features list --json`",
+            CapabilityProbeStep::FeaturesListText => "Parsed `features list`",
+            CapabilityProbeStep::HelpFallback => "Scanned `codex --help` for known flags",
+            CapabilityProbeStep::ExecHelp => "Scanned `codex exec --help` options",
+            CapabilityProbeStep::AppServerHelp => "Listed `codex app-server --help` subcommands",
+            CapabilityProbeStep::ManualOverride => "Applied manual overrides",
+        }
+    }
+}
+
+impl CapabilityProbePlan {
+    /// Human-readable explanation of the recorded steps, one line per step in probe order.
+    pub fn explain(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .map(|step| step.describe().to_string())
+            .collect()
+    }
+}
+
 impl CodexCapabilities {
+    /// Single-line summary of the snapshot (version, channel, detected features, and the probe
+    /// explanation) for log lines and support bundles.
+    ///
+    /// ```text
+    /// codex 0.61.0 (stable); features: codex features list, --output-schema; probes: Ran `codex --version`, Parsed `features list --json`
+    /// ```
+    pub fn summary(&self) -> String {
+        let version = match &self.version {
+            Some(CodexVersionInfo {
+                semantic: Some((major, minor, patch)),
+                channel,
+                ..
+            }) => format!("codex {major}.{minor}.{patch} ({channel})"),
+            Some(version) => format!("codex `{}` ({})", version.raw.trim(), version.channel),
+            None => "codex (version unknown)".to_string(),
+        };
+
+        let features: Vec<&str> = CapabilityFeature::ALL
+            .into_iter()
+            .filter(|feature| feature.is_enabled_in(&self.features))
+            .map(CapabilityFeature::label)
+            .collect();
+        let features = if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        };
+
+        let probes = self.probe_plan.explain();
+        let probes = if probes.is_empty() {
+            "none".to_string()
+        } else {
+            probes.join(", ")
+        };
+
+        format!("{version}; features: {features}; probes: {probes}")
+    }
+
     /// Guards whether `--output-schema` should be passed to `codex exec`.
     pub fn guard_output_schema(&self) -> CapabilityGuard {
         self.guard_feature(CapabilityFeature::OutputSchema)
@@ -185,6 +277,11 @@ impl CodexCapabilities {
         self.guard_feature(CapabilityFeature::AddDir)
     }
 
+    /// Guards whether `codex exec` accepts `--add-dir-read-only`.
+    pub fn guard_add_dir_read_only(&self) -> CapabilityGuard {
+        self.guard_feature(CapabilityFeature::AddDirReadOnly)
+    }
+
     /// Guards whether `codex login --mcp` is available.
     pub fn guard_mcp_login(&self) -> CapabilityGuard {
         self.guard_feature(CapabilityFeature::McpLogin)
@@ -209,14 +306,7 @@ fn guard_feature_support(
     flags: &CodexFeatureFlags,
     version: Option<&CodexVersionInfo>,
 ) -> CapabilityGuard {
-    let supported = match feature {
-        CapabilityFeature::OutputSchema => flags.supports_output_schema,
-        CapabilityFeature::AddDir => flags.supports_add_dir,
-        CapabilityFeature::McpLogin => flags.supports_mcp_login,
-        CapabilityFeature::FeaturesList => flags.supports_features_list,
-    };
-
-    if supported {
+    if feature.is_enabled_in(flags) {
         return CapabilityGuard::supported(
             feature,
             format!("Support for {} reported by Codex probe.", feature.label()),
@@ -275,3 +365,28 @@ pub(crate) fn log_guard_skip(guard: &CapabilityGuard) {
         "Skipping requested Codex capability because support was not confirmed"
     );
 }
+
+/// Callback invoked when a capability guard suppresses a requested CLI flag.
+pub type GuardSkipHook = Arc<dyn Fn(&CapabilityGuard) + Send + Sync>;
+
+/// Optional [`GuardSkipHook`] carried by the builder and client.
+#[derive(Clone, Default)]
+pub(crate) struct GuardSkipNotifier(pub(crate) Option<GuardSkipHook>);
+
+impl GuardSkipNotifier {
+    /// Logs the skipped guard and forwards it to the registered hook, if any.
+    pub(crate) fn report(&self, guard: &CapabilityGuard) {
+        log_guard_skip(guard);
+        if let Some(hook) = self.0.as_ref() {
+            hook(guard);
+        }
+    }
+}
+
+impl fmt::Debug for GuardSkipNotifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GuardSkipNotifier")
+            .field(&self.0.as_ref().map(|_| "<hook>"))
+            .finish()
+    }
+}
@@ -1,9 +1,11 @@
 mod cache;
+mod diff;
 mod guard;
 mod snapshot;
 mod types;
 
 pub use cache::*;
+pub use diff::*;
 pub use guard::*;
 pub use snapshot::*;
 pub use types::*;
@@ -53,6 +53,44 @@ pub fn read_capabilities_snapshot(
     deserialize_capabilities_snapshot(&contents, resolved_format)
 }
 
+/// Async counterpart of [`write_capabilities_snapshot`] backed by `tokio::fs`.
+///
+/// Prefer this inside async servers (e.g. under `#[tokio::main]`) so disk I/O does not stall
+/// executor threads; the sync variant remains for CLIs and build scripts.
+pub async fn write_capabilities_snapshot_async(
+    path: impl AsRef<Path>,
+    snapshot: &CodexCapabilities,
+    format: Option<CapabilitySnapshotFormat>,
+) -> Result<(), CapabilitySnapshotError> {
+    let path = path.as_ref();
+    let resolved_format = resolve_snapshot_format(format, path)?;
+    let contents = serialize_capabilities_snapshot(snapshot, resolved_format)?;
+    tokio::fs::write(path, contents).await.map_err(|source| {
+        CapabilitySnapshotError::WriteSnapshot {
+            path: path.to_path_buf(),
+            source,
+        }
+    })
+}
+
+/// Async counterpart of [`read_capabilities_snapshot`] backed by `tokio::fs`.
+///
+/// Prefer this inside async servers so reading the snapshot does not block the runtime.
+pub async fn read_capabilities_snapshot_async(
+    path: impl AsRef<Path>,
+    format: Option<CapabilitySnapshotFormat>,
+) -> Result<CodexCapabilities, CapabilitySnapshotError> {
+    let path = path.as_ref();
+    let resolved_format = resolve_snapshot_format(format, path)?;
+    let contents = tokio::fs::read_to_string(path).await.map_err(|source| {
+        CapabilitySnapshotError::ReadSnapshot {
+            path: path.to_path_buf(),
+            source,
+        }
+    })?;
+    deserialize_capabilities_snapshot(&contents, resolved_format)
+}
+
 /// Serializes capability overrides (snapshot, version, feature flags) to a JSON or TOML string.
 pub fn serialize_capability_overrides(
     overrides: &CapabilityOverrides,
@@ -108,7 +146,11 @@ pub fn capability_snapshot_matches_binary(snapshot: &CodexCapabilities, binary:
     if snapshot.cache_key != cache_key {
         return false;
     }
-    let current = current_fingerprint(&cache_key);
+    let hash_contents = snapshot
+        .fingerprint
+        .as_ref()
+        .is_some_and(|fingerprint| fingerprint.sha256.is_some());
+    let current = current_fingerprint(&cache_key, hash_contents);
     has_fingerprint_metadata(&snapshot.fingerprint)
         && has_fingerprint_metadata(&current)
         && fingerprints_match(&snapshot.fingerprint, &current)
@@ -1,7 +1,7 @@
-use semver::Version;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
 use super::{BinaryFingerprint, CapabilityCacheKey, CapabilityProbePlan};
@@ -30,6 +30,24 @@ pub struct CodexCapabilities {
     pub collected_at: SystemTime,
 }
 
+impl CodexCapabilities {
+    /// Time elapsed since the snapshot was collected.
+    ///
+    /// Returns `None` when `collected_at` lies in the future relative to `now`
+    /// (clock skew or a snapshot loaded from another host).
+    pub fn age(&self, now: SystemTime) -> Option<Duration> {
+        now.duration_since(self.collected_at).ok()
+    }
+
+    /// Returns true when the snapshot is at least `ttl` old.
+    ///
+    /// Snapshots with a future `collected_at` are treated as stale, matching
+    /// [`capability_cache_ttl_decision`](crate::capability_cache_ttl_decision).
+    pub fn is_stale(&self, ttl: Duration, now: SystemTime) -> bool {
+        self.age(now).map(|elapsed| elapsed >= ttl).unwrap_or(true)
+    }
+}
+
 /// Parsed version details emitted by `codex --version`.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CodexVersionInfo {
@@ -43,12 +61,37 @@ pub struct CodexVersionInfo {
     pub channel: CodexReleaseChannel,
 }
 
+impl CodexVersionInfo {
+    /// True when the parsed `major.minor.patch` is at least the given triplet; false when the
+    /// version could not be parsed.
+    ///
+    /// Only the triplet is compared, so a pre-release such as `2.0.0-beta` counts as `2.0.0`;
+    /// use [`Self::satisfies`] for full semver ordering.
+    pub fn is_at_least(&self, major: u64, minor: u64, patch: u64) -> bool {
+        self.semantic
+            .is_some_and(|semantic| semantic >= (major, minor, patch))
+    }
+
+    /// Checks the version against a semver range such as `>=1.2, <2`; false when the version
+    /// could not be parsed.
+    ///
+    /// Follows [`VersionReq::matches`], so pre-releases only match requirements that name a
+    /// pre-release of the same `major.minor.patch`.
+    pub fn satisfies(&self, req: &VersionReq) -> bool {
+        crate::version::codex_semver(self).is_some_and(|version| req.matches(&version))
+    }
+}
+
 /// Release channel segments inferred from the Codex version string.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum CodexReleaseChannel {
     Stable,
     Beta,
     Nightly,
+    /// Release candidates (`-rc`, `-rc.1`).
+    ReleaseCandidate,
+    /// Early pre-releases (`-alpha`, `-dev`, `-preview`).
+    Alpha,
     /// Fallback for bespoke or vendor-patched builds.
     Custom,
 }
@@ -59,6 +102,8 @@ impl std::fmt::Display for CodexReleaseChannel {
             CodexReleaseChannel::Stable => "stable",
             CodexReleaseChannel::Beta => "beta",
             CodexReleaseChannel::Nightly => "nightly",
+            CodexReleaseChannel::ReleaseCandidate => "rc",
+            CodexReleaseChannel::Alpha => "alpha",
             CodexReleaseChannel::Custom => "custom",
         };
         write!(f, "{label}")
@@ -68,7 +113,7 @@ impl std::fmt::Display for CodexReleaseChannel {
 /// Release metadata for a specific Codex build channel.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CodexRelease {
-    /// Release channel (stable/beta/nightly/custom).
+    /// Release channel (stable/beta/nightly/rc/alpha/custom).
     pub channel: CodexReleaseChannel,
     /// Parsed semantic version for comparison.
     pub version: Version,
@@ -88,12 +133,18 @@ pub struct CodexLatestReleases {
     pub beta: Option<Version>,
     /// Latest nightly build when available.
     pub nightly: Option<Version>,
+    /// Latest release candidate when available.
+    #[serde(default)]
+    pub release_candidate: Option<Version>,
+    /// Latest alpha pre-release when available.
+    #[serde(default)]
+    pub alpha: Option<Version>,
 }
 
 impl CodexLatestReleases {
     /// Returns the most appropriate latest release for the given channel,
     /// falling back to a more stable track when channel-specific data is
-    /// missing.
+    /// missing (alpha → rc → beta → stable, then any remaining track).
     pub fn select_for_channel(
         &self,
         channel: CodexReleaseChannel,
@@ -102,25 +153,9 @@ impl CodexLatestReleases {
             return (Some(release), channel, false);
         }
 
-        let fallback = self
-            .stable
-            .as_ref()
-            .map(|version| CodexRelease {
-                channel: CodexReleaseChannel::Stable,
-                version: version.clone(),
-            })
-            .or_else(|| {
-                self.beta.as_ref().map(|version| CodexRelease {
-                    channel: CodexReleaseChannel::Beta,
-                    version: version.clone(),
-                })
-            })
-            .or_else(|| {
-                self.nightly.as_ref().map(|version| CodexRelease {
-                    channel: CodexReleaseChannel::Nightly,
-                    version: version.clone(),
-                })
-            });
+        let fallback = fallback_order(channel)
+            .iter()
+            .find_map(|candidate| self.release_for_channel(*candidate));
 
         let fallback_channel = fallback
             .as_ref()
@@ -130,6 +165,51 @@ impl CodexLatestReleases {
         (fallback, fallback_channel, fell_back)
     }
 
+    /// Builds the table from a GitHub releases API payload
+    /// (`GET /repos/openai/codex/releases`) that the caller already fetched.
+    ///
+    /// Drafts and tags without a semantic version (after any `rust-v`/`v` prefix) are
+    /// skipped. Non-prerelease entries feed `stable`; prereleases are slotted by the channel
+    /// inferred from their version suffix (`nightly`, `release_candidate`, `alpha`, or `beta`),
+    /// with unrecognised suffixes filed under `beta`. The highest version per channel wins. No
+    /// network I/O is performed.
+    pub fn from_github_releases(json: &str) -> Result<Self, ReleaseParseError> {
+        #[derive(Deserialize)]
+        struct GithubRelease {
+            tag_name: String,
+            #[serde(default)]
+            prerelease: bool,
+            #[serde(default)]
+            draft: bool,
+        }
+
+        let releases: Vec<GithubRelease> =
+            serde_json::from_str(json).map_err(|source| ReleaseParseError::Json { source })?;
+
+        let mut latest = Self::default();
+        for release in releases.into_iter().filter(|release| !release.draft) {
+            let Some(version) = version_from_tag(&release.tag_name) else {
+                continue;
+            };
+            let slot = if !release.prerelease {
+                &mut latest.stable
+            } else {
+                match crate::version::infer_release_channel(version.pre.as_str()) {
+                    CodexReleaseChannel::Nightly => &mut latest.nightly,
+                    CodexReleaseChannel::ReleaseCandidate => &mut latest.release_candidate,
+                    CodexReleaseChannel::Alpha => &mut latest.alpha,
+                    CodexReleaseChannel::Stable
+                    | CodexReleaseChannel::Beta
+                    | CodexReleaseChannel::Custom => &mut latest.beta,
+                }
+            };
+            if slot.as_ref().map_or(true, |current| version > *current) {
+                *slot = Some(version);
+            }
+        }
+        Ok(latest)
+    }
+
     fn release_for_channel(&self, channel: CodexReleaseChannel) -> Option<CodexRelease> {
         match channel {
             CodexReleaseChannel::Stable => self.stable.as_ref().map(|version| CodexRelease {
@@ -144,11 +224,45 @@ impl CodexLatestReleases {
                 channel,
                 version: version.clone(),
             }),
+            CodexReleaseChannel::ReleaseCandidate => {
+                self.release_candidate.as_ref().map(|version| CodexRelease {
+                    channel,
+                    version: version.clone(),
+                })
+            }
+            CodexReleaseChannel::Alpha => self.alpha.as_ref().map(|version| CodexRelease {
+                channel,
+                version: version.clone(),
+            }),
             CodexReleaseChannel::Custom => None,
         }
     }
 }
 
+fn fallback_order(channel: CodexReleaseChannel) -> &'static [CodexReleaseChannel] {
+    use CodexReleaseChannel::{Alpha, Beta, Nightly, ReleaseCandidate, Stable};
+    match channel {
+        Alpha => &[ReleaseCandidate, Beta, Stable, Nightly],
+        ReleaseCandidate => &[Beta, Stable, Nightly, Alpha],
+        _ => &[Stable, Beta, Nightly, ReleaseCandidate, Alpha],
+    }
+}
+
+fn version_from_tag(tag: &str) -> Option<Version> {
+    let start = tag.find(|c: char| c.is_ascii_digit())?;
+    Version::parse(&tag[start..]).ok()
+}
+
+/// Errors returned by [`CodexLatestReleases::from_github_releases`].
+#[derive(Debug, Error)]
+pub enum ReleaseParseError {
+    #[error("failed to decode GitHub releases JSON: {source}")]
+    Json {
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
 /// Update guidance derived from comparing local and latest Codex versions.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CodexUpdateAdvisory {
@@ -203,6 +317,16 @@ pub struct CodexFeatureFlags {
     pub supports_add_dir: bool,
     /// True when `codex login --mcp` is recognized for MCP integration.
     pub supports_mcp_login: bool,
+    /// True when probes confirm `codex exec --json` emits JSONL events.
+    #[serde(default)]
+    pub supports_json_output: bool,
+    /// True when `codex exec --help` declares `--add-dir-read-only`.
+    #[serde(default)]
+    pub supports_add_dir_read_only: bool,
+    /// Subcommands listed by `codex app-server --help` (e.g. `generate-ts`); `None` when that
+    /// probe failed or did not run.
+    #[serde(default)]
+    pub app_server_subcommands: Option<Vec<String>>,
 }
 
 /// Optional overrides for feature detection that can be layered onto probe results.
@@ -216,6 +340,12 @@ pub struct CapabilityFeatureOverrides {
     pub supports_add_dir: Option<bool>,
     /// Override for `codex login --mcp` support; `None` defers to probes.
     pub supports_mcp_login: Option<bool>,
+    /// Override for `codex exec --json` support; `None` defers to probes.
+    #[serde(default)]
+    pub supports_json_output: Option<bool>,
+    /// Override for `codex exec --add-dir-read-only` support; `None` defers to probes.
+    #[serde(default)]
+    pub supports_add_dir_read_only: Option<bool>,
 }
 
 impl CapabilityFeatureOverrides {
@@ -225,6 +355,30 @@ impl CapabilityFeatureOverrides {
             && self.supports_output_schema.is_none()
             && self.supports_add_dir.is_none()
             && self.supports_mcp_login.is_none()
+            && self.supports_json_output.is_none()
+            && self.supports_add_dir_read_only.is_none()
+    }
+
+    /// Layers `higher_priority` over `self` flag by flag; `None` defers to the base value.
+    pub fn merged_with(&self, higher_priority: &CapabilityFeatureOverrides) -> Self {
+        CapabilityFeatureOverrides {
+            supports_features_list: higher_priority
+                .supports_features_list
+                .or(self.supports_features_list),
+            supports_output_schema: higher_priority
+                .supports_output_schema
+                .or(self.supports_output_schema),
+            supports_add_dir: higher_priority.supports_add_dir.or(self.supports_add_dir),
+            supports_mcp_login: higher_priority
+                .supports_mcp_login
+                .or(self.supports_mcp_login),
+            supports_json_output: higher_priority
+                .supports_json_output
+                .or(self.supports_json_output),
+            supports_add_dir_read_only: higher_priority
+                .supports_add_dir_read_only
+                .or(self.supports_add_dir_read_only),
+        }
     }
 
     /// Builds overrides that mirror every provided feature flag, including false values.
@@ -234,6 +388,8 @@ impl CapabilityFeatureOverrides {
             supports_output_schema: Some(flags.supports_output_schema),
             supports_add_dir: Some(flags.supports_add_dir),
             supports_mcp_login: Some(flags.supports_mcp_login),
+            supports_json_output: Some(flags.supports_json_output),
+            supports_add_dir_read_only: Some(flags.supports_add_dir_read_only),
         }
     }
 
@@ -244,6 +400,8 @@ impl CapabilityFeatureOverrides {
             supports_output_schema: flags.supports_output_schema.then_some(true),
             supports_add_dir: flags.supports_add_dir.then_some(true),
             supports_mcp_login: flags.supports_mcp_login.then_some(true),
+            supports_json_output: flags.supports_json_output.then_some(true),
+            supports_add_dir_read_only: flags.supports_add_dir_read_only.then_some(true),
         }
     }
 }
@@ -266,6 +424,26 @@ impl CapabilityOverrides {
     pub fn is_empty(&self) -> bool {
         self.snapshot.is_none() && self.version.is_none() && self.features.is_empty()
     }
+
+    /// Layers `higher_priority` over `self`, e.g. a per-request override over a base loaded
+    /// from a snapshot file.
+    ///
+    /// `snapshot` and `version` are taken from `higher_priority` when set and from `self`
+    /// otherwise; feature flags are merged individually via
+    /// [`CapabilityFeatureOverrides::merged_with`].
+    pub fn merged_with(&self, higher_priority: &CapabilityOverrides) -> CapabilityOverrides {
+        CapabilityOverrides {
+            snapshot: higher_priority
+                .snapshot
+                .clone()
+                .or_else(|| self.snapshot.clone()),
+            version: higher_priority
+                .version
+                .clone()
+                .or_else(|| self.version.clone()),
+            features: self.features.merged_with(&higher_priority.features),
+        }
+    }
 }
 
 /// Supported serialization formats for capability snapshots and overrides.
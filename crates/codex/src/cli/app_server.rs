@@ -18,6 +18,14 @@ impl AppServerCodegenTarget {
         }
     }
 
+    /// CLI surface for this target (e.g. `codex app-server generate-ts`), suitable for errors.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AppServerCodegenTarget::TypeScript { .. } => "codex app-server generate-ts",
+            AppServerCodegenTarget::JsonSchema => "codex app-server generate-json-schema",
+        }
+    }
+
     pub(crate) fn prettier(&self) -> Option<&PathBuf> {
         match self {
             AppServerCodegenTarget::TypeScript { prettier } => prettier.as_ref(),
@@ -147,4 +155,7 @@ pub struct AppServerCodegenOutput {
     pub stderr: String,
     /// Output directory passed to `--out`.
     pub out_dir: PathBuf,
+    /// Files under `out_dir` that the run created or modified, recursively and in sorted order.
+    /// Files already present and left untouched are omitted; symlinks are not followed.
+    pub files: Vec<PathBuf>,
 }
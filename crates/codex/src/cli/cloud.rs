@@ -92,6 +92,41 @@ pub struct CloudListOutput {
     pub json: Option<Value>,
 }
 
+/// Output from `codex cloud exec`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CloudExecOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+    /// Task URL printed by the CLI once the task was created.
+    pub url: Option<String>,
+    /// Task id taken from the final path segment of [`CloudExecOutput::url`].
+    pub task_id: Option<String>,
+}
+
+impl CloudExecOutput {
+    pub(crate) fn parse(status: ExitStatus, stdout: String, stderr: String) -> Self {
+        let url = stdout
+            .lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| line.starts_with("http") && line.contains("/tasks/"))
+            .map(str::to_string);
+        let task_id = url
+            .as_deref()
+            .and_then(|url| url.trim_end_matches('/').rsplit('/').next())
+            .filter(|id| !id.is_empty())
+            .map(str::to_string);
+        Self {
+            status,
+            stdout,
+            stderr,
+            url,
+            task_id,
+        }
+    }
+}
+
 /// Request for `codex cloud status <TASK_ID>`.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CloudStatusRequest {
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use crate::{CliOverridesPatch, ConfigOverride, FlagState};
 
 /// Options configuring a single exec request.
@@ -68,3 +70,51 @@ impl ExecRequest {
         self
     }
 }
+
+/// Per-call overrides for [`crate::CodexClient::send_prompt_with_options`].
+///
+/// Unset fields fall back to the client's builder configuration; config overrides are appended
+/// after the builder-level ones so they win for duplicate keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecOptions {
+    pub profile: Option<String>,
+    pub model: Option<String>,
+    pub config_overrides: Vec<ConfigOverride>,
+}
+
+impl ExecOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        let profile = profile.into();
+        self.profile = (!profile.trim().is_empty()).then_some(profile);
+        self
+    }
+
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        let model = model.into();
+        self.model = (!model.trim().is_empty()).then_some(model);
+        self
+    }
+
+    pub fn config_override(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config_overrides.push(ConfigOverride::new(key, value));
+        self
+    }
+
+    pub fn config_override_raw(mut self, raw: impl Into<String>) -> Self {
+        self.config_overrides.push(ConfigOverride::from_raw(raw));
+        self
+    }
+}
+
+/// File passed as context to [`crate::CodexClient::prompt_with_attachments`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Attachment {
+    /// Image forwarded via `--image`.
+    Image(PathBuf),
+    /// Text or source file, exposed via `--add-dir` when supported and inlined otherwise.
+    TextFile(PathBuf),
+}
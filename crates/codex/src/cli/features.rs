@@ -195,6 +195,12 @@ impl Default for FeaturesListRequest {
     }
 }
 
+impl From<FeaturesListFormat> for FeaturesListRequest {
+    fn from(format: FeaturesListFormat) -> Self {
+        Self::new().json(matches!(format, FeaturesListFormat::Json))
+    }
+}
+
 /// Request for `codex features`.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FeaturesCommandRequest {
@@ -13,13 +13,14 @@ mod stdio_to_uds;
 
 pub use app_server::{AppServerCodegenOutput, AppServerCodegenRequest, AppServerCodegenTarget};
 pub use cloud::{
-    CloudExecRequest, CloudListOutput, CloudListRequest, CloudOverviewRequest, CloudStatusRequest,
+    CloudExecOutput, CloudExecRequest, CloudListOutput, CloudListRequest, CloudOverviewRequest,
+    CloudStatusRequest,
 };
 pub use debug::{
     DebugAppServerHelpRequest, DebugAppServerRequest, DebugAppServerSendMessageV2Request,
     DebugCommandRequest, DebugHelpRequest,
 };
-pub use exec::ExecRequest;
+pub use exec::{Attachment, ExecOptions, ExecRequest};
 pub use features::{
     CodexFeature, CodexFeatureStage, FeaturesCommandRequest, FeaturesDisableRequest,
     FeaturesEnableRequest, FeaturesListFormat, FeaturesListOutput, FeaturesListRequest,
@@ -32,7 +33,12 @@ pub use mcp::{
 pub use responses_api_proxy::{
     ResponsesApiProxyHandle, ResponsesApiProxyInfo, ResponsesApiProxyRequest,
 };
-pub use review::{ExecReviewCommandRequest, ReviewCommandRequest};
-pub use sandbox::{SandboxCommandRequest, SandboxPlatform, SandboxRun};
-pub use session::{ForkSessionRequest, ResumeSessionRequest};
-pub use stdio_to_uds::StdioToUdsRequest;
+pub use review::{
+    ExecReviewCommandRequest, ReviewCommandRequest, ReviewFinding, ReviewLocation, ReviewOutcome,
+    ReviewOutputFormat, ReviewSeverity,
+};
+pub use sandbox::{
+    SandboxCommandRequest, SandboxDecision, SandboxDenial, SandboxPlatform, SandboxRun,
+};
+pub use session::{ForkSessionRequest, ResumeSessionRequest, SessionHandle};
+pub use stdio_to_uds::{StdioToUdsRequest, UdsBridgeHandle};
@@ -1,3 +1,6 @@
+use serde_json::Value;
+use std::{path::PathBuf, process::ExitStatus};
+
 use crate::CliOverridesPatch;
 
 /// Request for `codex review [OPTIONS] [PROMPT]`.
@@ -143,3 +146,216 @@ impl Default for ExecReviewCommandRequest {
         Self::new()
     }
 }
+
+/// Output mode used to parse a `codex review` run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReviewOutputFormat {
+    /// `--json` was passed and findings were read from structured output.
+    Json,
+    /// Findings were scraped from the human-readable `Review comment:` block.
+    Text,
+}
+
+/// Severity derived from the `[P0]`..`[P3]` priority Codex attaches to each finding.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ReviewSeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl ReviewSeverity {
+    fn from_priority(priority: u64) -> Option<Self> {
+        match priority {
+            0 => Some(Self::Critical),
+            1 => Some(Self::High),
+            2 => Some(Self::Medium),
+            3 => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    fn from_title(title: &str) -> Option<Self> {
+        let rest = title.trim_start().strip_prefix("[P")?;
+        let (digits, _) = rest.split_once(']')?;
+        Self::from_priority(digits.parse().ok()?)
+    }
+}
+
+/// File and line range a finding points at.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReviewLocation {
+    pub path: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// Single review comment produced by `codex review`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReviewFinding {
+    pub title: String,
+    pub body: String,
+    pub severity: Option<ReviewSeverity>,
+    pub location: Option<ReviewLocation>,
+    /// Model confidence in the finding (`0.0..=1.0`); only present in JSON output.
+    pub confidence: Option<f64>,
+}
+
+/// Captured output and parsed findings from [`crate::CodexClient::review`].
+#[derive(Clone, Debug)]
+pub struct ReviewOutcome {
+    /// Exit status returned by the subcommand.
+    pub status: ExitStatus,
+    /// Captured stdout (mirrored to the console when `mirror_stdout` is true).
+    pub stdout: String,
+    /// Captured stderr (mirrored unless `quiet` is set).
+    pub stderr: String,
+    /// Output mode the findings were parsed from.
+    pub format: ReviewOutputFormat,
+    /// Findings in the order Codex reported them; empty when the review raised no issues.
+    pub findings: Vec<ReviewFinding>,
+    /// Overall verdict (e.g. `patch is correct`) when reported in JSON output.
+    pub overall_correctness: Option<String>,
+    /// Overall explanation when reported in JSON output.
+    pub overall_explanation: Option<String>,
+}
+
+impl ReviewOutcome {
+    /// Parses captured review output, falling back to text scraping when JSON yields no review payload.
+    pub(crate) fn parse(status: ExitStatus, stdout: String, stderr: String, json: bool) -> Self {
+        if json {
+            if let Some(review) = find_json_review(&stdout) {
+                return Self {
+                    status,
+                    findings: parse_json_findings(&review),
+                    overall_correctness: json_string(&review, "overall_correctness"),
+                    overall_explanation: json_string(&review, "overall_explanation"),
+                    stdout,
+                    stderr,
+                    format: ReviewOutputFormat::Json,
+                };
+            }
+        }
+
+        Self {
+            status,
+            findings: parse_text_findings(&stdout),
+            overall_correctness: None,
+            overall_explanation: None,
+            stdout,
+            stderr,
+            format: ReviewOutputFormat::Text,
+        }
+    }
+}
+
+/// Locates the review payload either as a bare JSON document/line or inside an agent message item.
+fn find_json_review(stdout: &str) -> Option<Value> {
+    let is_review = |value: &Value| value.get("findings").is_some_and(Value::is_array);
+    if let Ok(value) = serde_json::from_str::<Value>(stdout.trim()) {
+        if is_review(&value) {
+            return Some(value);
+        }
+    }
+    stdout.lines().rev().find_map(|line| {
+        let value = serde_json::from_str::<Value>(line.trim()).ok()?;
+        if is_review(&value) {
+            return Some(value);
+        }
+        let text = value.get("item")?.get("text")?.as_str()?;
+        serde_json::from_str::<Value>(text).ok().filter(is_review)
+    })
+}
+
+fn json_string(value: &Value, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(str::to_string)
+}
+
+fn parse_json_findings(review: &Value) -> Vec<ReviewFinding> {
+    let Some(findings) = review.get("findings").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    findings
+        .iter()
+        .map(|finding| {
+            let title = json_string(finding, "title").unwrap_or_default();
+            let severity = finding
+                .get("priority")
+                .and_then(Value::as_u64)
+                .and_then(ReviewSeverity::from_priority)
+                .or_else(|| ReviewSeverity::from_title(&title));
+            let location = finding.get("code_location").and_then(|location| {
+                let path = location.get("absolute_file_path")?.as_str()?;
+                let range = location.get("line_range")?;
+                Some(ReviewLocation {
+                    path: PathBuf::from(path),
+                    start_line: u32::try_from(range.get("start")?.as_u64()?).ok()?,
+                    end_line: u32::try_from(range.get("end")?.as_u64()?).ok()?,
+                })
+            });
+            ReviewFinding {
+                body: json_string(finding, "body").unwrap_or_default(),
+                confidence: finding.get("confidence_score").and_then(Value::as_f64),
+                title,
+                severity,
+                location,
+            }
+        })
+        .collect()
+}
+
+/// Parses `- <title> — <path>:<start>-<end>` entries followed by indented body lines.
+fn parse_text_findings(stdout: &str) -> Vec<ReviewFinding> {
+    let mut findings: Vec<ReviewFinding> = Vec::new();
+    let mut in_block = false;
+    for line in stdout.lines() {
+        let trimmed = line.trim();
+        let header = trimmed.to_ascii_lowercase();
+        if header.ends_with("review comment:") || header.ends_with("review comments:") {
+            in_block = true;
+            continue;
+        }
+        if !in_block {
+            continue;
+        }
+        if let Some(entry) = line.strip_prefix("- ") {
+            let (title, location) = match entry.rsplit_once(" — ") {
+                Some((title, location)) => (title, parse_text_location(location)),
+                None => (entry, None),
+            };
+            findings.push(ReviewFinding {
+                title: title.trim().to_string(),
+                body: String::new(),
+                severity: ReviewSeverity::from_title(title),
+                location,
+                confidence: None,
+            });
+        } else if let Some(finding) = findings.last_mut() {
+            if line.starts_with("  ") || trimmed.is_empty() {
+                if !finding.body.is_empty() || !trimmed.is_empty() {
+                    finding
+                        .body
+                        .push_str(line.strip_prefix("  ").unwrap_or(trimmed));
+                    finding.body.push('\n');
+                }
+            } else {
+                in_block = false;
+            }
+        }
+    }
+    for finding in &mut findings {
+        finding.body = finding.body.trim_end().to_string();
+    }
+    findings
+}
+
+fn parse_text_location(location: &str) -> Option<ReviewLocation> {
+    let (path, range) = location.trim().rsplit_once(':')?;
+    let (start, end) = range.split_once('-').unwrap_or((range, range));
+    Some(ReviewLocation {
+        path: PathBuf::from(path),
+        start_line: start.parse().ok()?,
+        end_line: end.parse().ok()?,
+    })
+}
@@ -100,4 +100,62 @@ pub struct SandboxRun {
     pub stdout: String,
     /// Captured stderr (mirrored unless `quiet` is set).
     pub stderr: String,
+    /// Outcome derived from the exit status and any reported denials.
+    pub decision: SandboxDecision,
+    /// Policy violations listed in the `=== Sandbox denials ===` block (macOS `--log-denials` only).
+    pub denials: Vec<SandboxDenial>,
+}
+
+/// Allow/deny outcome for a sandboxed command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SandboxDecision {
+    /// The command exited successfully and no denials were reported.
+    Allowed,
+    /// The sandbox reported at least one denied operation.
+    Denied,
+    /// The command exited non-zero without reporting denials; the cause may or may not be the sandbox.
+    Failed,
+}
+
+/// Single operation blocked by the sandbox, as reported by `--log-denials`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SandboxDenial {
+    /// Name of the process that attempted the operation.
+    pub process: String,
+    /// Denied capability (e.g. `file-write-data /etc/hosts`).
+    pub capability: String,
+}
+
+const DENIALS_HEADER: &str = "=== Sandbox denials ===";
+
+impl SandboxDenial {
+    /// Parses `(process) capability` lines that follow the denials header in stderr.
+    pub(crate) fn parse_all(stderr: &str) -> Vec<Self> {
+        let Some((_, block)) = stderr.split_once(DENIALS_HEADER) else {
+            return Vec::new();
+        };
+        block
+            .lines()
+            .filter_map(|line| {
+                let rest = line.trim().strip_prefix('(')?;
+                let (process, capability) = rest.split_once(')')?;
+                Some(Self {
+                    process: process.to_string(),
+                    capability: capability.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl SandboxDecision {
+    pub(crate) fn from_run(status: ExitStatus, denials: &[SandboxDenial]) -> Self {
+        if !denials.is_empty() {
+            Self::Denied
+        } else if status.success() {
+            Self::Allowed
+        } else {
+            Self::Failed
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::process::ExitStatus;
+
 use crate::CliOverridesPatch;
 
 /// Request for `codex resume [OPTIONS] [SESSION_ID] [PROMPT]`.
@@ -111,3 +113,48 @@ impl Default for ForkSessionRequest {
         Self::new()
     }
 }
+
+/// Captured output from `codex fork` plus the id of the new thread.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionHandle {
+    /// Exit status returned by the subcommand.
+    pub status: ExitStatus,
+    /// Captured stdout (mirrored to the console when `mirror_stdout` is true).
+    pub stdout: String,
+    /// Captured stderr (mirrored unless `quiet` is set).
+    pub stderr: String,
+    /// Thread id of the forked session, taken from the `codex resume <ID>` hint or a
+    /// `session id:` line; `None` when the CLI printed neither.
+    pub thread_id: Option<String>,
+}
+
+impl SessionHandle {
+    pub(crate) fn parse(status: ExitStatus, stdout: String, stderr: String) -> Self {
+        let thread_id = [stdout.as_str(), stderr.as_str()]
+            .into_iter()
+            .flat_map(str::lines)
+            .filter_map(|line| {
+                let lower = line.to_ascii_lowercase();
+                let start = lower
+                    .find("codex resume ")
+                    .map(|idx| idx + "codex resume ".len())
+                    .or_else(|| {
+                        lower
+                            .find("session id:")
+                            .map(|idx| idx + "session id:".len())
+                    })?;
+                line[start..]
+                    .split_whitespace()
+                    .next()
+                    .map(|id| id.trim_matches(|c: char| c == '`' || c == '.').to_string())
+                    .filter(|id| !id.is_empty())
+            })
+            .next_back();
+        Self {
+            status,
+            stdout,
+            stderr,
+            thread_id,
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::CodexError;
 
 /// Request for `codex stdio-to-uds <SOCKET_PATH>`.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -23,3 +25,31 @@ impl StdioToUdsRequest {
         self
     }
 }
+
+/// Running `codex stdio-to-uds` bridge.
+///
+/// The child is spawned with kill-on-drop, so dropping the handle tears the bridge down.
+#[derive(Debug)]
+pub struct UdsBridgeHandle {
+    /// Spawned bridge process; write to its stdin and read its stdout to talk to the socket.
+    pub child: tokio::process::Child,
+    /// Socket the bridge relays to, resolved against the working directory when relative.
+    pub socket_path: PathBuf,
+}
+
+impl UdsBridgeHandle {
+    /// Unix socket the bridge is connected to.
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Kills the bridge and waits for it to exit.
+    pub async fn shutdown(mut self) -> Result<(), CodexError> {
+        if let Err(source) = self.child.kill().await {
+            if source.kind() != std::io::ErrorKind::InvalidInput {
+                return Err(CodexError::Wait { source });
+            }
+        }
+        Ok(())
+    }
+}
@@ -7,22 +7,59 @@ use std::{
 use crate::{
     apply_diff::ApplyDiffArtifacts,
     builder::{apply_cli_overrides, resolve_cli_overrides, CliOverridesPatch},
-    process::{spawn_with_retry, tee_stream, CommandOutput, ConsoleTarget},
+    process::{
+        spawn_with_retry, tee_stream, tee_stream_captured, timeout_with_shutdown, CaptureOptions,
+        CommandOutput, ConsoleTarget,
+    },
     CodexClient, CodexError,
 };
 use tempfile::TempDir;
-use tokio::{process::Command, time};
+use tokio::{process::Command, sync::OwnedSemaphorePermit};
 
 impl CodexClient {
+    pub(crate) fn capture_options(&self) -> CaptureOptions {
+        CaptureOptions {
+            chunk_size: self.io_chunk_size,
+            serialize_console: self.serialize_console_output,
+            max_output_bytes: self.max_output_bytes,
+        }
+    }
+
+    /// Waits for a free subprocess slot when [`crate::CodexClientBuilder::max_concurrent`] is
+    /// set; hold the permit until the child has exited.
+    pub(crate) async fn acquire_process_slot(&self) -> Option<OwnedSemaphorePermit> {
+        let slots = self.process_slots.clone()?;
+        Some(
+            slots
+                .acquire_owned()
+                .await
+                .expect("process slot semaphore is never closed"),
+        )
+    }
+
     pub(crate) fn directory_context(&self) -> Result<DirectoryContext, CodexError> {
         if let Some(dir) = &self.working_dir {
             return Ok(DirectoryContext::Fixed(dir.clone()));
         }
 
-        let temp = tempfile::tempdir().map_err(CodexError::TempDir)?;
+        let temp = match &self.temp_dir_base {
+            Some(base) => TempDir::new_in(base),
+            None => tempfile::tempdir(),
+        }
+        .map_err(CodexError::TempDir)?;
         Ok(DirectoryContext::Ephemeral(temp))
     }
 
+    /// [`Self::directory_context`] for `exec` runs; initializes ephemeral dirs as git repos when
+    /// [`crate::CodexClientBuilder::auto_git_init`] is enabled.
+    pub(crate) async fn exec_directory_context(&self) -> Result<DirectoryContext, CodexError> {
+        let dir_ctx = self.directory_context()?;
+        if let (true, DirectoryContext::Ephemeral(dir)) = (self.auto_git_init, &dir_ctx) {
+            git_init(dir.path()).await?;
+        }
+        Ok(dir_ctx)
+    }
+
     pub(crate) fn sandbox_working_dir(
         &self,
         request_dir: Option<PathBuf>,
@@ -58,18 +95,26 @@ impl CodexClient {
         apply_cli_overrides(&mut command, &resolved_overrides, true);
 
         self.command_env.apply(&mut command)?;
+        let command_line = crate::process::redacted_command_line(&command);
 
         let mut child = spawn_with_retry(&mut command, self.command_env.binary_path())?;
+        let pid = child.id();
 
         let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
         let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
 
-        let stdout_task = tokio::spawn(tee_stream(
+        let stdout_task = tokio::spawn(tee_stream_captured(
             stdout,
             ConsoleTarget::Stdout,
             self.mirror_stdout,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream_captured(
+            stderr,
+            ConsoleTarget::Stderr,
+            !self.quiet,
+            self.capture_options(),
         ));
-        let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !self.quiet));
 
         let timeout = self.timeout;
         let wait_task = async move {
@@ -78,23 +123,23 @@ impl CodexClient {
                 .wait()
                 .await
                 .map_err(|source| CodexError::Wait { source })?;
-            let stdout_bytes = stdout_task
+            let stdout_capture = stdout_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            let stderr_bytes = stderr_task
+            let stderr_capture = stderr_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            Ok::<_, CodexError>((status, stdout_bytes, stderr_bytes))
+            Ok::<_, CodexError>((status, stdout_capture, stderr_capture))
         };
 
-        let (status, stdout_bytes, stderr_bytes) = if timeout.is_zero() {
+        let (status, stdout_capture, stderr_capture) = if timeout.is_zero() {
             wait_task.await?
         } else {
-            match time::timeout(timeout, wait_task).await {
-                Ok(result) => result?,
-                Err(_) => {
+            match timeout_with_shutdown(timeout, wait_task, pid, self.graceful_shutdown).await {
+                Some(result) => result?,
+                None => {
                     return Err(CodexError::Timeout { timeout });
                 }
             }
@@ -102,15 +147,24 @@ impl CodexClient {
 
         if !status.success() {
             return Err(CodexError::NonZeroExit {
+                command: command_line.clone(),
+                exit_code: status.code(),
                 status,
-                stderr: String::from_utf8(stderr_bytes)?,
+                stderr: crate::ansi::error_stderr(
+                    String::from_utf8(stderr_capture.bytes)?,
+                    self.strip_ansi_errors,
+                ),
+                truncated: stderr_capture.truncated,
             });
         }
 
         Ok(ApplyDiffArtifacts {
+            command: command_line,
             status,
-            stdout: String::from_utf8(stdout_bytes)?,
-            stderr: String::from_utf8(stderr_bytes)?,
+            stdout: String::from_utf8(stdout_capture.bytes)?,
+            stderr: String::from_utf8(stderr_capture.bytes)?,
+            truncated: stdout_capture.truncated || stderr_capture.truncated,
+            conflicts: Vec::new(),
         })
     }
 
@@ -127,14 +181,26 @@ impl CodexClient {
             .kill_on_drop(true);
 
         self.command_env.apply(&mut command)?;
+        let command_line = crate::process::redacted_command_line(&command);
 
         let mut child = spawn_with_retry(&mut command, self.command_env.binary_path())?;
+        let pid = child.id();
 
         let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
         let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
 
-        let stdout_task = tokio::spawn(tee_stream(stdout, ConsoleTarget::Stdout, false));
-        let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, false));
+        let stdout_task = tokio::spawn(tee_stream(
+            stdout,
+            ConsoleTarget::Stdout,
+            false,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream(
+            stderr,
+            ConsoleTarget::Stderr,
+            false,
+            self.capture_options(),
+        ));
 
         let wait_task = async move {
             let status = child
@@ -155,9 +221,10 @@ impl CodexClient {
         let (status, stdout_bytes, stderr_bytes) = if self.timeout.is_zero() {
             wait_task.await?
         } else {
-            match time::timeout(self.timeout, wait_task).await {
-                Ok(result) => result?,
-                Err(_) => {
+            match timeout_with_shutdown(self.timeout, wait_task, pid, self.graceful_shutdown).await
+            {
+                Some(result) => result?,
+                None => {
                     return Err(CodexError::Timeout {
                         timeout: self.timeout,
                     });
@@ -166,6 +233,7 @@ impl CodexClient {
         };
 
         Ok(CommandOutput {
+            command: command_line,
             status,
             stdout: stdout_bytes,
             stderr: stderr_bytes,
@@ -173,6 +241,32 @@ impl CodexClient {
     }
 }
 
+async fn git_init(dir: &Path) -> Result<(), CodexError> {
+    let git_error = |source| CodexError::GitInit {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let output = Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(dir)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(git_error)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(git_error(std::io::Error::other(format!(
+            "git exited with {}: {}",
+            output.status,
+            stderr.trim()
+        ))));
+    }
+    Ok(())
+}
+
 pub(crate) enum DirectoryContext {
     Fixed(PathBuf),
     Ephemeral(TempDir),
@@ -1,18 +1,52 @@
-use std::fs as std_fs;
+use std::{
+    collections::HashMap,
+    fs as std_fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
-use tokio::{process::Command, time};
+use tokio::process::Command;
 
 use crate::{
     builder::{apply_cli_overrides, resolve_cli_overrides},
-    process::{spawn_with_retry, tee_stream, ConsoleTarget},
+    process::{
+        spawn_with_retry, tee_stream, tee_stream_captured, timeout_with_shutdown, ConsoleTarget,
+    },
     AppServerCodegenOutput, AppServerCodegenRequest, CodexClient, CodexError,
 };
 
 impl CodexClient {
+    /// Runs app-server codegen after checking that the binary offers the requested target.
+    ///
+    /// Fails with [`CodexError::UnsupportedCapability`] when the capability probe's
+    /// `codex app-server --help` listing (see [`crate::CodexFeatureFlags::app_server_subcommands`])
+    /// lacks the target subcommand. The listing follows the client's capability cache policy and
+    /// snapshots; when it is unavailable the check is skipped and the CLI reports any problem.
+    /// Otherwise behaves like [`Self::generate_app_server_bindings`].
+    pub async fn app_server_codegen(
+        &self,
+        request: AppServerCodegenRequest,
+    ) -> Result<AppServerCodegenOutput, CodexError> {
+        let capabilities = self.probe_capabilities().await;
+        if let Some(subcommands) = &capabilities.features.app_server_subcommands {
+            if !subcommands
+                .iter()
+                .any(|name| name == request.target.subcommand())
+            {
+                return Err(CodexError::UnsupportedCapability {
+                    feature: request.target.label(),
+                });
+            }
+        }
+
+        self.generate_app_server_bindings(request).await
+    }
+
     /// Generates app-server bindings via `codex app-server generate-ts` or `generate-json-schema`.
     ///
     /// Ensures the output directory exists, mirrors stdout/stderr according to the builder
-    /// (`mirror_stdout` / `quiet`), and returns captured output plus the exit status. Non-zero
+    /// (`mirror_stdout` / `quiet`), and returns captured output, the exit status, and the files
+    /// under the output directory that the run created or modified. Non-zero
     /// exits bubble up as [`CodexError::NonZeroExit`] with stderr attached. Use
     /// [`AppServerCodegenRequest::prettier`] to format TypeScript output with a specific
     /// Prettier binary and request-level overrides for config/profile toggles.
@@ -31,6 +65,7 @@ impl CodexClient {
             path: out_dir.clone(),
             source,
         })?;
+        let existing = snapshot_files(&out_dir);
 
         let dir_ctx = self.directory_context()?;
         let resolved_overrides =
@@ -58,8 +93,10 @@ impl CodexClient {
         }
 
         self.command_env.apply(&mut command)?;
+        let command_line = crate::process::redacted_command_line(&command);
 
         let mut child = spawn_with_retry(&mut command, self.command_env.binary_path())?;
+        let pid = child.id();
 
         let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
         let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
@@ -68,8 +105,14 @@ impl CodexClient {
             stdout,
             ConsoleTarget::Stdout,
             self.mirror_stdout,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream_captured(
+            stderr,
+            ConsoleTarget::Stderr,
+            !self.quiet,
+            self.capture_options(),
         ));
-        let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !self.quiet));
 
         let wait_task = async move {
             let status = child
@@ -80,19 +123,20 @@ impl CodexClient {
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            let stderr_bytes = stderr_task
+            let stderr_capture = stderr_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            Ok::<_, CodexError>((status, stdout_bytes, stderr_bytes))
+            Ok::<_, CodexError>((status, stdout_bytes, stderr_capture))
         };
 
-        let (status, stdout_bytes, stderr_bytes) = if self.timeout.is_zero() {
+        let (status, stdout_bytes, stderr_capture) = if self.timeout.is_zero() {
             wait_task.await?
         } else {
-            match time::timeout(self.timeout, wait_task).await {
-                Ok(result) => result?,
-                Err(_) => {
+            match timeout_with_shutdown(self.timeout, wait_task, pid, self.graceful_shutdown).await
+            {
+                Some(result) => result?,
+                None => {
                     return Err(CodexError::Timeout {
                         timeout: self.timeout,
                     });
@@ -102,16 +146,65 @@ impl CodexClient {
 
         if !status.success() {
             return Err(CodexError::NonZeroExit {
+                command: command_line,
+                exit_code: status.code(),
                 status,
-                stderr: String::from_utf8(stderr_bytes)?,
+                stderr: String::from_utf8(stderr_capture.bytes)?,
+                truncated: stderr_capture.truncated,
             });
         }
 
         Ok(AppServerCodegenOutput {
             status,
             stdout: String::from_utf8(stdout_bytes)?,
-            stderr: String::from_utf8(stderr_bytes)?,
+            stderr: String::from_utf8(stderr_capture.bytes)?,
+            files: changed_files(&existing, snapshot_files(&out_dir)),
             out_dir,
         })
     }
 }
+
+type FileStamp = (u64, Option<SystemTime>);
+
+/// Size and modification time of every non-directory entry under `root`.
+///
+/// Uses the entries' own file types, so symlinks are recorded but never followed.
+fn snapshot_files(root: &Path) -> HashMap<PathBuf, FileStamp> {
+    let mut files = HashMap::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std_fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(entry.path());
+            } else {
+                let stamp = entry
+                    .metadata()
+                    .map(|metadata| (metadata.len(), metadata.modified().ok()))
+                    .unwrap_or_default();
+                files.insert(entry.path(), stamp);
+            }
+        }
+    }
+    files
+}
+
+/// Paths in `after` that are new or whose size or modification time differs from `before`,
+/// sorted.
+fn changed_files(
+    before: &HashMap<PathBuf, FileStamp>,
+    after: HashMap<PathBuf, FileStamp>,
+) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = after
+        .into_iter()
+        .filter(|(path, stamp)| before.get(path) != Some(stamp))
+        .map(|(path, _)| path)
+        .collect();
+    files.sort();
+    files
+}
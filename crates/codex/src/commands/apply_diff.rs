@@ -1,11 +1,18 @@
-use std::{env, ffi::OsString};
+use std::{
+    env,
+    ffi::OsString,
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+};
 
-use tokio::{process::Command, time};
+use tokio::{fs, process::Command};
 
 use crate::{
+    apply_diff::split_unified_diff,
     builder::{apply_cli_overrides, resolve_cli_overrides},
-    process::{spawn_with_retry, tee_stream, ConsoleTarget},
-    ApplyDiffArtifacts, CliOverridesPatch, CodexClient, CodexError,
+    process::{spawn_with_retry, tee_stream_captured, timeout_with_shutdown, ConsoleTarget},
+    ApplyDiffArtifacts, ApplyReport, CliOverridesPatch, CodexClient, CodexError, FileChangeKind,
+    FileChangeState,
 };
 
 impl CodexClient {
@@ -13,7 +20,8 @@ impl CodexClient {
     ///
     /// Stdout mirrors to the console when `mirror_stdout` is enabled; stderr mirrors unless `quiet`
     /// is set. Output and exit status are always captured and returned, and `RUST_LOG=error` is
-    /// injected for the child process when the environment variable is unset.
+    /// injected for the child process when the environment variable is unset. Files that failed
+    /// to patch are listed in [`ApplyDiffArtifacts::conflicts`].
     ///
     /// Convenience behavior: if `CODEX_TASK_ID` is set, it is appended as `<TASK_ID>`. When the
     /// environment variable is missing, the subprocess is still spawned and will typically exit
@@ -36,6 +44,106 @@ impl CodexClient {
         self.apply_task_inner(Some(OsString::from(task_id))).await
     }
 
+    /// Applies a unified diff file to the working tree with `git apply`.
+    ///
+    /// `codex apply` only accepts Codex Cloud task ids, so saved or externally produced patches
+    /// are applied locally instead. Runs in the builder working directory (or the current
+    /// directory when none is set); output, exit status, and conflicts are captured the same way
+    /// as [`CodexClient::apply`]. A missing file returns [`CodexError::PatchNotFound`] without
+    /// spawning `git`.
+    pub async fn apply_patch(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<ApplyDiffArtifacts, CodexError> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(CodexError::PatchNotFound {
+                path: path.to_path_buf(),
+            });
+        }
+        let cwd = env::current_dir().map_err(|source| CodexError::WorkingDirectory { source })?;
+        let mut command = Command::new("git");
+        command
+            .args(["apply", "--verbose"])
+            .arg(cwd.join(path))
+            .current_dir(self.working_tree()?);
+        self.capture_command(command, Path::new("git"))
+            .await
+            .map(ApplyDiffArtifacts::with_detected_conflicts)
+    }
+
+    /// Backs up the files a Codex Cloud task will modify, then applies that task.
+    ///
+    /// The task id comes from `CODEX_TASK_ID` as for [`Self::apply`]; without it this returns
+    /// [`CodexError::EmptyTaskId`]. The affected paths are read from `codex cloud diff <TASK_ID>`,
+    /// the same diff `codex apply <TASK_ID>` applies, and each existing file is copied into
+    /// `backup_dir` under its path relative to the builder working directory (or the current
+    /// directory when none is set). Files the diff creates are skipped. A diff path that is
+    /// absolute or climbs out with `..` fails with [`CodexError::BackupFile`] before anything is
+    /// copied or applied. Backups are left in place whatever the outcome, so a failed or
+    /// conflicting apply can be undone by hand.
+    pub async fn apply_with_backup(
+        &self,
+        backup_dir: impl Into<PathBuf>,
+    ) -> Result<ApplyReport, CodexError> {
+        let backup_dir = backup_dir.into();
+        let task_id = env::var_os("CODEX_TASK_ID")
+            .and_then(|v| crate::normalize_non_empty(&v.to_string_lossy()).map(OsString::from))
+            .ok_or(CodexError::EmptyTaskId)?;
+        let base = self.working_tree()?;
+
+        let diff = self.cloud_diff_task_inner(Some(task_id.clone())).await?;
+        if !diff.status.success() {
+            return Err(CodexError::NonZeroExit {
+                command: diff.command,
+                exit_code: diff.status.code(),
+                status: diff.status,
+                stderr: diff.stderr,
+                truncated: diff.truncated,
+            });
+        }
+        let mut relatives = Vec::new();
+        for path in split_unified_diff(&diff.stdout)
+            .into_iter()
+            .filter_map(|file| file.old_path)
+        {
+            let relative = contained_path(&path).ok_or_else(|| CodexError::BackupFile {
+                path: path.clone(),
+                source: std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "path is outside the working directory",
+                ),
+            })?;
+            if !relatives.contains(&relative) {
+                relatives.push(relative);
+            }
+        }
+
+        let mut backed_up = Vec::new();
+        for relative in relatives {
+            let original = base.join(&relative);
+            if !original.is_file() {
+                continue;
+            }
+            let target = backup_dir.join(&relative);
+            let backup_error = |source| CodexError::BackupFile {
+                path: original.clone(),
+                source,
+            };
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).await.map_err(backup_error)?;
+            }
+            fs::copy(&original, &target).await.map_err(backup_error)?;
+            backed_up.push(target);
+        }
+
+        let artifacts = self.apply_task_inner(Some(task_id)).await?;
+        Ok(ApplyReport {
+            artifacts,
+            backed_up,
+        })
+    }
+
     /// Shows a Codex Cloud task diff by invoking `codex cloud diff <TASK_ID>`.
     ///
     /// Mirrors stdout/stderr using the same `mirror_stdout`/`quiet` defaults as `apply`, but always
@@ -51,6 +159,46 @@ impl CodexClient {
         self.cloud_diff_task_inner(task_id).await
     }
 
+    /// Lists uncommitted changes in the working tree as typed file changes.
+    ///
+    /// The CLI has no local diff command, so this runs `git diff HEAD` in the builder working
+    /// directory (or the current directory when none is set) and returns one
+    /// [`FileChangeState`] per file with [`FileChangeKind::Diff`] and that file's section of the
+    /// unified diff, in `git diff` order. Staged and unstaged edits are included; untracked
+    /// files are not. A non-zero `git` exit (for example outside a repository or before the
+    /// first commit) returns [`CodexError::NonZeroExit`].
+    pub async fn diff_events(&self) -> Result<Vec<FileChangeState>, CodexError> {
+        let mut command = Command::new("git");
+        command
+            .args(["diff", "--no-color", "--no-ext-diff", "HEAD"])
+            .current_dir(self.working_tree()?);
+        let artifacts = self.capture_command(command, Path::new("git")).await?;
+        if !artifacts.status.success() {
+            return Err(CodexError::NonZeroExit {
+                command: artifacts.command,
+                exit_code: artifacts.status.code(),
+                status: artifacts.status,
+                stderr: artifacts.stderr,
+                truncated: artifacts.truncated,
+            });
+        }
+
+        Ok(split_unified_diff(&artifacts.stdout)
+            .into_iter()
+            .filter_map(|file| {
+                Some(FileChangeState {
+                    path: file.new_path.or(file.old_path)?,
+                    change: Some(FileChangeKind::Diff),
+                    diff: Some(file.text),
+                    exit_code: None,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    extra: Default::default(),
+                })
+            })
+            .collect())
+    }
+
     /// Shows a Codex Cloud task diff by task id via `codex cloud diff <TASK_ID>`.
     pub async fn cloud_diff_task(
         &self,
@@ -72,7 +220,9 @@ impl CodexClient {
         if let Some(task_id) = task_id {
             args.push(task_id);
         }
-        self.capture_codex_command(args, false).await
+        self.capture_codex_command(args, false)
+            .await
+            .map(ApplyDiffArtifacts::with_detected_conflicts)
     }
 
     async fn cloud_diff_task_inner(
@@ -99,50 +249,78 @@ impl CodexClient {
         );
 
         let mut command = Command::new(self.command_env.binary_path());
-        command
-            .args(&args)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .kill_on_drop(true)
-            .current_dir(dir_ctx.path());
+        command.args(&args).current_dir(dir_ctx.path());
 
         apply_cli_overrides(&mut command, &resolved_overrides, include_search);
         self.command_env.apply(&mut command)?;
+        self.capture_command(command, self.command_env.binary_path())
+            .await
+    }
 
-        let mut child = spawn_with_retry(&mut command, self.command_env.binary_path())?;
+    /// Directory local git operations run in: the builder working directory, or the current
+    /// directory when none is set.
+    fn working_tree(&self) -> Result<PathBuf, CodexError> {
+        match &self.working_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => env::current_dir().map_err(|source| CodexError::WorkingDirectory { source }),
+        }
+    }
+
+    /// Runs a prepared command with the client's mirroring, capture limits, and timeout.
+    async fn capture_command(
+        &self,
+        mut command: Command,
+        program: &Path,
+    ) -> Result<ApplyDiffArtifacts, CodexError> {
+        command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let command_line = crate::process::redacted_command_line(&command);
+
+        let _permit = self.acquire_process_slot().await;
+        let mut child = spawn_with_retry(&mut command, program)?;
+        let pid = child.id();
 
         let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
         let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
 
-        let stdout_task = tokio::spawn(tee_stream(
+        let stdout_task = tokio::spawn(tee_stream_captured(
             stdout,
             ConsoleTarget::Stdout,
             self.mirror_stdout,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream_captured(
+            stderr,
+            ConsoleTarget::Stderr,
+            !self.quiet,
+            self.capture_options(),
         ));
-        let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !self.quiet));
 
         let wait_task = async move {
             let status = child
                 .wait()
                 .await
                 .map_err(|source| CodexError::Wait { source })?;
-            let stdout_bytes = stdout_task
+            let stdout_capture = stdout_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            let stderr_bytes = stderr_task
+            let stderr_capture = stderr_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            Ok::<_, CodexError>((status, stdout_bytes, stderr_bytes))
+            Ok::<_, CodexError>((status, stdout_capture, stderr_capture))
         };
 
-        let (status, stdout_bytes, stderr_bytes) = if self.timeout.is_zero() {
+        let (status, stdout_capture, stderr_capture) = if self.timeout.is_zero() {
             wait_task.await?
         } else {
-            match time::timeout(self.timeout, wait_task).await {
-                Ok(result) => result?,
-                Err(_) => {
+            match timeout_with_shutdown(self.timeout, wait_task, pid, self.graceful_shutdown).await
+            {
+                Some(result) => result?,
+                None => {
                     return Err(CodexError::Timeout {
                         timeout: self.timeout,
                     });
@@ -151,9 +329,25 @@ impl CodexClient {
         };
 
         Ok(ApplyDiffArtifacts {
+            command: command_line,
             status,
-            stdout: String::from_utf8(stdout_bytes)?,
-            stderr: String::from_utf8(stderr_bytes)?,
+            stdout: String::from_utf8(stdout_capture.bytes)?,
+            stderr: String::from_utf8(stderr_capture.bytes)?,
+            truncated: stdout_capture.truncated || stderr_capture.truncated,
+            conflicts: Vec::new(),
         })
     }
 }
+
+/// `path` with `.` segments dropped, or `None` when it is absolute or contains `..`.
+fn contained_path(path: &Path) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
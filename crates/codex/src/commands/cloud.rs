@@ -1,8 +1,9 @@
 use std::ffi::OsString;
 
 use crate::{
-    ApplyDiffArtifacts, CloudApplyRequest, CloudDiffRequest, CloudExecRequest, CloudListOutput,
-    CloudListRequest, CloudOverviewRequest, CloudStatusRequest, CodexClient, CodexError,
+    ApplyDiffArtifacts, CloudApplyRequest, CloudDiffRequest, CloudExecOutput, CloudExecRequest,
+    CloudListOutput, CloudListRequest, CloudOverviewRequest, CloudStatusRequest, CodexClient,
+    CodexError,
 };
 
 impl CodexClient {
@@ -133,10 +134,12 @@ impl CodexClient {
     }
 
     /// Submits a new Codex Cloud task via `codex cloud exec`.
+    ///
+    /// The created task's URL and id are parsed from stdout when the CLI prints them.
     pub async fn cloud_exec(
         &self,
         request: CloudExecRequest,
-    ) -> Result<ApplyDiffArtifacts, CodexError> {
+    ) -> Result<CloudExecOutput, CodexError> {
         let env_id = request.env_id.trim();
         if env_id.is_empty() {
             return Err(CodexError::EmptyEnvId);
@@ -157,7 +160,13 @@ impl CodexClient {
             args.push(OsString::from(query));
         }
 
-        self.run_simple_command_with_overrides(args, request.overrides)
-            .await
+        let artifacts = self
+            .run_simple_command_with_overrides(args, request.overrides)
+            .await?;
+        Ok(CloudExecOutput::parse(
+            artifacts.status,
+            artifacts.stdout,
+            artifacts.stderr,
+        ))
     }
 }
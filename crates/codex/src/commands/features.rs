@@ -1,12 +1,15 @@
-use std::ffi::OsString;
+use std::{ffi::OsString, process::ExitStatus};
 
-use tokio::{process::Command, time};
+use tokio::process::Command;
 
 use crate::{
     builder::{apply_cli_overrides, resolve_cli_overrides},
-    process::{spawn_with_retry, tee_stream, ConsoleTarget},
-    ApplyDiffArtifacts, CodexClient, CodexError, FeaturesCommandRequest, FeaturesDisableRequest,
-    FeaturesEnableRequest, FeaturesListOutput, FeaturesListRequest,
+    process::{
+        spawn_with_retry, tee_stream, tee_stream_captured, timeout_with_shutdown, CapturedStream,
+        ConsoleTarget,
+    },
+    ApplyDiffArtifacts, CliOverridesPatch, CodexClient, CodexError, FeaturesCommandRequest,
+    FeaturesDisableRequest, FeaturesEnableRequest, FeaturesListOutput, FeaturesListRequest,
 };
 
 impl CodexClient {
@@ -55,19 +58,59 @@ impl CodexClient {
 
     /// Lists CLI features via `codex features list`.
     ///
-    /// Requests JSON output when `json(true)` is set and falls back to parsing the text table when
-    /// JSON is unavailable. Shared config/profile/search/approval overrides flow through via the
-    /// request/builder, stdout/stderr are mirrored according to the builder, and non-zero exits
-    /// surface as [`CodexError::NonZeroExit`].
+    /// Accepts a [`FeaturesListRequest`] or a bare [`FeaturesListFormat`]. Requests JSON output when
+    /// JSON is selected and falls back to parsing the text table when JSON is unavailable, including
+    /// binaries that reject `--json` outright (the command is retried without it). Shared
+    /// config/profile/search/approval overrides flow through via the request/builder, stdout/stderr
+    /// are mirrored according to the builder, and non-zero exits surface as
+    /// [`CodexError::NonZeroExit`].
     pub async fn list_features(
         &self,
-        request: FeaturesListRequest,
+        request: impl Into<FeaturesListRequest>,
     ) -> Result<FeaturesListOutput, CodexError> {
-        let FeaturesListRequest { json, overrides } = request;
+        let FeaturesListRequest { json, overrides } = request.into();
 
+        let mut output = self.run_features_list(json, &overrides).await?;
+        if json && rejected_json_flag(&output) {
+            output = self.run_features_list(false, &overrides).await?;
+        }
+        let (status, stdout_bytes, stderr_capture, command_line) = output;
+
+        if !status.success() {
+            return Err(CodexError::NonZeroExit {
+                command: command_line,
+                exit_code: status.code(),
+                status,
+                stderr: String::from_utf8(stderr_capture.bytes)?,
+                truncated: stderr_capture.truncated,
+            });
+        }
+
+        let stdout_string = String::from_utf8(stdout_bytes)?;
+        let stderr_string = String::from_utf8(stderr_capture.bytes)?;
+        let (features, format) = crate::version::parse_feature_list_output(&stdout_string, json)
+            .map_err(|reason| CodexError::FeatureListParse {
+                reason,
+                stdout: stdout_string.clone(),
+            })?;
+
+        Ok(FeaturesListOutput {
+            status,
+            stdout: stdout_string,
+            stderr: stderr_string,
+            features,
+            format,
+        })
+    }
+
+    async fn run_features_list(
+        &self,
+        json: bool,
+        overrides: &CliOverridesPatch,
+    ) -> Result<(ExitStatus, Vec<u8>, CapturedStream, String), CodexError> {
         let dir_ctx = self.directory_context()?;
         let resolved_overrides =
-            resolve_cli_overrides(&self.cli_overrides, &overrides, self.model.as_deref());
+            resolve_cli_overrides(&self.cli_overrides, overrides, self.model.as_deref());
 
         let mut command = Command::new(self.command_env.binary_path());
         command
@@ -85,8 +128,10 @@ impl CodexClient {
         }
 
         self.command_env.apply(&mut command)?;
+        let command_line = crate::process::redacted_command_line(&command);
 
         let mut child = spawn_with_retry(&mut command, self.command_env.binary_path())?;
+        let pid = child.id();
 
         let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
         let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
@@ -95,8 +140,14 @@ impl CodexClient {
             stdout,
             ConsoleTarget::Stdout,
             self.mirror_stdout,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream_captured(
+            stderr,
+            ConsoleTarget::Stderr,
+            !self.quiet,
+            self.capture_options(),
         ));
-        let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !self.quiet));
 
         let wait_task = async move {
             let status = child
@@ -107,47 +158,37 @@ impl CodexClient {
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            let stderr_bytes = stderr_task
+            let stderr_capture = stderr_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            Ok::<_, CodexError>((status, stdout_bytes, stderr_bytes))
+            Ok::<_, CodexError>((status, stdout_bytes, stderr_capture, command_line))
         };
 
-        let (status, stdout_bytes, stderr_bytes) = if self.timeout.is_zero() {
-            wait_task.await?
+        if self.timeout.is_zero() {
+            wait_task.await
         } else {
-            match time::timeout(self.timeout, wait_task).await {
-                Ok(result) => result?,
-                Err(_) => {
-                    return Err(CodexError::Timeout {
-                        timeout: self.timeout,
-                    });
-                }
+            match timeout_with_shutdown(self.timeout, wait_task, pid, self.graceful_shutdown).await
+            {
+                Some(result) => result,
+                None => Err(CodexError::Timeout {
+                    timeout: self.timeout,
+                }),
             }
-        };
-
-        if !status.success() {
-            return Err(CodexError::NonZeroExit {
-                status,
-                stderr: String::from_utf8(stderr_bytes)?,
-            });
         }
+    }
+}
 
-        let stdout_string = String::from_utf8(stdout_bytes)?;
-        let stderr_string = String::from_utf8(stderr_bytes)?;
-        let (features, format) = crate::version::parse_feature_list_output(&stdout_string, json)
-            .map_err(|reason| CodexError::FeatureListParse {
-                reason,
-                stdout: stdout_string.clone(),
-            })?;
-
-        Ok(FeaturesListOutput {
-            status,
-            stdout: stdout_string,
-            stderr: stderr_string,
-            features,
-            format,
-        })
+/// True when a failed `features list --json` run complained about the `--json` flag itself.
+fn rejected_json_flag(
+    (status, _, stderr, _): &(ExitStatus, Vec<u8>, CapturedStream, String),
+) -> bool {
+    if status.success() {
+        return false;
     }
+    let stderr = String::from_utf8_lossy(&stderr.bytes).to_ascii_lowercase();
+    stderr.contains("--json")
+        && (stderr.contains("unexpected argument")
+            || stderr.contains("unrecognized")
+            || stderr.contains("unknown"))
 }
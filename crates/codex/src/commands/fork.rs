@@ -1,13 +1,22 @@
-use std::ffi::OsString;
+use std::{ffi::OsString, path::PathBuf};
 
-use crate::{ApplyDiffArtifacts, CodexClient, CodexError, ForkSessionRequest};
+use crate::{
+    defaults::CODEX_HOME_ENV, find_rollout_file_by_id, CodexClient, CodexError, ForkSessionRequest,
+    SessionHandle,
+};
 
 impl CodexClient {
-    /// Runs `codex fork [OPTIONS] [SESSION_ID] [PROMPT]` and returns captured output.
+    /// Runs `codex fork [OPTIONS] [SESSION_ID] [PROMPT]` and returns the new session.
+    ///
+    /// When a session id is given, a rollout with exactly that id must exist under the resolved
+    /// `CODEX_HOME` (builder home, then the `CODEX_HOME` env var, then `~/.codex`); otherwise
+    /// [`CodexError::SessionNotFound`] is returned before spawning. The lookup walks the session
+    /// tree on the blocking pool. The forked thread id is
+    /// parsed from the CLI output into [`SessionHandle::thread_id`].
     pub async fn fork_session(
         &self,
         request: ForkSessionRequest,
-    ) -> Result<ApplyDiffArtifacts, CodexError> {
+    ) -> Result<SessionHandle, CodexError> {
         if matches!(request.prompt.as_deref(), Some(prompt) if prompt.trim().is_empty()) {
             return Err(CodexError::EmptyPrompt);
         }
@@ -20,7 +29,20 @@ impl CodexClient {
             args.push(OsString::from("--last"));
         }
         if let Some(session_id) = request.session_id {
-            if !session_id.trim().is_empty() {
+            let session_id = session_id.trim();
+            if !session_id.is_empty() {
+                if let Some(codex_home) = self.resolved_codex_home() {
+                    let (root, id) = (codex_home.clone(), session_id.to_string());
+                    let found =
+                        tokio::task::spawn_blocking(move || find_rollout_file_by_id(root, &id))
+                            .await?;
+                    if found.is_none() {
+                        return Err(CodexError::SessionNotFound {
+                            session_id: session_id.to_string(),
+                            codex_home,
+                        });
+                    }
+                }
                 args.push(OsString::from(session_id));
             }
         }
@@ -30,7 +52,25 @@ impl CodexClient {
             }
         }
 
-        self.run_simple_command_with_overrides(args, request.overrides)
-            .await
+        let artifacts = self
+            .run_simple_command_with_overrides(args, request.overrides)
+            .await?;
+        Ok(SessionHandle::parse(
+            artifacts.status,
+            artifacts.stdout,
+            artifacts.stderr,
+        ))
+    }
+
+    fn resolved_codex_home(&self) -> Option<PathBuf> {
+        if let Some(layout) = self.codex_home_layout() {
+            return Some(layout.root().to_path_buf());
+        }
+        if let Some(home) = std::env::var_os(CODEX_HOME_ENV).filter(|home| !home.is_empty()) {
+            return Some(PathBuf::from(home));
+        }
+        std::env::var_os("HOME")
+            .filter(|home| !home.is_empty())
+            .map(|home| PathBuf::from(home).join(".codex"))
     }
 }
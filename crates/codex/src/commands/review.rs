@@ -1,20 +1,38 @@
 use std::ffi::OsString;
 
 use crate::{
+    capabilities::{guard_is_supported, CapabilityFeature},
     ApplyDiffArtifacts, CodexClient, CodexError, ExecReviewCommandRequest, ReviewCommandRequest,
+    ReviewOutcome,
 };
 
 impl CodexClient {
-    /// Runs `codex review [OPTIONS] [PROMPT]` and returns captured output.
-    pub async fn review(
-        &self,
-        request: ReviewCommandRequest,
-    ) -> Result<ApplyDiffArtifacts, CodexError> {
+    /// Runs `codex review [OPTIONS] [PROMPT]` and returns captured output plus parsed findings.
+    ///
+    /// Top-level `review` has no `--json` flag, so when the probed binary confirms
+    /// `codex exec --json` support the review runs as `codex exec review --json` instead; otherwise
+    /// the text `Review comment:` block is scraped. JSON runs without a recognizable review payload
+    /// also fall back to text parsing. Non-zero exits surface as [`CodexError::NonZeroExit`].
+    pub async fn review(&self, request: ReviewCommandRequest) -> Result<ReviewOutcome, CodexError> {
         if matches!(request.prompt.as_deref(), Some(prompt) if prompt.trim().is_empty()) {
             return Err(CodexError::EmptyPrompt);
         }
 
-        let mut args = vec![OsString::from("review")];
+        let json = guard_is_supported(
+            &self
+                .probe_capabilities()
+                .await
+                .guard_feature(CapabilityFeature::JsonOutput),
+        );
+        let mut args = if json {
+            vec![
+                OsString::from("exec"),
+                OsString::from("review"),
+                OsString::from("--json"),
+            ]
+        } else {
+            vec![OsString::from("review")]
+        };
         if let Some(base) = request.base {
             if !base.trim().is_empty() {
                 args.push(OsString::from("--base"));
@@ -42,8 +60,15 @@ impl CodexClient {
             }
         }
 
-        self.run_simple_command_with_overrides(args, request.overrides)
-            .await
+        let artifacts = self
+            .run_simple_command_with_overrides(args, request.overrides)
+            .await?;
+        Ok(ReviewOutcome::parse(
+            artifacts.status,
+            artifacts.stdout,
+            artifacts.stderr,
+            json,
+        ))
     }
 
     /// Runs `codex exec review [OPTIONS] [PROMPT]` and returns captured output.
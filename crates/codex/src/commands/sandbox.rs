@@ -1,8 +1,9 @@
-use tokio::{process::Command, time};
+use tokio::process::Command;
 
 use crate::{
-    process::{spawn_with_retry, tee_stream, ConsoleTarget},
-    CodexClient, CodexError, SandboxCommandRequest, SandboxPlatform, SandboxRun, StdioToUdsRequest,
+    process::{spawn_with_retry, tee_stream, timeout_with_shutdown, ConsoleTarget},
+    CodexClient, CodexError, SandboxCommandRequest, SandboxDecision, SandboxDenial,
+    SandboxPlatform, SandboxRun, StdioToUdsRequest, UdsBridgeHandle,
 };
 
 impl CodexClient {
@@ -39,6 +40,31 @@ impl CodexClient {
         spawn_with_retry(&mut command, self.command_env.binary_path())
     }
 
+    /// Launches `codex stdio-to-uds <SOCKET_PATH>` and returns a [`UdsBridgeHandle`].
+    ///
+    /// The handle exposes the socket path (resolved against the request/builder working
+    /// directory when relative) and kills the bridge when dropped. Returns
+    /// [`CodexError::UnsupportedPlatform`] on non-Unix targets.
+    pub fn bridge_stdio_to_uds(
+        &self,
+        request: StdioToUdsRequest,
+    ) -> Result<UdsBridgeHandle, CodexError> {
+        if !cfg!(unix) {
+            return Err(CodexError::UnsupportedPlatform {
+                feature: "codex stdio-to-uds",
+            });
+        }
+
+        let socket_path = if request.socket_path.is_absolute() {
+            request.socket_path.clone()
+        } else {
+            self.sandbox_working_dir(request.working_dir.clone())?
+                .join(&request.socket_path)
+        };
+        let child = self.stdio_to_uds(request)?;
+        Ok(UdsBridgeHandle { child, socket_path })
+    }
+
     /// Runs `codex sandbox <platform> [--full-auto|--log-denials] [--config/--enable/--disable] -- <COMMAND...>`.
     ///
     /// Captures stdout/stderr and mirrors them according to the builder (`mirror_stdout` / `quiet`). Unlike
@@ -105,6 +131,7 @@ impl CodexClient {
         self.command_env.apply(&mut process)?;
 
         let mut child = spawn_with_retry(&mut process, self.command_env.binary_path())?;
+        let pid = child.id();
 
         let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
         let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
@@ -113,8 +140,14 @@ impl CodexClient {
             stdout,
             ConsoleTarget::Stdout,
             self.mirror_stdout,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream(
+            stderr,
+            ConsoleTarget::Stderr,
+            !self.quiet,
+            self.capture_options(),
         ));
-        let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !self.quiet));
 
         let wait_task = async move {
             let status = child
@@ -135,9 +168,10 @@ impl CodexClient {
         let (status, stdout_bytes, stderr_bytes) = if self.timeout.is_zero() {
             wait_task.await?
         } else {
-            match time::timeout(self.timeout, wait_task).await {
-                Ok(result) => result?,
-                Err(_) => {
+            match timeout_with_shutdown(self.timeout, wait_task, pid, self.graceful_shutdown).await
+            {
+                Some(result) => result?,
+                None => {
                     return Err(CodexError::Timeout {
                         timeout: self.timeout,
                     });
@@ -145,10 +179,32 @@ impl CodexClient {
             }
         };
 
+        let stderr = String::from_utf8(stderr_bytes)?;
+        let denials = SandboxDenial::parse_all(&stderr);
         Ok(SandboxRun {
             status,
             stdout: String::from_utf8(stdout_bytes)?,
-            stderr: String::from_utf8(stderr_bytes)?,
+            stderr,
+            decision: SandboxDecision::from_run(status, &denials),
+            denials,
         })
     }
+
+    /// Executes `command` under `codex sandbox <platform>` with denial logging and classifies the
+    /// outcome.
+    ///
+    /// This is a real run, not a dry-run policy check: the command executes with whatever effects
+    /// the sandbox allows. Use [`Self::check_execpolicy`] to evaluate a command against policy
+    /// rules without running it. The returned
+    /// [`SandboxRun::decision`] is [`SandboxDecision::Denied`] when the sandbox reported blocked
+    /// operations (listed in [`SandboxRun::denials`]); only macOS emits denial logs, so on other
+    /// platforms a blocked command surfaces as [`SandboxDecision::Failed`].
+    pub async fn run_sandbox_with_denials(
+        &self,
+        command: Vec<String>,
+        platform: SandboxPlatform,
+    ) -> Result<SandboxRun, CodexError> {
+        self.run_sandbox(SandboxCommandRequest::new(platform, command).log_denials(true))
+            .await
+    }
 }
@@ -1,6 +1,9 @@
 use std::{env, path::PathBuf, time::Duration};
 
 pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+pub(crate) const DEFAULT_IO_CHUNK_SIZE: usize = 4096;
+pub(crate) const MIN_IO_CHUNK_SIZE: usize = 1024;
+pub(crate) const DEFAULT_MAX_ATTACHMENT_BYTES: u64 = 256 * 1024;
 pub(crate) const CODEX_BINARY_ENV: &str = "CODEX_BINARY";
 pub(crate) const CODEX_HOME_ENV: &str = "CODEX_HOME";
 pub(crate) const RUST_LOG_ENV: &str = "RUST_LOG";
@@ -18,8 +18,28 @@ pub enum CodexError {
     },
     #[error("codex exceeded timeout of {timeout:?}")]
     Timeout { timeout: Duration },
-    #[error("codex exited with {status:?}: {stderr}")]
-    NonZeroExit { status: ExitStatus, stderr: String },
+    #[error("`{command}` exited with {}: {stderr}", exit_label(*.exit_code, .status))]
+    NonZeroExit {
+        /// Command line that ran, with credentials and long arguments redacted.
+        command: String,
+        /// Exit code, or `None` when the process was terminated by a signal.
+        exit_code: Option<i32>,
+        status: ExitStatus,
+        stderr: String,
+        /// True when `stderr` was cut off by [`crate::CodexClientBuilder::max_output_bytes`].
+        truncated: bool,
+    },
+    #[error("`{command}` printed more than {limit} bytes to stdout; the output was truncated")]
+    OutputTruncated {
+        /// Command line that ran, with credentials and long arguments redacted.
+        command: String,
+        /// The [`crate::CodexClientBuilder::max_output_bytes`] cap that was exceeded.
+        limit: usize,
+    },
+    /// A streamed `codex exec --json` run failed; the original parse, idle-timeout, or
+    /// cancellation error is kept so callers can still match on it.
+    #[error("codex exec stream failed: {0}")]
+    Stream(#[source] Box<crate::ExecStreamError>),
     #[error("codex output was not valid UTF-8: {0}")]
     InvalidUtf8(#[from] std::string::FromUtf8Error),
     #[error("failed to parse {context} JSON output: {source}")]
@@ -29,6 +49,8 @@ pub enum CodexError {
         #[source]
         source: serde_json::Error,
     },
+    #[error("`codex exec --json` printed no `thread.started` event; the binary likely predates JSONL output")]
+    JsonModeUnsupported { stdout: String },
     #[error("failed to parse execpolicy JSON output: {source}")]
     ExecPolicyParse {
         stdout: String,
@@ -69,6 +91,21 @@ pub enum CodexError {
     EmptyMcpUrl,
     #[error("socket path must not be empty")]
     EmptySocketPath,
+    #[error("patch file `{path}` not found")]
+    PatchNotFound { path: PathBuf },
+    #[error("attachment `{path}` not found")]
+    AttachmentNotFound { path: PathBuf },
+    #[error("session `{session_id}` has no rollout under `{}`", codex_home.display())]
+    SessionNotFound {
+        session_id: String,
+        codex_home: PathBuf,
+    },
+    #[error("attachment `{path}` is {size} bytes, over the {limit}-byte limit")]
+    AttachmentTooLarge {
+        path: PathBuf,
+        size: u64,
+        limit: u64,
+    },
     #[error("failed to create temporary working directory: {0}")]
     TempDir(#[source] std::io::Error),
     #[error("failed to resolve working directory: {source}")]
@@ -82,6 +119,24 @@ pub enum CodexError {
         #[source]
         source: std::io::Error,
     },
+    #[error("failed to prepare artifact directory `{path}`: {source}")]
+    PrepareArtifactDir {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to run `git init` in `{path}`: {source}")]
+    GitInit {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to back up `{path}` before applying: {source}")]
+    BackupFile {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
     #[error("failed to prepare CODEX_HOME at `{path}`: {source}")]
     PrepareCodexHome {
         path: PathBuf,
@@ -100,4 +155,23 @@ pub enum CodexError {
     StdinWrite(#[source] std::io::Error),
     #[error("failed to join codex output task: {0}")]
     Join(#[from] tokio::task::JoinError),
+    #[error("codex binary does not support {feature}")]
+    UnsupportedCapability { feature: &'static str },
+    #[error("{feature} is not supported on this platform")]
+    UnsupportedPlatform { feature: &'static str },
+    #[error("failed to parse structured output as {expected}: {source}")]
+    StructuredOutput {
+        expected: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error(transparent)]
+    Template(#[from] crate::TemplateError),
+}
+
+fn exit_label(exit_code: Option<i32>, status: &ExitStatus) -> String {
+    match exit_code {
+        Some(code) => format!("exit code {code}"),
+        None => format!("{status} (no exit code)"),
+    }
 }
@@ -1,7 +1,13 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::ansi::strip_ansi;
 
 /// Single JSONL event emitted by `codex exec --json`.
 ///
@@ -14,8 +20,11 @@ use std::path::PathBuf;
 /// Item variants mirror the upstream `item_type` field: `agent_message`, `reasoning`,
 /// `command_execution`, `file_change`, `mcp_tool_call`, `web_search`, `todo_list`, and `error`.
 /// Unknown or future fields are preserved in `extra` maps to keep the parser forward-compatible.
+/// Wrapper-synthesized variants serialize for logging but are never produced by deserializing CLI
+/// output.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
+#[non_exhaustive]
 pub enum ThreadEvent {
     #[serde(rename = "thread.started", alias = "thread.resumed")]
     ThreadStarted(ThreadStarted),
@@ -35,6 +44,422 @@ pub enum ThreadEvent {
     ItemFailed(ItemEnvelope<ItemFailure>),
     #[serde(rename = "error")]
     Error(EventError),
+    /// Synthetic end-of-stream summary emitted by the wrapper when
+    /// [`crate::ExecStreamRequest::emit_summary`] is set; never produced by the CLI itself.
+    #[serde(rename = "thread.completed", skip_deserializing)]
+    ThreadCompleted(ThreadSummary),
+    /// Synthetic keep-alive emitted by the wrapper when [`crate::ExecStreamRequest::heartbeat`]
+    /// is set and no CLI event arrived for an interval; never produced by the CLI itself.
+    #[serde(rename = "wrapper.heartbeat", skip_deserializing)]
+    Heartbeat {
+        /// Time since the last CLI event, or since the stream started.
+        elapsed: Duration,
+    },
+    /// Synthetic marker emitted by the wrapper when [`crate::ExecStreamRequest::auto_resume`]
+    /// reconnected to the thread after the CLI exited mid-turn; the resumed run's events follow.
+    #[serde(rename = "wrapper.resumed", skip_deserializing)]
+    Resumed {
+        /// Thread continued via `codex exec resume`.
+        thread_id: String,
+        /// Reconnection number for this stream, starting at 1.
+        attempt: u32,
+    },
+}
+
+impl ThreadEvent {
+    /// Parses a single JSONL line (for example from a saved `json_event_log`) into a typed event.
+    ///
+    /// Lines are decoded as-is without thread/turn inference; use
+    /// [`crate::JsonlThreadEventParser`] when replaying raw CLI output that omits ids.
+    pub fn parse_line(line: &str) -> Result<Self, LineParseError> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        serde_json::from_str(line).map_err(|source| LineParseError {
+            line: line.to_string(),
+            message: source.to_string(),
+        })
+    }
+
+    /// Thread the event belongs to; `None` for `error` events, heartbeats, and summaries that
+    /// never saw a `thread.started`.
+    pub fn thread_id(&self) -> Option<&str> {
+        match self {
+            ThreadEvent::ThreadStarted(event) => Some(&event.thread_id),
+            ThreadEvent::TurnStarted(event) => Some(&event.thread_id),
+            ThreadEvent::TurnCompleted(event) => Some(&event.thread_id),
+            ThreadEvent::TurnFailed(event) => Some(&event.thread_id),
+            ThreadEvent::ItemStarted(event) | ThreadEvent::ItemCompleted(event) => {
+                Some(&event.thread_id)
+            }
+            ThreadEvent::ItemDelta(event) => Some(&event.thread_id),
+            ThreadEvent::ItemFailed(event) => Some(&event.thread_id),
+            ThreadEvent::ThreadCompleted(summary) => summary.thread_id.as_deref(),
+            ThreadEvent::Resumed { thread_id, .. } => Some(thread_id),
+            ThreadEvent::Error(_) | ThreadEvent::Heartbeat { .. } => None,
+        }
+    }
+
+    /// Turn the event belongs to; `None` for thread-level events, `error` events, and
+    /// wrapper-synthesized events.
+    pub fn turn_id(&self) -> Option<&str> {
+        match self {
+            ThreadEvent::TurnStarted(event) => Some(&event.turn_id),
+            ThreadEvent::TurnCompleted(event) => Some(&event.turn_id),
+            ThreadEvent::TurnFailed(event) => Some(&event.turn_id),
+            ThreadEvent::ItemStarted(event) | ThreadEvent::ItemCompleted(event) => {
+                Some(&event.turn_id)
+            }
+            ThreadEvent::ItemDelta(event) => Some(&event.turn_id),
+            ThreadEvent::ItemFailed(event) => Some(&event.turn_id),
+            ThreadEvent::ThreadStarted(_)
+            | ThreadEvent::ThreadCompleted(_)
+            | ThreadEvent::Error(_)
+            | ThreadEvent::Heartbeat { .. }
+            | ThreadEvent::Resumed { .. } => None,
+        }
+    }
+}
+
+impl ThreadEvent {
+    fn ids_mut(&mut self) -> (Option<&mut String>, Option<&mut String>) {
+        match self {
+            ThreadEvent::ThreadStarted(event) => (Some(&mut event.thread_id), None),
+            ThreadEvent::TurnStarted(event) => {
+                (Some(&mut event.thread_id), Some(&mut event.turn_id))
+            }
+            ThreadEvent::TurnCompleted(event) => {
+                (Some(&mut event.thread_id), Some(&mut event.turn_id))
+            }
+            ThreadEvent::TurnFailed(event) => {
+                (Some(&mut event.thread_id), Some(&mut event.turn_id))
+            }
+            ThreadEvent::ItemStarted(event) | ThreadEvent::ItemCompleted(event) => {
+                (Some(&mut event.thread_id), Some(&mut event.turn_id))
+            }
+            ThreadEvent::ItemDelta(event) => (Some(&mut event.thread_id), Some(&mut event.turn_id)),
+            ThreadEvent::ItemFailed(event) => {
+                (Some(&mut event.thread_id), Some(&mut event.turn_id))
+            }
+            ThreadEvent::ThreadCompleted(summary) => (summary.thread_id.as_mut(), None),
+            ThreadEvent::Resumed { thread_id, .. } => (Some(thread_id), None),
+            ThreadEvent::Error(_) | ThreadEvent::Heartbeat { .. } => (None, None),
+        }
+    }
+}
+
+/// Rewrites `thread_id`/`turn_id` values across an event stream to stable, sequential ids.
+///
+/// The first thread id seen becomes `thread-1`, the next `thread-2`, and so on (turns likewise
+/// use `turn-N`); every later occurrence of the same upstream id maps to the same replacement.
+/// Useful for deterministic snapshot tests of event sequences, or for mapping server-assigned
+/// ids onto a host's own naming via [`IdRemapper::with_prefixes`].
+#[derive(Clone, Debug)]
+pub struct IdRemapper {
+    thread_prefix: String,
+    turn_prefix: String,
+    threads: HashMap<String, String>,
+    turns: HashMap<String, String>,
+}
+
+impl IdRemapper {
+    pub fn new() -> Self {
+        Self::with_prefixes("thread-", "turn-")
+    }
+
+    /// Uses custom prefixes for generated ids (e.g. `"t"` yields `t1`, `t2`, ...).
+    pub fn with_prefixes(thread_prefix: impl Into<String>, turn_prefix: impl Into<String>) -> Self {
+        Self {
+            thread_prefix: thread_prefix.into(),
+            turn_prefix: turn_prefix.into(),
+            threads: HashMap::new(),
+            turns: HashMap::new(),
+        }
+    }
+
+    /// Rewrites the ids of a single event in place.
+    pub fn remap_in_place(&mut self, event: &mut ThreadEvent) {
+        let (thread_id, turn_id) = event.ids_mut();
+        if let Some(thread_id) = thread_id {
+            *thread_id = Self::assign(&mut self.threads, &self.thread_prefix, thread_id);
+        }
+        if let Some(turn_id) = turn_id {
+            *turn_id = Self::assign(&mut self.turns, &self.turn_prefix, turn_id);
+        }
+    }
+
+    /// Returns `event` with its ids rewritten.
+    pub fn remap(&mut self, mut event: ThreadEvent) -> ThreadEvent {
+        self.remap_in_place(&mut event);
+        event
+    }
+
+    /// Replacement assigned to an upstream thread id, if it has been seen.
+    pub fn thread_id(&self, original: &str) -> Option<&str> {
+        self.threads.get(original).map(String::as_str)
+    }
+
+    /// Replacement assigned to an upstream turn id, if it has been seen.
+    pub fn turn_id(&self, original: &str) -> Option<&str> {
+        self.turns.get(original).map(String::as_str)
+    }
+
+    fn assign(ids: &mut HashMap<String, String>, prefix: &str, original: &str) -> String {
+        let next = ids.len() + 1;
+        ids.entry(original.to_string())
+            .or_insert_with(|| format!("{prefix}{next}"))
+            .clone()
+    }
+}
+
+impl Default for IdRemapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryFrom<&str> for ThreadEvent {
+    type Error = LineParseError;
+
+    fn try_from(line: &str) -> Result<Self, LineParseError> {
+        Self::parse_line(line)
+    }
+}
+
+/// Characters of free text kept by [`ThreadEvent`]'s `Display` impl unless a precision is given.
+pub const DISPLAY_TEXT_LIMIT: usize = 80;
+
+/// One human-readable line per event for quick logging, e.g. `[turn-1] agent_message: "Hello"`
+/// or `[turn-1] command_execution exit=0: cargo build`.
+///
+/// Lines are prefixed with the turn id (or the thread id for thread-level events). Free text is
+/// cut to [`DISPLAY_TEXT_LIMIT`] characters, or to the formatter precision (`{event:.20}`), and
+/// truncated text ends with `…` followed by its original length. Use the JSON form when the
+/// full payload matters.
+impl fmt::Display for ThreadEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = f.precision().unwrap_or(DISPLAY_TEXT_LIMIT);
+        if let Some(id) = self.turn_id().or_else(|| self.thread_id()) {
+            write!(f, "[{id}] ")?;
+        }
+        match self {
+            ThreadEvent::ThreadStarted(_) => f.write_str("thread.started"),
+            ThreadEvent::TurnStarted(event) => match &event.input_text {
+                Some(input) => write!(f, "turn.started: {:?}", clip(input, limit)),
+                None => f.write_str("turn.started"),
+            },
+            ThreadEvent::TurnCompleted(_) => f.write_str("turn.completed"),
+            ThreadEvent::TurnFailed(event) => {
+                write!(f, "turn.failed: {}", clip(&event.error.message, limit))
+            }
+            ThreadEvent::ItemStarted(event) => {
+                fmt_item_payload(f, &event.item.payload, " started", limit)
+            }
+            ThreadEvent::ItemCompleted(event) => {
+                fmt_item_payload(f, &event.item.payload, "", limit)
+            }
+            ThreadEvent::ItemDelta(event) => fmt_item_delta(f, &event.delta, limit),
+            ThreadEvent::ItemFailed(event) => write!(
+                f,
+                "item.failed {}: {}",
+                event.item.item_id,
+                clip(&event.item.error.message, limit)
+            ),
+            ThreadEvent::Error(error) => write!(f, "error: {}", clip(&error.message, limit)),
+            ThreadEvent::ThreadCompleted(summary) => write!(
+                f,
+                "thread.completed: {} turns, {} items{}",
+                summary.turns,
+                summary.items,
+                if summary.any_turn_failed {
+                    " (failed)"
+                } else {
+                    ""
+                }
+            ),
+            ThreadEvent::Heartbeat { elapsed } => write!(f, "heartbeat: idle {elapsed:?}"),
+            ThreadEvent::Resumed { attempt, .. } => write!(f, "resumed (attempt {attempt})"),
+        }
+    }
+}
+
+fn fmt_item_payload(
+    f: &mut fmt::Formatter<'_>,
+    payload: &ItemPayload,
+    phase: &str,
+    limit: usize,
+) -> fmt::Result {
+    let kind = payload.kind().as_str();
+    match payload {
+        ItemPayload::AgentMessage(content) | ItemPayload::Reasoning(content) => {
+            write!(f, "{kind}{phase}: {:?}", clip(&content.text, limit))
+        }
+        ItemPayload::CommandExecution(state) => {
+            write!(f, "{kind}{phase}")?;
+            if let Some(code) = state.exit_code {
+                write!(f, " exit={code}")?;
+            }
+            write!(f, ": {}", clip(&state.command, limit))
+        }
+        ItemPayload::FileChange(state) => {
+            write!(
+                f,
+                "{kind}{phase}: {}",
+                clip(&state.path.to_string_lossy(), limit)
+            )
+        }
+        ItemPayload::McpToolCall(state) => {
+            write!(
+                f,
+                "{kind}{phase}: {}/{}",
+                state.server_name, state.tool_name
+            )
+        }
+        ItemPayload::WebSearch(state) => {
+            write!(f, "{kind}{phase}: {:?}", clip(&state.query, limit))
+        }
+        ItemPayload::TodoList(state) => {
+            let done = state.items.iter().filter(|item| item.completed).count();
+            write!(f, "{kind}{phase}: {done}/{} done", state.items.len())
+        }
+        ItemPayload::Error(error) => write!(f, "{kind}{phase}: {}", clip(&error.message, limit)),
+    }
+}
+
+fn fmt_item_delta(
+    f: &mut fmt::Formatter<'_>,
+    delta: &ItemDeltaPayload,
+    limit: usize,
+) -> fmt::Result {
+    let kind = delta.kind().as_str();
+    match delta {
+        ItemDeltaPayload::AgentMessage(delta) | ItemDeltaPayload::Reasoning(delta) => {
+            write!(f, "{kind} delta: {:?}", clip(&delta.text_delta, limit))
+        }
+        ItemDeltaPayload::CommandExecution(delta) => {
+            write!(f, "{kind} delta")?;
+            if let Some(code) = delta.exit_code {
+                write!(f, " exit={code}")?;
+            }
+            write!(f, ": {:?}", clip(&delta.stdout, limit))
+        }
+        ItemDeltaPayload::FileChange(delta) => {
+            write!(f, "{kind} delta: {:?}", clip(&delta.stdout, limit))
+        }
+        ItemDeltaPayload::McpToolCall(_) | ItemDeltaPayload::WebSearch(_) => {
+            write!(f, "{kind} delta")
+        }
+        ItemDeltaPayload::TodoList(delta) => {
+            write!(f, "{kind} delta: {} items", delta.items.len())
+        }
+        ItemDeltaPayload::Error(error) => {
+            write!(f, "{kind} delta: {}", clip(&error.message, limit))
+        }
+    }
+}
+
+fn clip(value: &str, limit: usize) -> DisplayText<'_> {
+    DisplayText { value, limit }
+}
+
+/// Free text cut to `limit` characters; `{}` prints it raw and `{:?}` quotes and escapes it.
+struct DisplayText<'a> {
+    value: &'a str,
+    limit: usize,
+}
+
+impl DisplayText<'_> {
+    fn parts(&self) -> (&str, Option<usize>) {
+        match self.value.char_indices().nth(self.limit) {
+            Some((end, _)) => (&self.value[..end], Some(self.value.chars().count())),
+            None => (self.value, None),
+        }
+    }
+}
+
+impl fmt::Display for DisplayText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.parts() {
+            (kept, Some(len)) => write!(f, "{kept}… ({len} chars)"),
+            (kept, None) => f.write_str(kept),
+        }
+    }
+}
+
+impl fmt::Debug for DisplayText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.parts() {
+            (kept, Some(len)) => write!(f, "{:?}… ({len} chars)", kept),
+            (kept, None) => write!(f, "{kept:?}"),
+        }
+    }
+}
+
+/// Error returned when a JSONL line cannot be parsed into a [`ThreadEvent`].
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[error("failed to parse codex JSONL event: {message}: `{line}`")]
+pub struct LineParseError {
+    /// The offending line (without trailing newline).
+    pub line: String,
+    /// The serde error message describing the failure.
+    pub message: String,
+}
+
+/// Outcome of a streamed thread, aggregated from the events observed before the stream closed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ThreadSummary {
+    /// Last thread id seen on a `thread.started` event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    /// Number of `turn.completed` and `turn.failed` events.
+    pub turns: usize,
+    /// Number of `item.completed` and `item.failed` events.
+    pub items: usize,
+    /// True when at least one `turn.failed` event was observed.
+    pub any_turn_failed: bool,
+    /// Sum of the `usage` objects reported on `turn.completed` events.
+    pub usage: TokenUsage,
+}
+
+impl ThreadSummary {
+    pub(crate) fn observe(&mut self, event: &ThreadEvent) {
+        match event {
+            ThreadEvent::ThreadStarted(started) => {
+                self.thread_id = Some(started.thread_id.clone());
+            }
+            ThreadEvent::TurnCompleted(completed) => {
+                self.turns += 1;
+                if let Some(usage) = completed.usage() {
+                    self.usage.add(&usage);
+                }
+            }
+            ThreadEvent::TurnFailed(_) => {
+                self.turns += 1;
+                self.any_turn_failed = true;
+            }
+            ThreadEvent::ItemCompleted(_) | ThreadEvent::ItemFailed(_) => self.items += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Token counts reported by `turn.completed` events.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub cached_input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    pub(crate) fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens = self.input_tokens.saturating_add(other.input_tokens);
+        self.cached_input_tokens = self
+            .cached_input_tokens
+            .saturating_add(other.cached_input_tokens);
+        self.output_tokens = self.output_tokens.saturating_add(other.output_tokens);
+    }
 }
 
 /// Marks the start of a new thread.
@@ -69,6 +494,15 @@ pub struct TurnCompleted {
     pub extra: BTreeMap<String, Value>,
 }
 
+impl TurnCompleted {
+    /// Token usage reported in the event's `usage` object, if present and well-formed.
+    pub(crate) fn usage(&self) -> Option<TokenUsage> {
+        self.extra
+            .get("usage")
+            .and_then(|usage| serde_json::from_value(usage.clone()).ok())
+    }
+}
+
 /// Indicates a turn-level failure.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TurnFailed {
@@ -125,6 +559,9 @@ pub struct ItemFailure {
     pub item_id: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub index: Option<u32>,
+    /// `Failed` unless the CLI reports the item as cancelled (e.g. after an interrupt).
+    #[serde(default = "ItemStatus::failed")]
+    pub status: ItemStatus,
     pub error: EventError,
     #[serde(flatten, default, skip_serializing_if = "BTreeMap::is_empty")]
     pub extra: BTreeMap<String, Value>,
@@ -158,6 +595,135 @@ pub enum ItemDeltaPayload {
     Error(EventError),
 }
 
+/// Item type shared by [`ItemPayload`] and [`ItemDeltaPayload`] variants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    AgentMessage,
+    Reasoning,
+    CommandExecution,
+    FileChange,
+    McpToolCall,
+    WebSearch,
+    TodoList,
+    Error,
+}
+
+impl ItemKind {
+    /// Upstream `item_type` name, e.g. `agent_message`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemKind::AgentMessage => "agent_message",
+            ItemKind::Reasoning => "reasoning",
+            ItemKind::CommandExecution => "command_execution",
+            ItemKind::FileChange => "file_change",
+            ItemKind::McpToolCall => "mcp_tool_call",
+            ItemKind::WebSearch => "web_search",
+            ItemKind::TodoList => "todo_list",
+            ItemKind::Error => "error",
+        }
+    }
+
+    /// Every item type, in declaration order.
+    pub const ALL: [ItemKind; 8] = [
+        ItemKind::AgentMessage,
+        ItemKind::Reasoning,
+        ItemKind::CommandExecution,
+        ItemKind::FileChange,
+        ItemKind::McpToolCall,
+        ItemKind::WebSearch,
+        ItemKind::TodoList,
+        ItemKind::Error,
+    ];
+}
+
+impl ItemPayload {
+    /// Item type of this payload.
+    pub fn kind(&self) -> ItemKind {
+        match self {
+            ItemPayload::AgentMessage(_) => ItemKind::AgentMessage,
+            ItemPayload::Reasoning(_) => ItemKind::Reasoning,
+            ItemPayload::CommandExecution(_) => ItemKind::CommandExecution,
+            ItemPayload::FileChange(_) => ItemKind::FileChange,
+            ItemPayload::McpToolCall(_) => ItemKind::McpToolCall,
+            ItemPayload::WebSearch(_) => ItemKind::WebSearch,
+            ItemPayload::TodoList(_) => ItemKind::TodoList,
+            ItemPayload::Error(_) => ItemKind::Error,
+        }
+    }
+}
+
+impl ItemDeltaPayload {
+    /// Item type this delta applies to.
+    pub fn kind(&self) -> ItemKind {
+        match self {
+            ItemDeltaPayload::AgentMessage(_) => ItemKind::AgentMessage,
+            ItemDeltaPayload::Reasoning(_) => ItemKind::Reasoning,
+            ItemDeltaPayload::CommandExecution(_) => ItemKind::CommandExecution,
+            ItemDeltaPayload::FileChange(_) => ItemKind::FileChange,
+            ItemDeltaPayload::McpToolCall(_) => ItemKind::McpToolCall,
+            ItemDeltaPayload::WebSearch(_) => ItemKind::WebSearch,
+            ItemDeltaPayload::TodoList(_) => ItemKind::TodoList,
+            ItemDeltaPayload::Error(_) => ItemKind::Error,
+        }
+    }
+}
+
+/// Item types forwarded by a stream; see [`crate::ExecStreamRequest::event_filter`].
+///
+/// Only `item.started`, `item.delta`, and `item.completed` events are subject to the filter.
+/// Thread and turn lifecycle events, `item.failed`, and `error` events always pass.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventFilter {
+    forward: BTreeSet<ItemKind>,
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl EventFilter {
+    /// Forwards every item type.
+    pub fn all() -> Self {
+        Self::only(ItemKind::ALL)
+    }
+
+    /// Forwards only the listed item types.
+    pub fn only(kinds: impl IntoIterator<Item = ItemKind>) -> Self {
+        Self {
+            forward: kinds.into_iter().collect(),
+        }
+    }
+
+    /// Forwards every item type except the listed ones.
+    pub fn except(kinds: impl IntoIterator<Item = ItemKind>) -> Self {
+        let mut filter = Self::all();
+        for kind in kinds {
+            filter.forward.remove(&kind);
+        }
+        filter
+    }
+
+    /// Returns whether items of `kind` are forwarded.
+    pub fn forwards(&self, kind: ItemKind) -> bool {
+        self.forward.contains(&kind)
+    }
+
+    /// Returns the item type of `event` when the filter drops it.
+    pub fn rejects(&self, event: &ThreadEvent) -> Option<ItemKind> {
+        let kind = match event {
+            ThreadEvent::ItemStarted(envelope) | ThreadEvent::ItemCompleted(envelope) => {
+                envelope.item.payload.kind()
+            }
+            ThreadEvent::ItemDelta(delta) => delta.delta.kind(),
+            _ => return None,
+        };
+        (!self.forwards(kind)).then_some(kind)
+    }
+}
+
 /// Item status supplied by the CLI for bookkeeping.
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -166,10 +732,27 @@ pub enum ItemStatus {
     InProgress,
     Completed,
     Failed,
+    /// Item stopped because its turn was interrupted.
+    #[serde(rename = "cancelled", alias = "canceled")]
+    Cancelled,
     #[serde(other)]
     Unknown,
 }
 
+impl ItemStatus {
+    /// Returns true once the item will receive no further updates.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ItemStatus::Completed | ItemStatus::Failed | ItemStatus::Cancelled
+        )
+    }
+
+    fn failed() -> Self {
+        ItemStatus::Failed
+    }
+}
+
 /// Human-readable content emitted by the agent.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TextContent {
@@ -211,6 +794,22 @@ pub struct CommandExecutionState {
     pub extra: BTreeMap<String, Value>,
 }
 
+impl CommandExecutionState {
+    /// Returns `stdout` with ANSI color and cursor escapes removed.
+    ///
+    /// Codex runs with `--color never`, but the commands it executes may still colorize
+    /// their output. The stored field is left untouched; this borrows when nothing needs
+    /// stripping.
+    pub fn plain_stdout(&self) -> Cow<'_, str> {
+        strip_ansi(&self.stdout)
+    }
+
+    /// Returns `stderr` with ANSI escapes removed; see [`CommandExecutionState::plain_stdout`].
+    pub fn plain_stderr(&self) -> Cow<'_, str> {
+        strip_ansi(&self.stderr)
+    }
+}
+
 /// Streaming delta for command execution.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CommandExecutionDelta {
@@ -263,6 +862,68 @@ pub struct FileChangeState {
     pub extra: BTreeMap<String, Value>,
 }
 
+impl FileChangeState {
+    /// Returns true when the change looks risky enough to warrant extra approval.
+    ///
+    /// See [`FileChangeState::destructive_reason`] for the heuristics applied.
+    pub fn is_destructive(&self) -> bool {
+        self.destructive_reason().is_some()
+    }
+
+    /// Returns a short reason when the change deletes a file or touches a sensitive path.
+    ///
+    /// The heuristic is intentionally conservative: a diff must remove the whole file
+    /// (`+++ /dev/null`, `deleted file mode`, or a `+0,0` hunk) to count as a deletion,
+    /// and paths are only flagged under `.git/`, `.env*` files, or common lockfiles.
+    pub fn destructive_reason(&self) -> Option<&'static str> {
+        if self.diff.as_deref().is_some_and(diff_deletes_file) {
+            return Some("deletes file");
+        }
+        sensitive_path_reason(&self.path)
+    }
+}
+
+const LOCKFILE_NAMES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "Gemfile.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "composer.lock",
+    "go.sum",
+];
+
+fn diff_deletes_file(diff: &str) -> bool {
+    diff.lines().any(|line| {
+        let line = line.trim_end();
+        line == "+++ /dev/null"
+            || line.starts_with("deleted file mode")
+            || (line.starts_with("@@ ") && line.contains(" +0,0 @@"))
+    })
+}
+
+fn sensitive_path_reason(path: &Path) -> Option<&'static str> {
+    if path
+        .components()
+        .any(|component| component.as_os_str() == ".git")
+    {
+        return Some("touches .git directory");
+    }
+
+    let name = path.file_name()?.to_str()?;
+    if name == ".env" || name.starts_with(".env.") {
+        return Some("touches environment file");
+    }
+    if LOCKFILE_NAMES.contains(&name) {
+        return Some("touches lockfile");
+    }
+    None
+}
+
 /// Streaming delta describing a file change.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct FileChangeDelta {
@@ -351,6 +1012,37 @@ pub struct WebSearchState {
     pub extra: BTreeMap<String, Value>,
 }
 
+impl WebSearchState {
+    /// Decodes `results` into typed entries when it uses the common array shape
+    /// (`[{"title", "url", "snippet"}]`, optionally wrapped in `{"results": [...]}`).
+    ///
+    /// Returns `None` when results are absent or the shape is unrecognized; the raw value
+    /// stays available on [`WebSearchState::results`].
+    pub fn parsed_results(&self) -> Option<Vec<WebSearchResult>> {
+        let results = self.results.as_ref()?;
+        let entries = match results {
+            Value::Array(_) => results,
+            Value::Object(map) => map.get("results").filter(|value| value.is_array())?,
+            _ => return None,
+        };
+        serde_json::from_value(entries.clone()).ok()
+    }
+}
+
+/// Single web search hit decoded from [`WebSearchState::results`].
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct WebSearchResult {
+    pub title: String,
+    #[serde(alias = "link")]
+    pub url: String,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        alias = "description"
+    )]
+    pub snippet: Option<String>,
+}
+
 /// Streaming delta for search results.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct WebSearchDelta {
@@ -412,3 +1104,186 @@ pub struct EventError {
     #[serde(flatten, default, skip_serializing_if = "BTreeMap::is_empty")]
     pub extra: BTreeMap<String, Value>,
 }
+
+impl EventError {
+    /// Classifies the failure from `code`, falling back to message heuristics when the code is
+    /// missing or unrecognized. The raw `code` is left untouched.
+    pub fn kind(&self) -> EventErrorKind {
+        self.code
+            .as_deref()
+            .and_then(classify_error_code)
+            .or_else(|| classify_error_message(&self.message))
+            .or_else(|| retry_after_hint(&self.extra).map(|_| EventErrorKind::RateLimit))
+            .unwrap_or(EventErrorKind::Unknown)
+    }
+
+    /// Throttling details when [`Self::kind`] is [`EventErrorKind::RateLimit`], with the
+    /// server's suggested delay read from `retry_after_ms` (milliseconds) or `retry_after`
+    /// (seconds) in `extra`. A hint alone is enough to classify the error as a rate limit.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        (self.kind() == EventErrorKind::RateLimit).then(|| RateLimit {
+            retry_after: retry_after_hint(&self.extra),
+        })
+    }
+}
+
+impl TurnFailed {
+    /// [`EventError::rate_limit`] for the failure, also honoring a retry hint placed on the
+    /// `turn.failed` event itself rather than inside `error`.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        let hint = retry_after_hint(&self.extra);
+        if let Some(mut limit) = self.error.rate_limit() {
+            limit.retry_after = limit.retry_after.or(hint);
+            return Some(limit);
+        }
+        let unclassified = self.error.kind() == EventErrorKind::Unknown;
+        (unclassified && hint.is_some()).then_some(RateLimit { retry_after: hint })
+    }
+}
+
+/// Rate-limit details extracted from a failed turn or `error` event.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RateLimit {
+    /// Delay the server asked for before retrying; `None` when no usable hint was sent, in
+    /// which case callers fall back to their own backoff.
+    pub retry_after: Option<Duration>,
+}
+
+/// Reads `retry_after_ms` (milliseconds) or `retry_after` (seconds) as a number or numeric
+/// string; negative, non-finite, and HTTP-date values are ignored.
+fn retry_after_hint(extra: &BTreeMap<String, Value>) -> Option<Duration> {
+    let hint = |key: &str, scale: f64| {
+        let amount = match extra.get(key)? {
+            Value::Number(number) => number.as_f64()?,
+            Value::String(text) => text.trim().parse::<f64>().ok()?,
+            _ => return None,
+        };
+        Duration::try_from_secs_f64(amount / scale).ok()
+    };
+    hint("retry_after_ms", 1000.0).or_else(|| hint("retry_after", 1.0))
+}
+
+/// Coarse classification of an [`EventError`] for retry/backoff decisions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventErrorKind {
+    /// Request was throttled (`rate_limit_exceeded`, HTTP 429).
+    RateLimit,
+    /// Prompt plus history exceeded the model's context window.
+    ContextLengthExceeded,
+    /// Credentials are missing, invalid, or lack permission.
+    Auth,
+    /// A tool invocation failed.
+    ToolError,
+    Unknown,
+}
+
+impl EventErrorKind {
+    /// Returns true when retrying the turn after a backoff may succeed.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, EventErrorKind::RateLimit)
+    }
+}
+
+fn classify_error_code(code: &str) -> Option<EventErrorKind> {
+    let code = code.trim().to_ascii_lowercase().replace('-', "_");
+    if code.contains("rate_limit") || code == "429" || code == "too_many_requests" {
+        Some(EventErrorKind::RateLimit)
+    } else if code.contains("context_length") || code.contains("context_window") {
+        Some(EventErrorKind::ContextLengthExceeded)
+    } else if code.contains("auth")
+        || code.contains("api_key")
+        || matches!(code.as_str(), "401" | "403" | "forbidden")
+    {
+        Some(EventErrorKind::Auth)
+    } else if code.contains("tool") {
+        Some(EventErrorKind::ToolError)
+    } else {
+        None
+    }
+}
+
+fn classify_error_message(message: &str) -> Option<EventErrorKind> {
+    let message = message.to_ascii_lowercase();
+    if message.contains("rate limit") || message.contains("too many requests") {
+        Some(EventErrorKind::RateLimit)
+    } else if message.contains("context length")
+        || message.contains("context window")
+        || message.contains("maximum context")
+    {
+        Some(EventErrorKind::ContextLengthExceeded)
+    } else if message.contains("unauthorized")
+        || message.contains("authentication")
+        || message.contains("invalid api key")
+        || message.contains("not logged in")
+    {
+        Some(EventErrorKind::Auth)
+    } else if message.contains("tool call") || message.contains("tool error") {
+        Some(EventErrorKind::ToolError)
+    } else {
+        None
+    }
+}
+
+/// Read access to the unmodeled fields an event kept in its `extra` map.
+///
+/// [`ExtraAccess::extra_get_path`] takes a JSON pointer (`/metadata/cost`) whose first segment
+/// names the `extra` key and whose remainder walks into that value, so nested fields the crate
+/// does not model yet stay reachable without deserializing the event again.
+pub trait ExtraAccess {
+    /// Unknown keys preserved while deserializing.
+    fn extra(&self) -> &BTreeMap<String, Value>;
+
+    /// Resolves a JSON pointer against [`Self::extra`]; `None` for missing fields and for
+    /// pointers that are empty or lack the leading `/`.
+    fn extra_get_path(&self, pointer: &str) -> Option<&Value> {
+        let path = pointer.strip_prefix('/')?;
+        let (key, rest) = match path.find('/') {
+            Some(index) => path.split_at(index),
+            None => (path, ""),
+        };
+        let key = key.replace("~1", "/").replace("~0", "~");
+        self.extra().get(&key)?.pointer(rest)
+    }
+}
+
+impl<T: ExtraAccess> ExtraAccess for ItemEnvelope<T> {
+    fn extra(&self) -> &BTreeMap<String, Value> {
+        self.item.extra()
+    }
+}
+
+macro_rules! impl_extra_access {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl ExtraAccess for $ty {
+                fn extra(&self) -> &BTreeMap<String, Value> {
+                    &self.extra
+                }
+            }
+        )+
+    };
+}
+
+impl_extra_access!(
+    ThreadStarted,
+    TurnStarted,
+    TurnCompleted,
+    TurnFailed,
+    ItemSnapshot,
+    ItemDelta,
+    ItemFailure,
+    TextContent,
+    TextDelta,
+    CommandExecutionState,
+    CommandExecutionDelta,
+    FileChangeState,
+    FileChangeDelta,
+    McpToolCallState,
+    McpToolCallDelta,
+    WebSearchState,
+    WebSearchDelta,
+    TodoListState,
+    TodoListDelta,
+    TodoItem,
+    EventError,
+);
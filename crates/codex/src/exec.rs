@@ -1,28 +1,39 @@
 use std::{
+    collections::BTreeMap,
     env,
     ffi::OsString,
     future::Future,
     path::{Path, PathBuf},
     pin::Pin,
     process::ExitStatus,
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use futures_core::Stream;
+use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
 use thiserror::Error;
-use tokio::{fs, io::AsyncWriteExt, process::Command, time};
+use tokio::{fs, io::AsyncWriteExt, process::Command};
 use tracing::debug;
 
 use crate::{
     builder::{apply_cli_overrides, resolve_cli_overrides},
-    capabilities::{guard_is_supported, log_guard_skip},
-    process::{spawn_with_retry, tee_stream, ConsoleTarget},
-    ApplyDiffArtifacts, CliOverridesPatch, CodexClient, CodexError, ConfigOverride, ExecRequest,
-    FlagState, ResumeSessionRequest, ThreadEvent,
+    capabilities::guard_is_supported,
+    process::{
+        spawn_with_retry, tee_stream, tee_stream_captured, timeout_with_shutdown, CommandOutput,
+        ConsoleTarget,
+    },
+    Attachment, CliOverridesPatch, CodexCapabilities, CodexClient, CodexError, ConfigOverride,
+    DirAccess, EventFilter, ExecOptions, ExecRequest, FlagState, ItemDeltaPayload, ItemKind,
+    ItemPayload, ResumeSessionRequest, ThreadEvent, TokenUsage,
 };
 
+mod abort;
 mod streaming;
 
+pub use abort::ExecAbortHandle;
+
 impl CodexClient {
     /// Sends `prompt` to `codex exec` and returns its stdout (the final agent message) on success.
     ///
@@ -32,6 +43,12 @@ impl CodexClient {
     /// `.mirror_stdout(false)`; the returned string contains the buffered lines for offline
     /// parsing. For per-event handling, see `crates/codex/examples/stream_events.rs`.
     ///
+    /// Older binaries that predate JSONL output ignore `--json` and print plain text. When no
+    /// `thread.started` line is found and the capability probe does not confirm JSON support
+    /// ([`crate::CodexFeatureFlags::supports_json_output`]), this returns
+    /// [`CodexError::JsonModeUnsupported`] carrying the unstructured stdout instead of passing it
+    /// off as a stream.
+    ///
     /// ```rust,no_run
     /// use codex::CodexClient;
     /// # #[tokio::main]
@@ -46,13 +63,371 @@ impl CodexClient {
             .await
     }
 
+    /// Roughly estimates the input tokens `prompt` will cost, for pre-flight budget checks.
+    ///
+    /// This is a heuristic, not a tokenizer: about four characters per token plus
+    /// [`PromptEstimate::TOKENS_PER_IMAGE`] for each image configured via
+    /// [`CodexClientBuilder::image`]. The CLI's own system prompt and tool context are not
+    /// counted, so compare against the reported [`TokenUsage`] with
+    /// [`PromptEstimate::calibration`] to learn a per-host correction factor.
+    pub fn estimate_prompt(&self, prompt: &str) -> PromptEstimate {
+        let text_tokens = prompt.chars().count().div_ceil(4);
+        let image_tokens = self.images.len() * PromptEstimate::TOKENS_PER_IMAGE;
+        PromptEstimate {
+            approx_input_tokens: text_tokens + image_tokens,
+        }
+    }
+
     /// Sends an exec request with per-call CLI overrides.
     pub async fn send_prompt_with(&self, request: ExecRequest) -> Result<String, CodexError> {
         if request.prompt.trim().is_empty() {
             return Err(CodexError::EmptyPrompt);
         }
 
-        self.invoke_codex_exec(request).await
+        self.invoke_codex_exec(request)
+            .await
+            .map(|(_, output)| output)
+    }
+
+    /// Like [`Self::send_prompt`], but bounded by an absolute `deadline` instead of the
+    /// configured timeout, so one request budget can be shared across several Codex calls.
+    ///
+    /// The remaining time replaces [`CodexClientBuilder::timeout`] for this call only. Returns
+    /// [`CodexError::Timeout`] without spawning when the deadline has already passed.
+    pub async fn send_prompt_until(
+        &self,
+        prompt: impl AsRef<str>,
+        deadline: Instant,
+    ) -> Result<String, CodexError> {
+        self.until(deadline)?.send_prompt(prompt).await
+    }
+
+    /// Streaming counterpart of [`Self::send_prompt_until`]: the whole stream, not just its
+    /// startup, must finish before `deadline`.
+    pub async fn stream_exec_until(
+        &self,
+        request: ExecStreamRequest,
+        deadline: Instant,
+    ) -> Result<ExecStream, ExecStreamError> {
+        self.until(deadline)?.stream_exec(request).await
+    }
+
+    fn until(&self, deadline: Instant) -> Result<CodexClient, CodexError> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(CodexError::Timeout { timeout: remaining });
+        }
+        Ok(self.with_timeout(remaining))
+    }
+
+    /// Runs `prompt` via `codex exec --json` and returns the raw JSONL together with the parsed
+    /// events, final agent message, exit status, and token usage.
+    ///
+    /// JSON output is requested for this call regardless of [`CodexClientBuilder::json`]. Lines
+    /// that fail to parse are recorded in [`ExecResult::parse_errors`] instead of failing the
+    /// call; non-zero exits and unsupported JSON mode still return the same errors as
+    /// [`Self::send_prompt`].
+    pub async fn exec_collect(&self, prompt: impl AsRef<str>) -> Result<ExecResult, CodexError> {
+        let prompt = prompt.as_ref();
+        if prompt.trim().is_empty() {
+            return Err(CodexError::EmptyPrompt);
+        }
+
+        let mut client = self.clone();
+        client.json_output = true;
+        let (status, raw) = client.invoke_codex_exec(ExecRequest::new(prompt)).await?;
+
+        let mut parser = crate::JsonlThreadEventParser::new();
+        let mut result = ExecResult {
+            raw: String::new(),
+            events: Vec::new(),
+            last_message: None,
+            status,
+            usage: None,
+            parse_errors: Vec::new(),
+        };
+        for line in raw.lines() {
+            let event = match parser.parse_line(line) {
+                Ok(Some(event)) => event,
+                Ok(None) => continue,
+                Err(err) => {
+                    result
+                        .parse_errors
+                        .push(crate::jsonl::line_parse_error(line.to_string(), &err));
+                    continue;
+                }
+            };
+            match &event {
+                ThreadEvent::ItemCompleted(envelope) => {
+                    if let ItemPayload::AgentMessage(content) = &envelope.item.payload {
+                        result.last_message = Some(content.text.clone());
+                    }
+                }
+                ThreadEvent::TurnCompleted(completed) => {
+                    if let Some(usage) = completed.usage() {
+                        result
+                            .usage
+                            .get_or_insert_with(TokenUsage::default)
+                            .add(&usage);
+                    }
+                }
+                _ => {}
+            }
+            result.events.push(event);
+        }
+        result.raw = raw;
+        Ok(result)
+    }
+
+    /// Sends `prompt` with a bundle of per-call overrides layered over the client defaults.
+    ///
+    /// A `model` in `options` behaves like [`CodexClient::with_model`] for this call only; the
+    /// profile and config overrides are applied as request-level CLI overrides.
+    pub async fn send_prompt_with_options(
+        &self,
+        prompt: impl AsRef<str>,
+        options: ExecOptions,
+    ) -> Result<String, CodexError> {
+        let ExecOptions {
+            profile,
+            model,
+            config_overrides,
+        } = options;
+        let mut request = ExecRequest::new(prompt.as_ref());
+        request.overrides.profile = profile;
+        request.overrides.config_overrides = config_overrides;
+
+        match model {
+            Some(model) => self.with_model(model).send_prompt_with(request).await,
+            None => self.send_prompt_with(request).await,
+        }
+    }
+
+    /// Sends `prompt` with files attached as context.
+    ///
+    /// Images are forwarded via `--image`. Text files must fit within
+    /// [`CodexClientBuilder::max_attachment_bytes`] and their contents are inlined into the
+    /// prompt, so attaching a file never widens the sandbox with its directory. Missing files
+    /// return [`CodexError::AttachmentNotFound`] without spawning the CLI.
+    pub async fn prompt_with_attachments(
+        &self,
+        prompt: impl AsRef<str>,
+        attachments: Vec<Attachment>,
+    ) -> Result<String, CodexError> {
+        let mut prompt = prompt.as_ref().to_string();
+        if prompt.trim().is_empty() {
+            return Err(CodexError::EmptyPrompt);
+        }
+
+        let mut client = self.clone();
+        let mut text_files = Vec::new();
+        for attachment in attachments {
+            match attachment {
+                Attachment::Image(path) => client.images.push(resolve_attachment(path).await?),
+                Attachment::TextFile(path) => {
+                    let path = resolve_attachment(path).await?;
+                    let size = fs::metadata(&path).await?.len();
+                    if size > self.max_attachment_bytes {
+                        return Err(CodexError::AttachmentTooLarge {
+                            path,
+                            size,
+                            limit: self.max_attachment_bytes,
+                        });
+                    }
+                    text_files.push(path);
+                }
+            }
+        }
+
+        for path in &text_files {
+            let contents = fs::read_to_string(path).await?;
+            prompt.push_str(&format!(
+                "\n\n--- {} ---\n{}\n--- end of {} ---",
+                path.display(),
+                contents.trim_end(),
+                path.display()
+            ));
+        }
+
+        client.send_prompt(prompt).await
+    }
+
+    /// Runs `codex <args>` verbatim and returns the captured stdout/stderr/status.
+    ///
+    /// This is an escape hatch for CLI flags the crate does not model yet. It applies the
+    /// configured binary, `CODEX_HOME`/env, working directory, and timeout, but intentionally
+    /// skips everything else: no implicit flags (`--skip-git-repo-check`, `--color`, model,
+    /// CLI overrides), no capability guards, and no console mirroring. A non-zero exit is
+    /// returned in [`CommandOutput::status`] rather than as an error. When `stdin` is `None`
+    /// the child's stdin is closed.
+    pub async fn exec_raw(
+        &self,
+        args: Vec<OsString>,
+        stdin: Option<&[u8]>,
+    ) -> Result<CommandOutput, CodexError> {
+        let dir_ctx = self.directory_context()?;
+
+        let mut command = Command::new(self.command_env.binary_path());
+        command
+            .args(&args)
+            .stdin(if stdin.is_some() {
+                std::process::Stdio::piped()
+            } else {
+                std::process::Stdio::null()
+            })
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .current_dir(dir_ctx.path());
+
+        self.command_env.apply(&mut command)?;
+        let command_line = crate::process::redacted_command_line(&command);
+
+        let mut child = spawn_with_retry(&mut command, self.command_env.binary_path())?;
+        let pid = child.id();
+
+        let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
+        let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
+        let stdout_task = tokio::spawn(tee_stream(
+            stdout,
+            ConsoleTarget::Stdout,
+            false,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream(
+            stderr,
+            ConsoleTarget::Stderr,
+            false,
+            self.capture_options(),
+        ));
+
+        let mut child_stdin = child.stdin.take();
+        let wait_task = async move {
+            let _dir_ctx = dir_ctx;
+            if let (Some(mut pipe), Some(bytes)) = (child_stdin.take(), stdin) {
+                if let Err(source) = pipe.write_all(bytes).await {
+                    if source.kind() != std::io::ErrorKind::BrokenPipe {
+                        return Err(CodexError::StdinWrite(source));
+                    }
+                }
+                if let Err(source) = pipe.shutdown().await {
+                    if source.kind() != std::io::ErrorKind::BrokenPipe {
+                        return Err(CodexError::StdinWrite(source));
+                    }
+                }
+            }
+            let status = child
+                .wait()
+                .await
+                .map_err(|source| CodexError::Wait { source })?;
+            let stdout_bytes = stdout_task
+                .await
+                .map_err(CodexError::Join)?
+                .map_err(CodexError::CaptureIo)?;
+            let stderr_bytes = stderr_task
+                .await
+                .map_err(CodexError::Join)?
+                .map_err(CodexError::CaptureIo)?;
+            Ok::<_, CodexError>(CommandOutput {
+                command: command_line,
+                status,
+                stdout: stdout_bytes,
+                stderr: stderr_bytes,
+            })
+        };
+
+        if self.timeout.is_zero() {
+            wait_task.await
+        } else {
+            match timeout_with_shutdown(self.timeout, wait_task, pid, self.graceful_shutdown).await
+            {
+                Some(result) => result,
+                None => Err(CodexError::Timeout {
+                    timeout: self.timeout,
+                }),
+            }
+        }
+    }
+
+    /// Runs `prompt` with `--output-schema <schema_path>` and deserializes the final message
+    /// into `T`.
+    ///
+    /// Fails with [`CodexError::UnsupportedCapability`] when the probed binary does not
+    /// confirm `--output-schema` support, and with [`CodexError::StructuredOutput`] when the
+    /// last message is not valid JSON for `T`.
+    pub async fn send_prompt_typed<T: DeserializeOwned>(
+        &self,
+        prompt: impl AsRef<str>,
+        schema_path: impl Into<PathBuf>,
+    ) -> Result<T, CodexError> {
+        let guard = self.probe_capabilities().await.guard_output_schema();
+        if !guard_is_supported(&guard) {
+            return Err(CodexError::UnsupportedCapability {
+                feature: guard.feature.label(),
+            });
+        }
+
+        let request = ExecStreamRequest::new(prompt.as_ref()).output_schema(schema_path.into());
+        let completion = self
+            .stream_exec(request)
+            .await
+            .map_err(exec_stream_error_into_codex)?
+            .finish()
+            .await
+            .map_err(exec_stream_error_into_codex)?;
+
+        let message = completion
+            .last_message
+            .or(completion.agent_message)
+            .unwrap_or_default();
+        serde_json::from_str(message.trim()).map_err(|source| CodexError::StructuredOutput {
+            expected: std::any::type_name::<T>(),
+            source,
+        })
+    }
+
+    /// Sends `prompt` via `codex exec --json` and feeds agent-message text to `on_chunk` as it
+    /// arrives, returning the final agent message.
+    ///
+    /// `item.delta` text is forwarded as-is; when the CLI only reports whole messages, the part
+    /// not yet delivered is forwarded once the item starts or completes. Reasoning, tool, and
+    /// other events are dropped; use [`Self::send_prompt_with_progress_and_events`] to observe
+    /// them.
+    pub async fn send_prompt_with_progress(
+        &self,
+        prompt: impl AsRef<str>,
+        on_chunk: impl FnMut(&str),
+    ) -> Result<String, CodexError> {
+        self.send_prompt_with_progress_and_events(prompt, on_chunk, |_| {})
+            .await
+    }
+
+    /// Like [`Self::send_prompt_with_progress`], but hands every event that does not carry
+    /// agent-message text (reasoning, commands, tool calls, turn lifecycle) to `on_event`.
+    pub async fn send_prompt_with_progress_and_events(
+        &self,
+        prompt: impl AsRef<str>,
+        mut on_chunk: impl FnMut(&str),
+        mut on_event: impl FnMut(&ThreadEvent),
+    ) -> Result<String, CodexError> {
+        let request = ExecStreamRequest::new(prompt.as_ref());
+        let mut stream = self
+            .stream_exec(request)
+            .await
+            .map_err(exec_stream_error_into_codex)?;
+
+        let mut progress = AgentMessageProgress::default();
+        while let Some(event) = stream.events.next().await {
+            let event = event.map_err(exec_stream_error_into_codex)?;
+            if !progress.observe(&event, &mut on_chunk) {
+                on_event(&event);
+            }
+        }
+        let completion = stream
+            .completion
+            .await
+            .map_err(exec_stream_error_into_codex)?;
+        Ok(completion.agent_message.unwrap_or(progress.text))
     }
 
     /// Streams structured JSONL events from `codex exec --json`.
@@ -82,43 +457,81 @@ impl CodexClient {
         &self,
         request: ResumeRequest,
     ) -> Result<ExecStream, ExecStreamError> {
-        streaming::stream_resume(self, request).await
+        streaming::stream_resume(self, request, streaming::SegmentOptions::default()).await
     }
 
-    /// Runs `codex resume [OPTIONS] [SESSION_ID] [PROMPT]` and returns captured output.
+    /// Resumes a session and streams its events via `codex exec --json resume`.
+    ///
+    /// An explicit session id wins over `last`/`all`; with none of them set the most recent
+    /// session is resumed. Use [`Self::stream_resume`] with a [`ResumeRequest`] for control over
+    /// idle timeouts and output paths.
     pub async fn resume_session(
         &self,
         request: ResumeSessionRequest,
-    ) -> Result<ApplyDiffArtifacts, CodexError> {
-        if matches!(request.prompt.as_deref(), Some(prompt) if prompt.trim().is_empty()) {
-            return Err(CodexError::EmptyPrompt);
-        }
+    ) -> Result<ExecStream, ExecStreamError> {
+        self.stream_resume(request.into()).await
+    }
 
-        let mut args = vec![OsString::from("resume")];
-        if request.all {
-            args.push(OsString::from("--all"));
-        }
-        if request.last {
-            args.push(OsString::from("--last"));
+    /// Whether a `\n` should follow `prompt` on stdin; never doubles an existing terminator.
+    fn appends_prompt_newline(&self, prompt: &str) -> bool {
+        self.prompt_trailing_newline && !prompt.ends_with('\n')
+    }
+
+    /// Emits `--add-dir` / `--add-dir-read-only` for the builder's extra directories.
+    ///
+    /// [`DirAccess::ReadOnly`] entries fail with [`CodexError::UnsupportedCapability`] unless
+    /// `--add-dir-read-only` is confirmed, so they never fall back to writable access. Writable
+    /// entries are skipped when `--add-dir` is unconfirmed, and the skipped guard is reported.
+    fn apply_add_dirs(
+        &self,
+        command: &mut Command,
+        capabilities: &CodexCapabilities,
+    ) -> Result<(), CodexError> {
+        if self
+            .add_dirs
+            .iter()
+            .any(|dir| dir.access == DirAccess::ReadOnly)
+            && !guard_is_supported(&capabilities.guard_add_dir_read_only())
+        {
+            return Err(CodexError::UnsupportedCapability {
+                feature: "read-only --add-dir",
+            });
         }
-        if let Some(session_id) = request.session_id {
-            if !session_id.trim().is_empty() {
-                args.push(OsString::from(session_id));
-            }
+        let writable = self
+            .add_dirs
+            .iter()
+            .any(|dir| dir.access == DirAccess::ReadWrite);
+        let guard = capabilities.guard_add_dir();
+        let emit_writable = guard_is_supported(&guard);
+        if writable && !emit_writable {
+            self.guard_skip.report(&guard);
         }
-        if let Some(prompt) = request.prompt {
-            if !prompt.trim().is_empty() {
-                args.push(OsString::from(prompt));
-            }
+
+        for dir in &self.add_dirs {
+            match dir.access {
+                DirAccess::ReadOnly => command.arg("--add-dir-read-only").arg(&dir.path),
+                DirAccess::ReadWrite if emit_writable => command.arg("--add-dir").arg(&dir.path),
+                DirAccess::ReadWrite => continue,
+            };
         }
+        Ok(())
+    }
 
-        self.run_simple_command_with_overrides(args, request.overrides)
-            .await
+    async fn invoke_codex_exec(
+        &self,
+        request: ExecRequest,
+    ) -> Result<(ExitStatus, String), CodexError> {
+        let result = self.run_codex_exec(request).await;
+        self.metrics.record_result(&result);
+        result
     }
 
-    async fn invoke_codex_exec(&self, request: ExecRequest) -> Result<String, CodexError> {
+    async fn run_codex_exec(
+        &self,
+        request: ExecRequest,
+    ) -> Result<(ExitStatus, String), CodexError> {
         let ExecRequest { prompt, overrides } = request;
-        let dir_ctx = self.directory_context()?;
+        let dir_ctx = self.exec_directory_context().await?;
         let needs_capabilities = self.output_schema || !self.add_dirs.is_empty();
         let capabilities = if needs_capabilities {
             Some(self.probe_capabilities().await)
@@ -162,20 +575,11 @@ impl CodexClient {
                 if guard_is_supported(&guard) {
                     command.arg("--output-schema");
                 } else {
-                    log_guard_skip(&guard);
+                    self.guard_skip.report(&guard);
                 }
             }
 
-            if !self.add_dirs.is_empty() {
-                let guard = capabilities.guard_add_dir();
-                if guard_is_supported(&guard) {
-                    for dir in &self.add_dirs {
-                        command.arg("--add-dir").arg(dir);
-                    }
-                } else {
-                    log_guard_skip(&guard);
-                }
-            }
+            self.apply_add_dirs(&mut command, capabilities)?;
         }
 
         for image in &self.images {
@@ -187,8 +591,12 @@ impl CodexClient {
         }
 
         self.command_env.apply(&mut command)?;
+        let command_line = crate::process::redacted_command_line(&command);
 
+        let _permit = self.acquire_process_slot().await;
+        let started = Instant::now();
         let mut child = spawn_with_retry(&mut command, self.command_env.binary_path())?;
+        let pid = child.id();
 
         if send_prompt_via_stdin {
             let mut stdin = child.stdin.take().ok_or(CodexError::StdinUnavailable)?;
@@ -197,9 +605,11 @@ impl CodexClient {
                     return Err(CodexError::StdinWrite(source));
                 }
             }
-            if let Err(source) = stdin.write_all(b"\n").await {
-                if source.kind() != std::io::ErrorKind::BrokenPipe {
-                    return Err(CodexError::StdinWrite(source));
+            if self.appends_prompt_newline(&prompt) {
+                if let Err(source) = stdin.write_all(b"\n").await {
+                    if source.kind() != std::io::ErrorKind::BrokenPipe {
+                        return Err(CodexError::StdinWrite(source));
+                    }
                 }
             }
             if let Err(source) = stdin.shutdown().await {
@@ -214,35 +624,42 @@ impl CodexClient {
         let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
         let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
 
-        let stdout_task = tokio::spawn(tee_stream(
+        let stdout_task = tokio::spawn(tee_stream_captured(
             stdout,
             ConsoleTarget::Stdout,
             self.mirror_stdout,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream_captured(
+            stderr,
+            ConsoleTarget::Stderr,
+            !self.quiet,
+            self.capture_options(),
         ));
-        let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !self.quiet));
 
         let wait_task = async move {
             let status = child
                 .wait()
                 .await
                 .map_err(|source| CodexError::Wait { source })?;
-            let stdout_bytes = stdout_task
+            let stdout_capture = stdout_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            let stderr_bytes = stderr_task
+            let stderr_capture = stderr_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            Ok::<_, CodexError>((status, stdout_bytes, stderr_bytes))
+            Ok::<_, CodexError>((status, stdout_capture, stderr_capture))
         };
 
-        let (status, stdout_bytes, stderr_bytes) = if self.timeout.is_zero() {
+        let (status, stdout_capture, stderr_capture) = if self.timeout.is_zero() {
             wait_task.await?
         } else {
-            match time::timeout(self.timeout, wait_task).await {
-                Ok(result) => result?,
-                Err(_) => {
+            match timeout_with_shutdown(self.timeout, wait_task, pid, self.graceful_shutdown).await
+            {
+                Some(result) => result?,
+                None => {
                     return Err(CodexError::Timeout {
                         timeout: self.timeout,
                     });
@@ -250,43 +667,76 @@ impl CodexClient {
             }
         };
 
-        let stderr_string = String::from_utf8(stderr_bytes).unwrap_or_default();
+        self.metrics.record_exec(started.elapsed(), status);
+        let stderr_string = String::from_utf8_lossy(&stderr_capture.bytes).into_owned();
         if !status.success() {
             return Err(CodexError::NonZeroExit {
+                command: command_line,
+                exit_code: status.code(),
                 status,
-                stderr: stderr_string,
+                stderr: crate::ansi::error_stderr(stderr_string, self.strip_ansi_errors),
+                truncated: stderr_capture.truncated,
+            });
+        }
+        if stdout_capture.truncated {
+            return Err(CodexError::OutputTruncated {
+                command: command_line,
+                limit: self.max_output_bytes.unwrap_or_default(),
             });
         }
 
-        let primary_output = if self.json_output && stdout_bytes.is_empty() {
+        let primary_output = if self.json_output && stdout_capture.bytes.is_empty() {
             stderr_string
         } else {
-            String::from_utf8(stdout_bytes)?
+            String::from_utf8(stdout_capture.bytes)?
         };
         let trimmed = if self.json_output {
             primary_output
         } else {
             primary_output.trim().to_string()
         };
+        if self.json_output {
+            let mut parser = crate::JsonlThreadEventParser::new();
+            for line in trimmed.lines() {
+                if let Ok(Some(ThreadEvent::TurnCompleted(completed))) = parser.parse_line(line) {
+                    if let Some(usage) = completed.usage() {
+                        self.metrics.record_tokens(usage);
+                    }
+                }
+            }
+        }
+        if self.json_output && !has_thread_started(&trimmed) {
+            let capabilities = self.probe_capabilities().await;
+            if !capabilities.features.supports_json_output {
+                return Err(CodexError::JsonModeUnsupported { stdout: trimmed });
+            }
+        }
         debug!(
             binary = ?self.command_env.binary_path(),
             bytes = trimmed.len(),
             "received Codex output"
         );
-        Ok(trimmed)
+        Ok((status, trimmed))
     }
 }
 
 /// Options configuring a streaming exec invocation.
-#[derive(Clone, Debug)]
+///
+/// Build with [`ExecStreamRequest::new`] and the chained setters; new options are added without
+/// breaking callers.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
 pub struct ExecStreamRequest {
     /// User prompt that will be forwarded to `codex exec`.
     pub prompt: String,
     /// Per-event idle timeout. If no JSON lines arrive before the duration elapses,
-    /// [`ExecStreamError::IdleTimeout`] is returned.
+    /// [`ExecStreamError::IdleTimeout`] is returned. Falls back to
+    /// [`CodexClientBuilder::idle_timeout`] when unset; `Duration::ZERO` disables idle timing.
     pub idle_timeout: Option<Duration>,
     /// Optional file path passed through to `--output-last-message`. When unset, the wrapper
-    /// will request a temporary path and return it in [`ExecCompletion::last_message_path`].
+    /// picks a path (see [`CodexClientBuilder::artifact_dir`]), returns it in
+    /// [`ExecCompletion::last_message_path`], and deletes the file after reading it unless
+    /// [`CodexClientBuilder::keep_artifacts`] is enabled.
     pub output_last_message: Option<PathBuf>,
     /// Optional file path passed through to `--output-schema` so clients can persist the schema
     /// describing the item envelope structure seen during the run.
@@ -295,6 +745,135 @@ pub struct ExecStreamRequest {
     /// Appends to existing files, flushes each line, and creates parent directories. Overrides
     /// [`CodexClientBuilder::json_event_log`] for this request when provided.
     pub json_event_log: Option<PathBuf>,
+    /// When true, a final synthetic [`ThreadEvent::ThreadCompleted`] summarizing the observed
+    /// turns, items, failures, and token usage is yielded after the last CLI event.
+    pub emit_summary: bool,
+    /// Optional self-describing transcript (header + raw JSONL) written alongside any
+    /// `json_event_log`; replay it with [`crate::replay_transcript`].
+    pub transcript: Option<crate::TranscriptSink>,
+    /// When set, a synthetic [`ThreadEvent::Heartbeat`] is yielded every interval in which no
+    /// CLI event arrived. Unlike `idle_timeout` this never fails the stream; real events restart
+    /// the interval and no heartbeat follows the end of the stream.
+    pub heartbeat: Option<Duration>,
+    /// How malformed JSONL lines are handled. [`ParseErrorMode::Fail`] (the default) yields
+    /// them as stream errors; the other modes keep the stream going.
+    pub on_parse_error: ParseErrorMode,
+    /// When true, a run that exits non-zero after `thread.started` but before the turn finished
+    /// is continued with `codex exec resume <thread_id>` (at most [`MAX_AUTO_RESUMES`] times).
+    /// Each reconnection yields a [`ThreadEvent::Resumed`] marker and the resumed events follow
+    /// on the same stream; the other request options (summary, heartbeat, transcript, parse-error
+    /// handling, filter) carry over. Reconnection stops early when a resume attempt exits
+    /// non-zero without emitting any event. Disabled by default.
+    pub auto_resume: bool,
+    /// Prompt sent on stdin to each `codex exec resume` reconnection; falls back to
+    /// [`DEFAULT_RESUME_PROMPT`]. The CLI reads a prompt when none is given, so one is always sent.
+    pub resume_prompt: Option<String>,
+    /// When set, item events whose type the filter rejects are dropped before they reach the
+    /// event channel (e.g. [`EventFilter::except`] with [`ItemKind::Reasoning`] for chat UIs).
+    /// Lifecycle events always pass; drops are counted in [`ExecCompletion::filtered_events`].
+    pub event_filter: Option<EventFilter>,
+    /// Write strategy for stdout mirroring; [`MirrorFlush::PerLine`] keeps output interactive.
+    pub mirror_flush: MirrorFlush,
+}
+
+impl ExecStreamRequest {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn output_last_message(mut self, path: impl Into<PathBuf>) -> Self {
+        self.output_last_message = Some(path.into());
+        self
+    }
+
+    pub fn output_schema(mut self, path: impl Into<PathBuf>) -> Self {
+        self.output_schema = Some(path.into());
+        self
+    }
+
+    pub fn json_event_log(mut self, path: impl Into<PathBuf>) -> Self {
+        self.json_event_log = Some(path.into());
+        self
+    }
+
+    pub fn emit_summary(mut self, enable: bool) -> Self {
+        self.emit_summary = enable;
+        self
+    }
+
+    pub fn transcript(mut self, sink: crate::TranscriptSink) -> Self {
+        self.transcript = Some(sink);
+        self
+    }
+
+    pub fn heartbeat(mut self, interval: Duration) -> Self {
+        self.heartbeat = Some(interval);
+        self
+    }
+
+    pub fn on_parse_error(mut self, mode: ParseErrorMode) -> Self {
+        self.on_parse_error = mode;
+        self
+    }
+
+    pub fn auto_resume(mut self, enable: bool) -> Self {
+        self.auto_resume = enable;
+        self
+    }
+
+    pub fn resume_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.resume_prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn event_filter(mut self, filter: EventFilter) -> Self {
+        self.event_filter = Some(filter);
+        self
+    }
+
+    pub fn mirror_flush(mut self, flush: MirrorFlush) -> Self {
+        self.mirror_flush = flush;
+        self
+    }
+}
+
+/// Upper bound on reconnections attempted for [`ExecStreamRequest::auto_resume`].
+pub const MAX_AUTO_RESUMES: u32 = 3;
+
+/// Continuation prompt used by [`ExecStreamRequest::auto_resume`] when
+/// [`ExecStreamRequest::resume_prompt`] is unset.
+pub const DEFAULT_RESUME_PROMPT: &str = "Continue from where you left off.";
+
+/// How mirrored JSONL lines are written to stdout when [`CodexClientBuilder::mirror_stdout`] is
+/// enabled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MirrorFlush {
+    /// Write and flush every line as it arrives.
+    #[default]
+    PerLine,
+    /// Coalesce lines and write them at most once per interval, trading latency for throughput
+    /// when thousands of deltas stream. Buffered lines are written when the stream ends.
+    Batched(Duration),
+}
+
+/// Handling of JSONL lines that cannot be parsed or normalized during streaming.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ParseErrorMode {
+    /// Yield [`ExecStreamError::Parse`]/[`ExecStreamError::Normalize`] on the event stream.
+    #[default]
+    Fail,
+    /// Log the line at `warn` level and drop it.
+    Skip,
+    /// Drop the line and record it in [`ExecCompletion::parse_errors`].
+    Collect,
 }
 
 /// Selector for `codex resume` targets.
@@ -317,6 +896,28 @@ pub struct ResumeRequest {
     pub overrides: CliOverridesPatch,
 }
 
+impl From<ResumeSessionRequest> for ResumeRequest {
+    fn from(request: ResumeSessionRequest) -> Self {
+        let ResumeSessionRequest {
+            session_id,
+            prompt,
+            all,
+            last,
+            overrides,
+        } = request;
+        let selector = match session_id {
+            Some(id) => ResumeSelector::Id(id),
+            None if all && !last => ResumeSelector::All,
+            None => ResumeSelector::Last,
+        };
+        Self {
+            prompt,
+            overrides,
+            ..Self::new(selector)
+        }
+    }
+}
+
 impl ResumeRequest {
     pub fn new(selector: ResumeSelector) -> Self {
         Self {
@@ -406,9 +1007,66 @@ impl ResumeRequest {
 /// `events` yields parsed [`ThreadEvent`] values as soon as each JSONL line arrives from the CLI.
 /// `completion` resolves once the Codex process exits and is the place to surface `--output-last-message`
 /// and `--output-schema` paths after streaming finishes.
+///
+/// Dropping `completion` (or the whole stream) before it resolves kills the child process and
+/// aborts the background stdout/stderr forwarding tasks. To cancel from elsewhere without owning
+/// the stream, use [`ExecStream::abort_handle`].
 pub struct ExecStream {
     pub events: DynThreadEventStream,
     pub completion: DynExecCompletion,
+    abort: ExecAbortHandle,
+}
+
+impl ExecStream {
+    /// Returns a cloneable handle that kills the child and ends both `events` and `completion`
+    /// with [`ExecStreamError::Cancelled`].
+    pub fn abort_handle(&self) -> ExecAbortHandle {
+        self.abort.clone()
+    }
+
+    /// Splits the stream into its event stream and completion future, e.g. to `select!` over
+    /// both; dropping the completion still kills the child.
+    pub fn into_parts(self) -> (DynThreadEventStream, DynExecCompletion) {
+        (self.events, self.completion)
+    }
+
+    /// Feeds every event to `handler`, then waits for the process to exit.
+    ///
+    /// Stops at the first event error, kills the child, and returns that error; see
+    /// [`Self::run_with`] to keep going past recoverable errors.
+    pub async fn run(
+        self,
+        handler: impl FnMut(ThreadEvent),
+    ) -> Result<ExecCompletion, ExecStreamError> {
+        self.run_with(false, handler).await
+    }
+
+    /// Like [`Self::run`], but with `continue_on_error` set, event errors are dropped and the
+    /// stream is drained to the end. Parse failures are better kept via
+    /// [`ParseErrorMode::Collect`], which records them in [`ExecCompletion::parse_errors`].
+    pub async fn run_with(
+        mut self,
+        continue_on_error: bool,
+        mut handler: impl FnMut(ThreadEvent),
+    ) -> Result<ExecCompletion, ExecStreamError> {
+        while let Some(event) = self.events.next().await {
+            match event {
+                Ok(event) => handler(event),
+                Err(_) if continue_on_error => {}
+                Err(err) => {
+                    self.abort.abort();
+                    return Err(err);
+                }
+            }
+        }
+        self.completion.await
+    }
+
+    /// Drains the remaining events and waits for the process to exit.
+    async fn finish(mut self) -> Result<ExecCompletion, ExecStreamError> {
+        while self.events.next().await.is_some() {}
+        self.completion.await
+    }
 }
 
 /// Type-erased stream of events from the Codex CLI.
@@ -424,11 +1082,86 @@ pub type DynExecCompletion =
 pub struct ExecCompletion {
     pub status: ExitStatus,
     /// Path that codex wrote when `--output-last-message` was enabled. The wrapper may eagerly
-    /// read the file and populate `last_message` when feasible.
+    /// read the file and populate `last_message` when feasible; wrapper-chosen paths are already
+    /// deleted by then unless [`CodexClientBuilder::keep_artifacts`] is enabled.
     pub last_message_path: Option<PathBuf>,
     pub last_message: Option<String>,
+    /// Text of the final completed `agent_message` item seen on the JSON stream. Unlike
+    /// `last_message`, this never reflects tool output; `None` when no agent message was emitted.
+    pub agent_message: Option<String>,
     /// Path to the JSON schema requested via `--output-schema`, if provided by the caller.
     pub schema_path: Option<PathBuf>,
+    /// Malformed lines dropped under [`ParseErrorMode::Collect`], in stream order.
+    pub parse_errors: Vec<crate::LineParseError>,
+    /// Item events withheld by [`ExecStreamRequest::event_filter`], per item type. With
+    /// `auto_resume` this covers the final CLI process only.
+    pub filtered_events: BTreeMap<ItemKind, u64>,
+}
+
+impl ExecCompletion {
+    /// Deserializes [`Self::last_message`] as JSON, e.g. the structured reply of an
+    /// `output_schema` run. Returns `Ok(None)` when no last message was captured; surrounding
+    /// whitespace is ignored.
+    pub fn last_message_json<T: DeserializeOwned>(&self) -> Result<Option<T>, serde_json::Error> {
+        self.last_message
+            .as_deref()
+            .map(|message| serde_json::from_str(message.trim()))
+            .transpose()
+    }
+
+    /// Collapses the completion into the final reply text, mirroring [`CodexClient::send_prompt`]
+    /// for streaming callers that only need the answer once events are handled.
+    ///
+    /// On success returns [`Self::agent_message`], falling back to [`Self::last_message`] and
+    /// then to an empty string. A failed status becomes [`CodexError::NonZeroExit`] with
+    /// `command` set to `codex exec` and empty `stderr`, since the completion records neither.
+    pub fn into_result(self) -> Result<String, CodexError> {
+        if !self.status.success() {
+            return Err(CodexError::NonZeroExit {
+                command: "codex exec".to_string(),
+                exit_code: self.status.code(),
+                status: self.status,
+                stderr: String::new(),
+                truncated: false,
+            });
+        }
+        Ok(self.agent_message.or(self.last_message).unwrap_or_default())
+    }
+}
+
+/// Approximate prompt size returned by [`CodexClient::estimate_prompt`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PromptEstimate {
+    /// Heuristic input token count; expect it to undershoot the billed figure.
+    pub approx_input_tokens: usize,
+}
+
+impl PromptEstimate {
+    /// Flat surcharge assumed for each attached image.
+    pub const TOKENS_PER_IMAGE: usize = 765;
+
+    /// Ratio of the actual `input_tokens` reported for the run to this estimate, e.g. `1.8`
+    /// when the CLI context nearly doubled the prompt. `None` for an empty estimate.
+    pub fn calibration(&self, actual: &TokenUsage) -> Option<f64> {
+        (self.approx_input_tokens > 0)
+            .then(|| actual.input_tokens as f64 / self.approx_input_tokens as f64)
+    }
+}
+
+/// Everything captured by [`CodexClient::exec_collect`].
+#[derive(Clone, Debug)]
+pub struct ExecResult {
+    /// Raw JSONL stdout as printed by the CLI.
+    pub raw: String,
+    /// Events parsed from `raw`, in order; malformed lines are skipped.
+    pub events: Vec<ThreadEvent>,
+    /// Text of the last completed `agent_message` item, if any.
+    pub last_message: Option<String>,
+    pub status: ExitStatus,
+    /// Sum of the `usage` objects on `turn.completed` events; `None` when none reported usage.
+    pub usage: Option<TokenUsage>,
+    /// Lines that could not be parsed into events, in stream order.
+    pub parse_errors: Vec<crate::LineParseError>,
 }
 
 /// Errors that may occur while consuming the JSONL stream.
@@ -448,12 +1181,114 @@ pub enum ExecStreamError {
     IdleTimeout { idle_for: Duration },
     #[error("codex JSON stream closed unexpectedly")]
     ChannelClosed,
+    #[error("codex exec stream was cancelled")]
+    Cancelled,
+}
+
+/// Tracks the agent message currently being streamed so whole-message snapshots only forward
+/// text that was not already delivered as deltas.
+#[derive(Default)]
+struct AgentMessageProgress {
+    item_id: Option<String>,
+    text: String,
+}
+
+impl AgentMessageProgress {
+    /// Forwards new agent-message text to `on_chunk`; returns false for unrelated events.
+    fn observe(&mut self, event: &ThreadEvent, on_chunk: &mut impl FnMut(&str)) -> bool {
+        let (item_id, delta, snapshot) = match event {
+            ThreadEvent::ItemDelta(delta) => match &delta.delta {
+                ItemDeltaPayload::AgentMessage(text) => {
+                    (&delta.item_id, Some(&text.text_delta), None)
+                }
+                _ => return false,
+            },
+            ThreadEvent::ItemStarted(envelope) | ThreadEvent::ItemCompleted(envelope) => {
+                match &envelope.item.payload {
+                    ItemPayload::AgentMessage(content) => {
+                        (&envelope.item.item_id, None, Some(&content.text))
+                    }
+                    _ => return false,
+                }
+            }
+            _ => return false,
+        };
+
+        if self.item_id.as_ref() != Some(item_id) {
+            self.item_id = Some(item_id.clone());
+            self.text.clear();
+        }
+        if let Some(delta) = delta {
+            self.text.push_str(delta);
+            if !delta.is_empty() {
+                on_chunk(delta);
+            }
+        }
+        if let Some(snapshot) = snapshot {
+            if let Some(rest) = snapshot.strip_prefix(self.text.as_str()) {
+                if !rest.is_empty() {
+                    on_chunk(rest);
+                }
+            }
+            self.text = snapshot.clone();
+        }
+        true
+    }
+}
+
+fn exec_stream_error_into_codex(error: ExecStreamError) -> CodexError {
+    match error {
+        ExecStreamError::Codex(source) => source,
+        other => CodexError::Stream(Box::new(other)),
+    }
+}
+
+fn has_thread_started(output: &str) -> bool {
+    output.lines().any(|line| {
+        matches!(
+            ThreadEvent::try_from(line),
+            Ok(ThreadEvent::ThreadStarted(_))
+        )
+    })
 }
 
 async fn read_last_message(path: &Path) -> Option<String> {
     (fs::read_to_string(path).await).ok()
 }
 
+/// Canonicalizes an attachment so it survives Codex running in a different working directory.
+async fn resolve_attachment(path: PathBuf) -> Result<PathBuf, CodexError> {
+    match fs::canonicalize(&path).await {
+        Ok(resolved) if resolved.is_file() => Ok(resolved),
+        _ => Err(CodexError::AttachmentNotFound { path }),
+    }
+}
+
+/// Picks the path for a wrapper-managed artifact, numbered under
+/// [`CodexClientBuilder::artifact_dir`] when set and timestamped in the OS temp dir otherwise.
+async fn artifact_path(
+    client: &CodexClient,
+    prefix: &str,
+    extension: &str,
+) -> Result<PathBuf, CodexError> {
+    static ARTIFACT_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let Some(dir) = &client.artifact_dir else {
+        return Ok(unique_temp_path(prefix, extension));
+    };
+    fs::create_dir_all(dir)
+        .await
+        .map_err(|source| CodexError::PrepareArtifactDir {
+            path: dir.clone(),
+            source,
+        })?;
+    let counter = ARTIFACT_COUNTER.fetch_add(1, Ordering::Relaxed);
+    Ok(dir.join(format!(
+        "{prefix}{}_{counter}.{extension}",
+        std::process::id()
+    )))
+}
+
 fn unique_temp_path(prefix: &str, extension: &str) -> PathBuf {
     let mut path = env::temp_dir();
     let timestamp = SystemTime::now()
@@ -0,0 +1,141 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use tokio::sync::watch;
+
+use super::{DynExecCompletion, DynThreadEventStream, ExecStreamError};
+use crate::process::kill_pid;
+
+/// Cancels an in-flight [`super::ExecStream`] from any task.
+///
+/// Obtained via [`super::ExecStream::abort_handle`]. Clones share the same signal, and aborting a
+/// stream whose child already exited is a no-op.
+#[derive(Clone, Debug)]
+pub struct ExecAbortHandle {
+    inner: Arc<AbortInner>,
+}
+
+#[derive(Debug)]
+struct AbortInner {
+    signal: watch::Sender<bool>,
+    /// Child pid while it may still be running; cleared once the exit status is reaped so a
+    /// late abort never signals a recycled pid.
+    pid: Mutex<Option<u32>>,
+}
+
+impl ExecAbortHandle {
+    pub(crate) fn new(pid: Option<u32>) -> Self {
+        let (signal, _) = watch::channel(false);
+        Self {
+            inner: Arc::new(AbortInner {
+                signal,
+                pid: Mutex::new(pid),
+            }),
+        }
+    }
+
+    /// Kills the Codex child and ends the event stream and completion future with
+    /// [`ExecStreamError::Cancelled`].
+    pub fn abort(&self) {
+        let Some(pid) = self.take_pid() else {
+            return;
+        };
+        self.inner.signal.send_replace(true);
+        kill_pid(pid);
+    }
+
+    /// Returns true once [`ExecAbortHandle::abort`] has taken effect.
+    pub fn is_aborted(&self) -> bool {
+        *self.inner.signal.borrow()
+    }
+
+    /// Records that the child was reaped; later aborts become no-ops.
+    pub(crate) fn child_exited(&self) {
+        self.take_pid();
+    }
+
+    /// Takes over the child tracked by `other`, e.g. when a stream switches to a resumed run.
+    /// The child is killed right away if this handle was already aborted.
+    pub(crate) fn adopt(&self, other: &ExecAbortHandle) {
+        let pid = other.take_pid();
+        *self
+            .inner
+            .pid
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = pid;
+        if self.is_aborted() {
+            if let Some(pid) = self.take_pid() {
+                kill_pid(pid);
+            }
+        }
+    }
+
+    fn take_pid(&self) -> Option<u32> {
+        self.inner
+            .pid
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+    }
+
+    fn aborted(&self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let mut receiver = self.inner.signal.subscribe();
+        Box::pin(async move {
+            if receiver.wait_for(|aborted| *aborted).await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        })
+    }
+
+    /// Ends `events` with a single [`ExecStreamError::Cancelled`] once aborted.
+    pub(crate) fn wrap_events(&self, events: DynThreadEventStream) -> DynThreadEventStream {
+        Box::pin(AbortableEvents {
+            events,
+            aborted: self.aborted(),
+            finished: false,
+        })
+    }
+
+    /// Resolves `completion` to [`ExecStreamError::Cancelled`] once aborted, dropping (and so
+    /// killing) the child.
+    pub(crate) fn wrap_completion(&self, completion: DynExecCompletion) -> DynExecCompletion {
+        let aborted = self.aborted();
+        Box::pin(async move {
+            tokio::select! {
+                biased;
+                _ = aborted => Err(ExecStreamError::Cancelled),
+                result = completion => result,
+            }
+        })
+    }
+}
+
+struct AbortableEvents {
+    events: DynThreadEventStream,
+    aborted: Pin<Box<dyn Future<Output = ()> + Send>>,
+    finished: bool,
+}
+
+impl Stream for AbortableEvents {
+    type Item = <DynThreadEventStream as Stream>::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        if self.aborted.as_mut().poll(cx).is_ready() {
+            self.finished = true;
+            return Poll::Ready(Some(Err(ExecStreamError::Cancelled)));
+        }
+        let next = self.events.as_mut().poll_next(cx);
+        if let Poll::Ready(None) = next {
+            self.finished = true;
+        }
+        next
+    }
+}
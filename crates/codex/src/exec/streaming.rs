@@ -1,17 +1,43 @@
-use tokio::{io::AsyncWriteExt, process::Command, sync::mpsc, time};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use futures_util::StreamExt;
+use tokio::{
+    fs,
+    io::AsyncWriteExt,
+    process::Command,
+    sync::mpsc,
+    task::{JoinError, JoinHandle},
+};
 
 use super::{
-    read_last_message, unique_temp_path, ExecCompletion, ExecStream, ExecStreamError,
-    ExecStreamRequest, ResumeRequest, ResumeSelector,
+    artifact_path, read_last_message, ExecAbortHandle, ExecCompletion, ExecStream, ExecStreamError,
+    ExecStreamRequest, ResumeRequest, ResumeSelector, DEFAULT_RESUME_PROMPT, MAX_AUTO_RESUMES,
 };
 use crate::{
     builder::{apply_cli_overrides, resolve_cli_overrides},
-    capabilities::{guard_is_supported, log_guard_skip},
+    capabilities::guard_is_supported,
     jsonl,
-    process::{spawn_with_retry, tee_stream, ConsoleTarget},
-    CliOverridesPatch, CodexClient, CodexError,
+    process::{spawn_with_retry, tee_stream_captured, timeout_with_shutdown, ConsoleTarget},
+    CliOverridesPatch, CodexClient, CodexError, EventFilter, MirrorFlush, ParseErrorMode,
+    ThreadEvent, TranscriptSink,
 };
 
+/// Per-segment stream options that [`ExecStreamRequest::auto_resume`] carries from the original
+/// request into each `codex exec resume` reconnection.
+#[derive(Clone, Debug, Default)]
+pub(super) struct SegmentOptions {
+    heartbeat: Option<std::time::Duration>,
+    transcript: Option<TranscriptSink>,
+    on_parse_error: ParseErrorMode,
+    event_filter: Option<EventFilter>,
+    mirror_flush: MirrorFlush,
+}
+
 pub(super) async fn stream_exec_with_overrides(
     client: &CodexClient,
     request: ExecStreamRequest,
@@ -27,12 +53,42 @@ pub(super) async fn stream_exec_with_overrides(
         output_last_message,
         output_schema,
         json_event_log,
+        emit_summary,
+        transcript,
+        heartbeat,
+        on_parse_error,
+        auto_resume,
+        resume_prompt,
+        event_filter,
+        mirror_flush,
     } = request;
+    let resume_template = auto_resume.then(|| {
+        let request = ResumeRequest {
+            selector: ResumeSelector::Last,
+            prompt: Some(resume_prompt.unwrap_or_else(|| DEFAULT_RESUME_PROMPT.to_string())),
+            idle_timeout,
+            output_last_message: output_last_message.clone(),
+            output_schema: output_schema.clone(),
+            json_event_log: json_event_log.clone(),
+            overrides: overrides.clone(),
+        };
+        let options = SegmentOptions {
+            heartbeat,
+            transcript: transcript.clone(),
+            on_parse_error,
+            event_filter: event_filter.clone(),
+            mirror_flush,
+        };
+        (request, options)
+    });
 
-    let dir_ctx = client.directory_context()?;
+    let dir_ctx = client.exec_directory_context().await?;
     let dir_path = dir_ctx.path().to_path_buf();
-    let last_message_path =
-        output_last_message.unwrap_or_else(|| unique_temp_path("codex_last_message_", "txt"));
+    let discard_last_message = output_last_message.is_none() && !client.keep_artifacts;
+    let last_message_path = match output_last_message {
+        Some(path) => path,
+        None => artifact_path(client, "codex_last_message_", "txt").await?,
+    };
     let needs_capabilities = output_schema.is_some() || !client.add_dirs.is_empty();
     let capabilities = if needs_capabilities {
         Some(client.probe_capabilities().await)
@@ -62,16 +118,7 @@ pub(super) async fn stream_exec_with_overrides(
     }
 
     if let Some(capabilities) = &capabilities {
-        if !client.add_dirs.is_empty() {
-            let guard = capabilities.guard_add_dir();
-            if guard_is_supported(&guard) {
-                for dir in &client.add_dirs {
-                    command.arg("--add-dir").arg(dir);
-                }
-            } else {
-                log_guard_skip(&guard);
-            }
-        }
+        client.apply_add_dirs(&mut command, capabilities)?;
     }
 
     for image in &client.images {
@@ -86,7 +133,7 @@ pub(super) async fn stream_exec_with_overrides(
             if guard_is_supported(&guard) {
                 command.arg("--output-schema").arg(schema_path);
             } else {
-                log_guard_skip(&guard);
+                client.guard_skip.report(&guard);
             }
         } else {
             command.arg("--output-schema").arg(schema_path);
@@ -94,8 +141,13 @@ pub(super) async fn stream_exec_with_overrides(
     }
 
     client.command_env.apply(&mut command)?;
+    let command_line = crate::process::redacted_command_line(&command);
 
+    let permit = client.acquire_process_slot().await;
+    let started = Instant::now();
     let mut child = spawn_with_retry(&mut command, client.command_env.binary_path())?;
+    let pid = child.id();
+    let abort = ExecAbortHandle::new(pid);
 
     {
         let mut stdin = child.stdin.take().ok_or(CodexError::StdinUnavailable)?;
@@ -104,9 +156,11 @@ pub(super) async fn stream_exec_with_overrides(
                 return Err(CodexError::StdinWrite(source).into());
             }
         }
-        if let Err(source) = stdin.write_all(b"\n").await {
-            if source.kind() != std::io::ErrorKind::BrokenPipe {
-                return Err(CodexError::StdinWrite(source).into());
+        if client.appends_prompt_newline(&prompt) {
+            if let Err(source) = stdin.write_all(b"\n").await {
+                if source.kind() != std::io::ErrorKind::BrokenPipe {
+                    return Err(CodexError::StdinWrite(source).into());
+                }
             }
         }
         if let Err(source) = stdin.shutdown().await {
@@ -124,67 +178,199 @@ pub(super) async fn stream_exec_with_overrides(
         json_event_log
             .or_else(|| client.json_event_log.clone())
             .filter(|path| !path.as_os_str().is_empty()),
+        client.json_event_log_compression,
     )
     .await?;
-    let stdout_task = tokio::spawn(jsonl::forward_json_events(
+    let mut json_logs: Vec<_> = json_log.into_iter().collect();
+    if let Some(transcript) = transcript {
+        let sink = transcript
+            .open(&prompt)
+            .await
+            .map_err(|err| ExecStreamError::from(CodexError::CaptureIo(err)))?;
+        json_logs.push(sink);
+    }
+    let stdout_task = AbortOnDrop(tokio::spawn(jsonl::forward_json_events(
         stdout,
         tx,
-        client.mirror_stdout,
-        json_log,
-    ));
-    let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !client.quiet));
-
-    let events = jsonl::EventChannelStream::new(rx, idle_timeout);
+        client.mirror_stdout.then_some(mirror_flush),
+        json_logs,
+        client.capture_options(),
+        on_parse_error,
+        event_filter,
+    )));
+    let stderr_task = AbortOnDrop(tokio::spawn(tee_stream_captured(
+        stderr,
+        ConsoleTarget::Stderr,
+        !client.quiet,
+        client.capture_options(),
+    )));
+
+    // With auto-resume the summary is emitted once for the whole stream, not per segment.
+    let events = jsonl::EventChannelStream::new(rx, resolve_idle_timeout(client, idle_timeout))
+        .with_summary(emit_summary && !auto_resume)
+        .with_heartbeat(heartbeat)
+        .with_metrics(client.metrics.clone());
     let timeout = client.timeout;
+    let graceful_shutdown = client.graceful_shutdown;
+    let strip_ansi_errors = client.strip_ansi_errors;
     let schema_path = output_schema.clone();
+    let child_abort = abort.clone();
+    let metrics = client.metrics.clone();
     let completion = Box::pin(async move {
         let _dir_ctx = dir_ctx;
+        let _permit = permit;
+        let exec_metrics = metrics.clone();
         let wait_task = async move {
             let status = child
                 .wait()
                 .await
                 .map_err(|source| CodexError::Wait { source })?;
-            let stdout_result = stdout_task.await.map_err(CodexError::Join)?;
-            stdout_result?;
-            let stderr_bytes = stderr_task
+            exec_metrics.record_exec(started.elapsed(), status);
+            child_abort.child_exited();
+            let forwarded = stdout_task.await.map_err(CodexError::Join)??;
+            let stderr_capture = stderr_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
+            let last_message = read_last_message(&last_message_path).await;
+            if discard_last_message {
+                let _ = fs::remove_file(&last_message_path).await;
+            }
             if !status.success() {
                 return Err(CodexError::NonZeroExit {
+                    command: command_line,
+                    exit_code: status.code(),
                     status,
-                    stderr: String::from_utf8(stderr_bytes).unwrap_or_default(),
+                    stderr: crate::ansi::error_stderr(
+                        String::from_utf8_lossy(&stderr_capture.bytes).into_owned(),
+                        strip_ansi_errors,
+                    ),
+                    truncated: stderr_capture.truncated,
                 }
                 .into());
             }
-            let last_message = read_last_message(&last_message_path).await;
             Ok(ExecCompletion {
                 status,
                 last_message_path: Some(last_message_path),
                 last_message,
+                agent_message: forwarded.agent_message,
                 schema_path,
+                parse_errors: forwarded.parse_errors,
+                filtered_events: forwarded.filtered,
             })
         };
 
-        if timeout.is_zero() {
+        let result = if timeout.is_zero() {
             wait_task.await
         } else {
-            match time::timeout(timeout, wait_task).await {
-                Ok(result) => result,
-                Err(_) => Err(CodexError::Timeout { timeout }.into()),
+            match timeout_with_shutdown(timeout, wait_task, pid, graceful_shutdown).await {
+                Some(result) => result,
+                None => Err(CodexError::Timeout { timeout }.into()),
             }
-        }
+        };
+        metrics.record_result(&result);
+        result
     });
 
-    Ok(ExecStream {
-        events: Box::pin(events),
-        completion,
+    let stream = ExecStream {
+        events: abort.wrap_events(Box::pin(events)),
+        completion: abort.wrap_completion(completion),
+        abort,
+    };
+    Ok(match resume_template {
+        Some((template, options)) => {
+            with_auto_resume(client.clone(), stream, template, options, emit_summary)
+        }
+        None => stream,
     })
 }
 
+/// Re-exposes `first` as one stream that continues the thread with `codex exec resume` when
+/// the CLI exits non-zero after `thread.started` but before the turn finished.
+///
+/// A resume attempt that exits non-zero before emitting any event ends the retries, since the
+/// next attempt would fail the same way.
+fn with_auto_resume(
+    client: CodexClient,
+    first: ExecStream,
+    template: ResumeRequest,
+    options: SegmentOptions,
+    emit_summary: bool,
+) -> ExecStream {
+    let abort = ExecAbortHandle::new(None);
+    abort.adopt(&first.abort);
+
+    let (tx, rx) = mpsc::channel(32);
+    let driver_abort = abort.clone();
+    let driver = AbortOnDrop(tokio::spawn(async move {
+        let mut stream = first;
+        let mut thread_id = None;
+        let mut turn_finished = false;
+        let mut attempt = 0;
+        loop {
+            let mut segment_events = 0usize;
+            while let Some(event) = stream.events.next().await {
+                if !matches!(event, Ok(ThreadEvent::Heartbeat { .. })) {
+                    segment_events += 1;
+                }
+                match &event {
+                    Ok(ThreadEvent::ThreadStarted(started)) => {
+                        thread_id = Some(started.thread_id.clone());
+                    }
+                    Ok(ThreadEvent::TurnStarted(_)) => turn_finished = false,
+                    Ok(ThreadEvent::TurnCompleted(_) | ThreadEvent::TurnFailed(_)) => {
+                        turn_finished = true;
+                    }
+                    _ => {}
+                }
+                if tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+            let result = stream.completion.await;
+            driver_abort.child_exited();
+
+            let resumable = matches!(
+                result,
+                Err(ExecStreamError::Codex(CodexError::NonZeroExit { .. }))
+            ) && !turn_finished
+                && (attempt == 0 || segment_events > 0)
+                && attempt < MAX_AUTO_RESUMES
+                && !driver_abort.is_aborted()
+                && !tx.is_closed();
+            let Some(id) = thread_id.clone().filter(|_| resumable) else {
+                return result;
+            };
+
+            attempt += 1;
+            tracing::warn!(thread_id = %id, attempt, "codex exited mid-turn; resuming thread");
+            let _ = tx
+                .send(Ok(ThreadEvent::Resumed {
+                    thread_id: id.clone(),
+                    attempt,
+                }))
+                .await;
+            let mut request = template.clone();
+            request.selector = ResumeSelector::Id(id);
+            stream = stream_resume(&client, request, options.clone()).await?;
+            driver_abort.adopt(&stream.abort);
+        }
+    }));
+
+    let events = jsonl::EventChannelStream::new(rx, None).with_summary(emit_summary);
+    let completion = Box::pin(async move { driver.await.map_err(CodexError::Join)? });
+
+    ExecStream {
+        events: abort.wrap_events(Box::pin(events)),
+        completion: abort.wrap_completion(completion),
+        abort,
+    }
+}
+
 pub(super) async fn stream_resume(
     client: &CodexClient,
     request: ResumeRequest,
+    options: SegmentOptions,
 ) -> Result<ExecStream, ExecStreamError> {
     if let Some(prompt) = &request.prompt {
         if prompt.trim().is_empty() {
@@ -201,11 +387,21 @@ pub(super) async fn stream_resume(
         json_event_log,
         overrides,
     } = request;
-
-    let dir_ctx = client.directory_context()?;
+    let SegmentOptions {
+        heartbeat,
+        transcript,
+        on_parse_error,
+        event_filter,
+        mirror_flush,
+    } = options;
+
+    let dir_ctx = client.exec_directory_context().await?;
     let dir_path = dir_ctx.path().to_path_buf();
-    let last_message_path =
-        output_last_message.unwrap_or_else(|| unique_temp_path("codex_last_message_", "txt"));
+    let discard_last_message = output_last_message.is_none() && !client.keep_artifacts;
+    let last_message_path = match output_last_message {
+        Some(path) => path,
+        None => artifact_path(client, "codex_last_message_", "txt").await?,
+    };
     let needs_capabilities = output_schema.is_some() || !client.add_dirs.is_empty();
     let capabilities = if needs_capabilities {
         Some(client.probe_capabilities().await)
@@ -235,16 +431,7 @@ pub(super) async fn stream_resume(
     }
 
     if let Some(capabilities) = &capabilities {
-        if !client.add_dirs.is_empty() {
-            let guard = capabilities.guard_add_dir();
-            if guard_is_supported(&guard) {
-                for dir in &client.add_dirs {
-                    command.arg("--add-dir").arg(dir);
-                }
-            } else {
-                log_guard_skip(&guard);
-            }
-        }
+        client.apply_add_dirs(&mut command, capabilities)?;
     }
 
     for image in &client.images {
@@ -259,7 +446,7 @@ pub(super) async fn stream_resume(
             if guard_is_supported(&guard) {
                 command.arg("--output-schema").arg(schema_path);
             } else {
-                log_guard_skip(&guard);
+                client.guard_skip.report(&guard);
             }
         } else {
             command.arg("--output-schema").arg(schema_path);
@@ -286,8 +473,13 @@ pub(super) async fn stream_resume(
     }
 
     client.command_env.apply(&mut command)?;
+    let command_line = crate::process::redacted_command_line(&command);
 
+    let permit = client.acquire_process_slot().await;
+    let started = Instant::now();
     let mut child = spawn_with_retry(&mut command, client.command_env.binary_path())?;
+    let pid = child.id();
+    let abort = ExecAbortHandle::new(pid);
 
     if let Some(prompt) = &prompt {
         let mut stdin = child.stdin.take().ok_or(CodexError::StdinUnavailable)?;
@@ -296,9 +488,11 @@ pub(super) async fn stream_resume(
                 return Err(CodexError::StdinWrite(source).into());
             }
         }
-        if let Err(source) = stdin.write_all(b"\n").await {
-            if source.kind() != std::io::ErrorKind::BrokenPipe {
-                return Err(CodexError::StdinWrite(source).into());
+        if client.appends_prompt_newline(prompt) {
+            if let Err(source) = stdin.write_all(b"\n").await {
+                if source.kind() != std::io::ErrorKind::BrokenPipe {
+                    return Err(CodexError::StdinWrite(source).into());
+                }
             }
         }
         if let Err(source) = stdin.shutdown().await {
@@ -318,60 +512,129 @@ pub(super) async fn stream_resume(
         json_event_log
             .or_else(|| client.json_event_log.clone())
             .filter(|path| !path.as_os_str().is_empty()),
+        client.json_event_log_compression,
     )
     .await?;
-    let stdout_task = tokio::spawn(jsonl::forward_json_events(
+    let mut json_logs: Vec<_> = json_log.into_iter().collect();
+    if let Some(transcript) = transcript {
+        let sink = transcript
+            .open(prompt.as_deref().unwrap_or_default())
+            .await
+            .map_err(|err| ExecStreamError::from(CodexError::CaptureIo(err)))?;
+        json_logs.push(sink);
+    }
+    let stdout_task = AbortOnDrop(tokio::spawn(jsonl::forward_json_events(
         stdout,
         tx,
-        client.mirror_stdout,
-        json_log,
-    ));
-    let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !client.quiet));
-
-    let events = jsonl::EventChannelStream::new(rx, idle_timeout);
+        client.mirror_stdout.then_some(mirror_flush),
+        json_logs,
+        client.capture_options(),
+        on_parse_error,
+        event_filter,
+    )));
+    let stderr_task = AbortOnDrop(tokio::spawn(tee_stream_captured(
+        stderr,
+        ConsoleTarget::Stderr,
+        !client.quiet,
+        client.capture_options(),
+    )));
+
+    let events = jsonl::EventChannelStream::new(rx, resolve_idle_timeout(client, idle_timeout))
+        .with_heartbeat(heartbeat)
+        .with_metrics(client.metrics.clone());
     let timeout = client.timeout;
+    let graceful_shutdown = client.graceful_shutdown;
+    let strip_ansi_errors = client.strip_ansi_errors;
     let schema_path = output_schema.clone();
+    let child_abort = abort.clone();
+    let metrics = client.metrics.clone();
     let completion = Box::pin(async move {
         let _dir_ctx = dir_ctx;
+        let _permit = permit;
+        let exec_metrics = metrics.clone();
         let wait_task = async move {
             let status = child
                 .wait()
                 .await
                 .map_err(|source| CodexError::Wait { source })?;
-            let stdout_result = stdout_task.await.map_err(CodexError::Join)?;
-            stdout_result?;
-            let stderr_bytes = stderr_task
+            exec_metrics.record_exec(started.elapsed(), status);
+            child_abort.child_exited();
+            let forwarded = stdout_task.await.map_err(CodexError::Join)??;
+            let stderr_capture = stderr_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
+            let last_message = read_last_message(&last_message_path).await;
+            if discard_last_message {
+                let _ = fs::remove_file(&last_message_path).await;
+            }
             if !status.success() {
                 return Err(CodexError::NonZeroExit {
+                    command: command_line,
+                    exit_code: status.code(),
                     status,
-                    stderr: String::from_utf8(stderr_bytes).unwrap_or_default(),
+                    stderr: crate::ansi::error_stderr(
+                        String::from_utf8_lossy(&stderr_capture.bytes).into_owned(),
+                        strip_ansi_errors,
+                    ),
+                    truncated: stderr_capture.truncated,
                 }
                 .into());
             }
-            let last_message = read_last_message(&last_message_path).await;
             Ok(ExecCompletion {
                 status,
                 last_message_path: Some(last_message_path),
                 last_message,
+                agent_message: forwarded.agent_message,
                 schema_path,
+                parse_errors: forwarded.parse_errors,
+                filtered_events: forwarded.filtered,
             })
         };
 
-        if timeout.is_zero() {
+        let result = if timeout.is_zero() {
             wait_task.await
         } else {
-            match time::timeout(timeout, wait_task).await {
-                Ok(result) => result,
-                Err(_) => Err(CodexError::Timeout { timeout }.into()),
+            match timeout_with_shutdown(timeout, wait_task, pid, graceful_shutdown).await {
+                Some(result) => result,
+                None => Err(CodexError::Timeout { timeout }.into()),
             }
-        }
+        };
+        metrics.record_result(&result);
+        result
     });
 
     Ok(ExecStream {
-        events: Box::pin(events),
-        completion,
+        events: abort.wrap_events(Box::pin(events)),
+        completion: abort.wrap_completion(completion),
+        abort,
     })
 }
+
+/// Picks the per-request idle timeout, falling back to the client default; zero disables it.
+fn resolve_idle_timeout(
+    client: &CodexClient,
+    idle_timeout: Option<std::time::Duration>,
+) -> Option<std::time::Duration> {
+    idle_timeout
+        .or(client.idle_timeout)
+        .filter(|duration| !duration.is_zero())
+}
+
+/// Aborts the wrapped task when dropped so an abandoned [`ExecStream`] does not leave
+/// forwarding tasks writing to mirrors or log sinks after the caller has moved on.
+struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
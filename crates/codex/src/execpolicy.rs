@@ -7,7 +7,9 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tokio::{process::Command, time};
+use tokio::process::Command;
+
+use crate::process::{tee_stream_captured, timeout_with_shutdown};
 
 use super::{
     apply_cli_overrides, resolve_cli_overrides, spawn_with_retry, tee_stream, CliOverridesPatch,
@@ -257,8 +259,10 @@ impl CodexClient {
         process.args(&command);
 
         self.command_env.apply(&mut process)?;
+        let command_line = crate::process::redacted_command_line(&process);
 
         let mut child = spawn_with_retry(&mut process, self.command_env.binary_path())?;
+        let pid = child.id();
 
         let stdout = child.stdout.take().ok_or(CodexError::StdoutUnavailable)?;
         let stderr = child.stderr.take().ok_or(CodexError::StderrUnavailable)?;
@@ -267,8 +271,14 @@ impl CodexClient {
             stdout,
             ConsoleTarget::Stdout,
             self.mirror_stdout,
+            self.capture_options(),
+        ));
+        let stderr_task = tokio::spawn(tee_stream_captured(
+            stderr,
+            ConsoleTarget::Stderr,
+            !self.quiet,
+            self.capture_options(),
         ));
-        let stderr_task = tokio::spawn(tee_stream(stderr, ConsoleTarget::Stderr, !self.quiet));
 
         let wait_task = async move {
             let status = child
@@ -279,19 +289,20 @@ impl CodexClient {
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            let stderr_bytes = stderr_task
+            let stderr_capture = stderr_task
                 .await
                 .map_err(CodexError::Join)?
                 .map_err(CodexError::CaptureIo)?;
-            Ok::<_, CodexError>((status, stdout_bytes, stderr_bytes))
+            Ok::<_, CodexError>((status, stdout_bytes, stderr_capture))
         };
 
-        let (status, stdout_bytes, stderr_bytes) = if self.timeout.is_zero() {
+        let (status, stdout_bytes, stderr_capture) = if self.timeout.is_zero() {
             wait_task.await?
         } else {
-            match time::timeout(self.timeout, wait_task).await {
-                Ok(result) => result?,
-                Err(_) => {
+            match timeout_with_shutdown(self.timeout, wait_task, pid, self.graceful_shutdown).await
+            {
+                Some(result) => result?,
+                None => {
                     return Err(CodexError::Timeout {
                         timeout: self.timeout,
                     });
@@ -300,12 +311,15 @@ impl CodexClient {
         };
 
         let stdout_string = String::from_utf8(stdout_bytes)?;
-        let stderr_string = String::from_utf8(stderr_bytes)?;
+        let stderr_string = String::from_utf8(stderr_capture.bytes)?;
 
         if !status.success() {
             return Err(CodexError::NonZeroExit {
+                command: command_line,
+                exit_code: status.code(),
                 status,
                 stderr: stderr_string,
+                truncated: stderr_capture.truncated,
             });
         }
 
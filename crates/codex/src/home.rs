@@ -222,6 +222,63 @@ impl CodexHomeLayout {
 
         Ok(outcome)
     }
+
+    /// Reports `auth.json` / `.credentials.json` files whose mode grants any group or world
+    /// access; these hold tokens and should be `0600`.
+    ///
+    /// Missing or unreadable files are skipped. Always empty on non-Unix targets.
+    pub fn audit_permissions(&self) -> Vec<PermissionFinding> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            [self.auth_path(), self.credentials_path()]
+                .into_iter()
+                .filter_map(|path| {
+                    let mode = std_fs::metadata(&path).ok()?.permissions().mode() & 0o777;
+                    (mode & 0o077 != 0).then_some(PermissionFinding {
+                        group_readable: mode & 0o040 != 0,
+                        world_readable: mode & 0o004 != 0,
+                        path,
+                        mode,
+                    })
+                })
+                .collect()
+        }
+        #[cfg(not(unix))]
+        {
+            Vec::new()
+        }
+    }
+
+    /// Restricts every file reported by [`Self::audit_permissions`] to `0600` and returns the
+    /// paths that changed. No-op on non-Unix targets.
+    pub fn tighten_permissions(&self) -> Result<Vec<PathBuf>, std::io::Error> {
+        let mut tightened = Vec::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            for finding in self.audit_permissions() {
+                std_fs::set_permissions(&finding.path, std_fs::Permissions::from_mode(0o600))?;
+                tightened.push(finding.path);
+            }
+        }
+        Ok(tightened)
+    }
+}
+
+/// Secret file under `CODEX_HOME` with permissions broader than owner-only.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PermissionFinding {
+    /// Path to `auth.json` or `.credentials.json`.
+    pub path: PathBuf,
+    /// Permission bits (`st_mode & 0o777`).
+    pub mode: u32,
+    /// True when the group read bit is set.
+    pub group_readable: bool,
+    /// True when the world read bit is set.
+    pub world_readable: bool,
 }
 
 /// Options controlling how auth files are seeded from a trusted home.
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     future::Future,
     io::{self as stdio, BufRead, Write},
     path::{Path, PathBuf},
@@ -6,6 +7,7 @@ use std::{
     task::{Context, Poll},
 };
 
+use flate2::{write::GzEncoder, Compression};
 use futures_core::Stream;
 use tokio::{
     fs,
@@ -14,8 +16,14 @@ use tokio::{
     sync::mpsc,
     task, time,
 };
+use tracing::warn;
 
-use crate::{CodexError, ExecStreamError, ThreadEvent};
+use crate::{
+    metrics::MetricsRecorder,
+    process::{console_lock, CaptureOptions},
+    CodexError, EventFilter, ExecStreamError, ItemKind, ItemPayload, LineParseError, MirrorFlush,
+    ParseErrorMode, ThreadEvent, ThreadSummary,
+};
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct StreamContext {
@@ -93,6 +101,7 @@ fn clone_exec_stream_error(err: &ExecStreamError) -> ExecStreamError {
             idle_for: *idle_for,
         },
         ExecStreamError::ChannelClosed => ExecStreamError::ChannelClosed,
+        ExecStreamError::Cancelled => ExecStreamError::Cancelled,
     }
 }
 
@@ -106,10 +115,24 @@ fn clone_codex_error(err: &CodexError) -> CodexError {
             source: clone_io_error(source),
         },
         CodexError::Timeout { timeout } => CodexError::Timeout { timeout: *timeout },
-        CodexError::NonZeroExit { status, stderr } => CodexError::NonZeroExit {
+        CodexError::NonZeroExit {
+            command,
+            exit_code,
+            status,
+            stderr,
+            truncated,
+        } => CodexError::NonZeroExit {
+            command: command.clone(),
+            exit_code: *exit_code,
             status: *status,
             stderr: stderr.clone(),
+            truncated: *truncated,
+        },
+        CodexError::OutputTruncated { command, limit } => CodexError::OutputTruncated {
+            command: command.clone(),
+            limit: *limit,
         },
+        CodexError::Stream(source) => CodexError::Stream(Box::new(clone_exec_stream_error(source))),
         CodexError::InvalidUtf8(source) => {
             let io_err = std::io::Error::new(std::io::ErrorKind::InvalidData, source.to_string());
             CodexError::CaptureIo(io_err)
@@ -153,6 +176,25 @@ fn clone_codex_error(err: &CodexError) -> CodexError {
         CodexError::EmptyMcpCommand => CodexError::EmptyMcpCommand,
         CodexError::EmptyMcpUrl => CodexError::EmptyMcpUrl,
         CodexError::EmptySocketPath => CodexError::EmptySocketPath,
+        CodexError::JsonModeUnsupported { stdout } => CodexError::JsonModeUnsupported {
+            stdout: stdout.clone(),
+        },
+        CodexError::PatchNotFound { path } => CodexError::PatchNotFound { path: path.clone() },
+        CodexError::AttachmentNotFound { path } => {
+            CodexError::AttachmentNotFound { path: path.clone() }
+        }
+        CodexError::SessionNotFound {
+            session_id,
+            codex_home,
+        } => CodexError::SessionNotFound {
+            session_id: session_id.clone(),
+            codex_home: codex_home.clone(),
+        },
+        CodexError::AttachmentTooLarge { path, size, limit } => CodexError::AttachmentTooLarge {
+            path: path.clone(),
+            size: *size,
+            limit: *limit,
+        },
         CodexError::TempDir(source) => CodexError::TempDir(clone_io_error(source)),
         CodexError::WorkingDirectory { source } => CodexError::WorkingDirectory {
             source: clone_io_error(source),
@@ -161,6 +203,18 @@ fn clone_codex_error(err: &CodexError) -> CodexError {
             path: path.clone(),
             source: clone_io_error(source),
         },
+        CodexError::PrepareArtifactDir { path, source } => CodexError::PrepareArtifactDir {
+            path: path.clone(),
+            source: clone_io_error(source),
+        },
+        CodexError::GitInit { path, source } => CodexError::GitInit {
+            path: path.clone(),
+            source: clone_io_error(source),
+        },
+        CodexError::BackupFile { path, source } => CodexError::BackupFile {
+            path: path.clone(),
+            source: clone_io_error(source),
+        },
         CodexError::PrepareCodexHome { path, source } => CodexError::PrepareCodexHome {
             path: path.clone(),
             source: clone_io_error(source),
@@ -174,6 +228,15 @@ fn clone_codex_error(err: &CodexError) -> CodexError {
             let io_err = std::io::Error::other(source.to_string());
             CodexError::CaptureIo(io_err)
         }
+        CodexError::UnsupportedCapability { feature } => {
+            CodexError::UnsupportedCapability { feature }
+        }
+        CodexError::UnsupportedPlatform { feature } => CodexError::UnsupportedPlatform { feature },
+        CodexError::StructuredOutput { expected, source } => CodexError::StructuredOutput {
+            expected,
+            source: <serde_json::Error as serde::de::Error>::custom(source.to_string()),
+        },
+        CodexError::Template(err) => CodexError::Template(err.clone()),
     }
 }
 
@@ -277,10 +340,11 @@ pub fn thread_event_jsonl_file(
 
 pub(crate) async fn prepare_json_log(
     path: Option<PathBuf>,
+    compression: Option<JsonLogCompression>,
 ) -> Result<Option<JsonLogSink>, ExecStreamError> {
     match path {
         Some(path) => {
-            let sink = JsonLogSink::new(path)
+            let sink = JsonLogSink::new(path, compression)
                 .await
                 .map_err(|err| ExecStreamError::from(CodexError::CaptureIo(err)))?;
             Ok(Some(sink))
@@ -289,13 +353,49 @@ pub(crate) async fn prepare_json_log(
     }
 }
 
+/// Compression applied to JSON event logs written via `json_event_log`.
+///
+/// Gzip logs are sync-flushed after every line, so a crash leaves a readable prefix of the
+/// stream; only the gzip trailer (CRC and length) is missing, which makes tools such as `zcat`
+/// print the recovered lines followed by an "unexpected end of file" warning. Per-line flushing
+/// costs some compression ratio compared to compressing the whole log at once. Appending to an
+/// existing `.gz` log adds a new gzip member, which standard decoders read transparently.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum JsonLogCompression {
+    /// Plain JSONL (the default unless the log path ends in `.gz`).
+    #[default]
+    None,
+    /// Gzip-compressed JSONL, finalized when the stream completes.
+    Gzip,
+}
+
+impl JsonLogCompression {
+    /// Picks [`JsonLogCompression::Gzip`] for paths with a `.gz` extension.
+    pub fn for_path(path: &Path) -> Self {
+        let is_gzip = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"));
+        if is_gzip {
+            Self::Gzip
+        } else {
+            Self::None
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct JsonLogSink {
     writer: BufWriter<fs::File>,
+    encoder: Option<GzEncoder<Vec<u8>>>,
 }
 
 impl JsonLogSink {
-    pub(crate) async fn new(path: PathBuf) -> Result<Self, std::io::Error> {
+    /// Opens the log; `None` infers compression from the file extension.
+    pub(crate) async fn new(
+        path: PathBuf,
+        compression: Option<JsonLogCompression>,
+    ) -> Result<Self, std::io::Error> {
+        let compression = compression.unwrap_or_else(|| JsonLogCompression::for_path(&path));
         if let Some(parent) = path.parent() {
             if !parent.as_os_str().is_empty() {
                 fs::create_dir_all(parent).await?;
@@ -308,14 +408,39 @@ impl JsonLogSink {
             .open(&path)
             .await?;
 
+        let encoder = (compression == JsonLogCompression::Gzip)
+            .then(|| GzEncoder::new(Vec::new(), Compression::default()));
         Ok(Self {
             writer: BufWriter::new(file),
+            encoder,
         })
     }
 
-    async fn write_line(&mut self, line: &str) -> Result<(), std::io::Error> {
-        self.writer.write_all(line.as_bytes()).await?;
-        self.writer.write_all(b"\n").await?;
+    pub(crate) async fn write_line(&mut self, line: &str) -> Result<(), std::io::Error> {
+        match self.encoder.as_mut() {
+            Some(encoder) => {
+                encoder.write_all(line.as_bytes())?;
+                encoder.write_all(b"\n")?;
+                // Sync-flush so every completed line is decodable even if we never finish.
+                encoder.flush()?;
+                let compressed = encoder.get_mut();
+                self.writer.write_all(compressed).await?;
+                compressed.clear();
+            }
+            None => {
+                self.writer.write_all(line.as_bytes()).await?;
+                self.writer.write_all(b"\n").await?;
+            }
+        }
+        self.writer.flush().await
+    }
+
+    /// Writes the gzip trailer (when compressing) and flushes the file.
+    pub(crate) async fn finish(mut self) -> Result<(), std::io::Error> {
+        if let Some(encoder) = self.encoder.take() {
+            let trailer = encoder.finish()?;
+            self.writer.write_all(&trailer).await?;
+        }
         self.writer.flush().await
     }
 }
@@ -324,6 +449,12 @@ pub(crate) struct EventChannelStream {
     rx: mpsc::Receiver<Result<ThreadEvent, ExecStreamError>>,
     idle_timeout: Option<std::time::Duration>,
     idle_timer: Option<Pin<Box<time::Sleep>>>,
+    summary: Option<ThreadSummary>,
+    heartbeat: Option<std::time::Duration>,
+    heartbeat_timer: Option<Pin<Box<time::Sleep>>>,
+    last_event_at: time::Instant,
+    closed: bool,
+    metrics: Option<MetricsRecorder>,
 }
 
 impl EventChannelStream {
@@ -335,9 +466,34 @@ impl EventChannelStream {
             rx,
             idle_timeout,
             idle_timer: None,
+            summary: None,
+            heartbeat: None,
+            heartbeat_timer: None,
+            last_event_at: time::Instant::now(),
+            closed: false,
+            metrics: None,
         }
     }
 
+    /// Emits a synthetic [`ThreadEvent::ThreadCompleted`] once the channel closes.
+    pub(crate) fn with_summary(mut self, enabled: bool) -> Self {
+        self.summary = enabled.then(ThreadSummary::default);
+        self
+    }
+
+    /// Yields [`ThreadEvent::Heartbeat`] after each `interval` without a channel event; zero or
+    /// `None` disables heartbeats.
+    pub(crate) fn with_heartbeat(mut self, interval: Option<std::time::Duration>) -> Self {
+        self.heartbeat = interval.filter(|interval| !interval.is_zero());
+        self
+    }
+
+    /// Reports token usage from each `turn.completed` event to `metrics`.
+    pub(crate) fn with_metrics(mut self, metrics: MetricsRecorder) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     fn reset_timer(&mut self) {
         self.idle_timer = self
             .idle_timeout
@@ -350,6 +506,9 @@ impl Stream for EventChannelStream {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.get_mut();
+        if this.closed {
+            return Poll::Ready(None);
+        }
 
         if let Some(timer) = this.idle_timer.as_mut() {
             if let Poll::Ready(()) = timer.as_mut().poll(cx) {
@@ -364,9 +523,30 @@ impl Stream for EventChannelStream {
                 if this.idle_timeout.is_some() {
                     this.reset_timer();
                 }
+                this.last_event_at = time::Instant::now();
+                this.heartbeat_timer = None;
+                if let (Some(summary), Ok(event)) = (this.summary.as_mut(), &item) {
+                    summary.observe(event);
+                }
+                if let (Some(metrics), Ok(ThreadEvent::TurnCompleted(completed))) =
+                    (this.metrics.as_ref(), &item)
+                {
+                    if let Some(usage) = completed.usage() {
+                        metrics.record_tokens(usage);
+                    }
+                }
                 Poll::Ready(Some(item))
             }
-            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Ready(None) => {
+                this.idle_timer = None;
+                this.heartbeat_timer = None;
+                this.closed = true;
+                Poll::Ready(
+                    this.summary
+                        .take()
+                        .map(|summary| Ok(ThreadEvent::ThreadCompleted(summary))),
+                )
+            }
             Poll::Pending => {
                 if this.idle_timer.is_none() {
                     if let Some(duration) = this.idle_timeout {
@@ -379,25 +559,64 @@ impl Stream for EventChannelStream {
                         this.idle_timer = Some(sleep);
                     }
                 }
+                if let Some(interval) = this.heartbeat {
+                    let timer = this
+                        .heartbeat_timer
+                        .get_or_insert_with(|| Box::pin(time::sleep(interval)));
+                    if timer.as_mut().poll(cx).is_ready() {
+                        this.heartbeat_timer = None;
+                        return Poll::Ready(Some(Ok(ThreadEvent::Heartbeat {
+                            elapsed: this.last_event_at.elapsed(),
+                        })));
+                    }
+                }
                 Poll::Pending
             }
         }
     }
 }
 
+/// Output of [`forward_json_events`] once stdout closes.
+#[derive(Debug, Default)]
+pub(crate) struct ForwardedEvents {
+    /// Text of the last completed `agent_message` item.
+    pub(crate) agent_message: Option<String>,
+    /// Lines dropped under [`ParseErrorMode::Collect`].
+    pub(crate) parse_errors: Vec<LineParseError>,
+    /// Events withheld by the request's [`EventFilter`], per item type.
+    pub(crate) filtered: BTreeMap<ItemKind, u64>,
+}
+
 pub(crate) async fn forward_json_events<R>(
     reader: R,
     sender: mpsc::Sender<Result<ThreadEvent, ExecStreamError>>,
-    mirror_stdout: bool,
-    mut log: Option<JsonLogSink>,
-) -> Result<(), ExecStreamError>
+    mirror: Option<MirrorFlush>,
+    mut logs: Vec<JsonLogSink>,
+    options: CaptureOptions,
+    on_parse_error: ParseErrorMode,
+    event_filter: Option<EventFilter>,
+) -> Result<ForwardedEvents, ExecStreamError>
 where
     R: AsyncRead + Unpin,
 {
-    let mut lines = BufReader::new(reader).lines();
+    let mut lines = BufReader::with_capacity(options.chunk_size, reader).lines();
     let mut context = StreamContext::default();
+    let mut forwarded = ForwardedEvents::default();
+    let mut mirror = mirror.map(MirrorBatch::new);
     loop {
-        let line = match lines.next_line().await {
+        let next = match mirror.as_ref().and_then(MirrorBatch::deadline) {
+            Some(deadline) => tokio::select! {
+                next = lines.next_line() => next,
+                _ = time::sleep_until(deadline) => {
+                    if let Some(text) = mirror.as_mut().and_then(MirrorBatch::take) {
+                        write_mirrored(&text, &options)?;
+                    }
+                    continue;
+                }
+            },
+            None => lines.next_line().await,
+        };
+        let line = match next {
             Ok(Some(line)) => line,
             Ok(None) => break,
             Err(err) => {
@@ -409,30 +628,125 @@ where
             continue;
         }
 
-        if let Some(sink) = log.as_mut() {
+        for sink in logs.iter_mut() {
             sink.write_line(&line)
                 .await
                 .map_err(|err| ExecStreamError::from(CodexError::CaptureIo(err)))?;
         }
 
-        if mirror_stdout {
-            if let Err(err) = task::block_in_place(|| {
-                let mut out = stdio::stdout();
-                out.write_all(line.as_bytes())?;
-                out.write_all(b"\n")?;
-                out.flush()
-            }) {
-                return Err(CodexError::CaptureIo(err).into());
-            }
+        if let Some(text) = mirror.as_mut().and_then(|mirror| mirror.push(&line)) {
+            write_mirrored(&text, &options)?;
         }
 
         let event = normalize_thread_event(&line, &mut context);
+        if let Ok(ThreadEvent::ItemCompleted(envelope)) = &event {
+            if let ItemPayload::AgentMessage(content) = &envelope.item.payload {
+                forwarded.agent_message = Some(content.text.clone());
+            }
+        }
+        if let (Ok(event), Some(filter)) = (&event, &event_filter) {
+            if let Some(kind) = filter.rejects(event) {
+                *forwarded.filtered.entry(kind).or_default() += 1;
+                continue;
+            }
+        }
+        if let Err(err) = &event {
+            match on_parse_error {
+                ParseErrorMode::Fail => {}
+                ParseErrorMode::Skip => {
+                    warn!(error = %err, "skipping malformed codex JSONL line");
+                    continue;
+                }
+                ParseErrorMode::Collect => {
+                    forwarded.parse_errors.push(line_parse_error(line, err));
+                    continue;
+                }
+            }
+        }
         if sender.send(event).await.is_err() {
             break;
         }
     }
 
-    Ok(())
+    if let Some(text) = mirror.as_mut().and_then(MirrorBatch::take) {
+        write_mirrored(&text, &options)?;
+    }
+
+    for sink in logs {
+        sink.finish()
+            .await
+            .map_err(|err| ExecStreamError::from(CodexError::CaptureIo(err)))?;
+    }
+
+    Ok(forwarded)
+}
+
+/// Buffers mirrored stdout lines according to [`MirrorFlush`].
+#[derive(Debug)]
+pub(crate) struct MirrorBatch {
+    mode: MirrorFlush,
+    pending: String,
+    deadline: Option<time::Instant>,
+}
+
+impl MirrorBatch {
+    pub(crate) fn new(mode: MirrorFlush) -> Self {
+        Self {
+            mode,
+            pending: String::new(),
+            deadline: None,
+        }
+    }
+
+    /// Queues `line` and returns the text that is due for writing now.
+    pub(crate) fn push(&mut self, line: &str) -> Option<String> {
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        match self.mode {
+            MirrorFlush::PerLine => self.take(),
+            MirrorFlush::Batched(interval) => {
+                let now = time::Instant::now();
+                let deadline = *self.deadline.get_or_insert(now + interval);
+                if now >= deadline {
+                    self.take()
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// When buffered text must be written even if no further line arrives.
+    pub(crate) fn deadline(&self) -> Option<time::Instant> {
+        self.deadline
+    }
+
+    /// Drains everything buffered so far.
+    pub(crate) fn take(&mut self) -> Option<String> {
+        self.deadline = None;
+        (!self.pending.is_empty()).then(|| std::mem::take(&mut self.pending))
+    }
+}
+
+fn write_mirrored(text: &str, options: &CaptureOptions) -> Result<(), ExecStreamError> {
+    task::block_in_place(|| {
+        let _lock = options
+            .serialize_console
+            .then(|| console_lock().lock().unwrap_or_else(|err| err.into_inner()));
+        let mut out = stdio::stdout();
+        out.write_all(text.as_bytes())?;
+        out.flush()
+    })
+    .map_err(|err| CodexError::CaptureIo(err).into())
+}
+
+pub(crate) fn line_parse_error(line: String, err: &ExecStreamError) -> LineParseError {
+    let message = match err {
+        ExecStreamError::Parse { source, .. } => source.to_string(),
+        ExecStreamError::Normalize { message, .. } => message.clone(),
+        other => other.to_string(),
+    };
+    LineParseError { line, message }
 }
 
 pub(crate) fn normalize_thread_event(
@@ -34,20 +34,29 @@
 //!
 //! Surfaces:
 //! - [`CodexClient::send_prompt`] for a single prompt/response with optional `--json` output.
+//! - [`CodexClient::send_prompt_with_progress`] to stream agent-message text to a callback (typewriter UX) via `codex exec --json` and return the final message.
+//! - [`CodexClient::exec_collect`] to run `codex exec --json` once and get the raw JSONL, parsed events, final agent message, exit status, and token usage in an [`ExecResult`].
 //! - [`CodexClient::stream_exec`] for typed, real-time JSONL events from `codex exec --json`, returning an [`ExecStream`] with an event stream plus a completion future.
 //! - [`CodexClient::apply`] / [`CodexClient::diff`] to run `codex apply <TASK_ID>` and `codex cloud diff <TASK_ID>`, echo stdout/stderr according to the builder (`mirror_stdout` / `quiet`), and return captured output + exit status.
 //! - [`CodexClient::generate_app_server_bindings`] to refresh app-server protocol bindings via `codex app-server generate-ts` (optional `--prettier`) or `generate-json-schema`, returning captured stdout/stderr plus the exit status.
+//! - [`CodexClient::app_server_codegen`] to run the same codegen after confirming the target subcommand appears in the capability probe's `codex app-server --help` listing, returning the files the run created or modified alongside captured output.
 //! - [`CodexClient::run_sandbox`] to wrap `codex sandbox <platform>` (macOS/Linux/Windows), pass `--full-auto`/`--log-denials`/`--config`/`--enable`/`--disable`, and return the inner command status + output. macOS is the only platform that emits denial logs; Linux depends on the bundled `codex-linux-sandbox`; Windows sandboxing is experimental and relies on the upstream helper (no capability gating—non-zero exits bubble through).
+//! - [`CodexClient::run_sandbox_with_denials`] to execute a command under the sandbox with denial logging and report a [`SandboxDecision`] plus any [`SandboxDenial`]s; it runs the command, so use [`CodexClient::check_execpolicy`] for a side-effect-free policy check.
 //! - [`CodexClient::check_execpolicy`] to evaluate shell commands against Starlark execpolicy files with repeatable `--policy` flags, optional pretty JSON, and parsed decision output (allow/prompt/forbidden or noMatch).
-//! - [`CodexClient::list_features`] to wrap `codex features list` with optional `--json` parsing, shared config/profile overrides, and parsed feature entries (name/stage/enabled).
+//! - [`CodexClient::list_features`] to wrap `codex features list` with optional `--json` parsing (accepts a [`FeaturesListFormat`] directly and retries as text when `--json` is rejected), shared config/profile overrides, and parsed feature entries (name/stage/enabled).
+//! - [`CodexClient::review`] to run `codex review` (or `codex exec review --json` when probes confirm `exec --json` support) and return a [`ReviewOutcome`] with findings (title/severity/file location) parsed from JSON or the text comment block.
 //! - [`CodexClient::start_responses_api_proxy`] to launch the `codex responses-api-proxy` helper with an API key piped via stdin plus optional port/server-info/upstream/shutdown flags.
 //! - [`CodexClient::stdio_to_uds`] to spawn `codex stdio-to-uds <SOCKET_PATH>` with piped stdio so callers can bridge Unix domain sockets manually.
+//! - [`CodexClient::bridge_stdio_to_uds`] to launch the same bridge as a [`UdsBridgeHandle`] that exposes the resolved socket path and tears the child down on drop (Unix only).
 //!
 //! ## Streaming, events, and artifacts
 //! - `.json(true)` requests JSONL streaming. Expect `thread.started`/`thread.resumed`, `turn.started`/`turn.completed`/`turn.failed`, and `item.created`/`item.updated` with `item.type` such as `agent_message`, `reasoning`, `command_execution`, `file_change`, `mcp_tool_call`, `web_search`, or `todo_list` plus optional `status`/`content`/`input`. Errors surface as `{"type":"error","message":...}`.
 //! - Sample payloads ship with the streaming examples (`crates/codex/examples/fixtures/*`); most examples support `--sample` for offline inspection.
 //! - Disable `mirror_stdout` when parsing JSON so stdout stays under caller control; `quiet` controls stderr mirroring. `json_event_log` tees raw JSONL lines to disk before parsing; `idle_timeout`, `output_last_message`, and `output_schema` cover artifact handling.
 //! - `crates/codex/examples/stream_events.rs`, `stream_last_message.rs`, `stream_with_log.rs`, and `json_stream.rs` cover typed consumption, artifact handling, log teeing, and minimal streaming.
+//! - [`IdRemapper`] rewrites upstream `thread_id`/`turn_id` values to stable sequential ids (`thread-1`, `turn-1`, ...) across a stream for deterministic snapshot tests or host-side id mapping.
+//! - [`ExtraAccess::extra_get_path`] reads unmodeled event fields kept in `extra` maps by JSON pointer (`/metadata/cost`).
+//! - `CodexClientBuilder::metrics` registers a [`Metrics`] sink that receives exec latency/exit status, `turn.completed` token usage, and [`ErrorCategory`] counts for buffered and streamed runs; [`InMemoryMetrics`] collects samples for tests.
 //!
 //! ## Resume + apply/diff
 //! - `codex exec --json resume --last [-]` streams the same `thread/turn/item` events as `codex exec --json` but starts from an existing session (`thread.resumed`).
@@ -64,10 +73,12 @@
 //! ## Capability/versioning surfaces (Workstream F)
 //! - `probe_capabilities` captures `--version`, `features list`, and `--help` hints into a `CodexCapabilities` snapshot with `collected_at` timestamps and `BinaryFingerprint` metadata keyed by canonical binary path.
 //! - Guard helpers (`guard_output_schema`, `guard_add_dir`, `guard_mcp_login`, `guard_features_list`) keep optional flags disabled when support is unknown and return operator-facing notes for unsupported features.
-//! - Cache controls: `CapabilityCachePolicy::{PreferCache, Refresh, Bypass}` plus builder helpers steer cache reuse. Use `Refresh` for TTL/backoff windows or hot-swaps that reuse the same binary path; use `Bypass` when metadata is missing (FUSE/overlay filesystems) or when you need an isolated probe.
+//! - Cache controls: `CapabilityCachePolicy::{PreferCache, Refresh, Bypass, OfflineSnapshotOnly}` plus builder helpers steer cache reuse. Use `Refresh` for TTL/backoff windows or hot-swaps that reuse the same binary path; use `Bypass` when metadata is missing (FUSE/overlay filesystems) or when you need an isolated probe; use `OfflineSnapshotOnly` on air-gapped hosts to resolve capabilities from the override snapshot without spawning the binary.
 //! - TTL/backoff helper: `capability_cache_ttl_decision` inspects `collected_at` to suggest when to reuse, refresh, or bypass cached snapshots and stretches the recommended policy when metadata is missing.
 //! - Overrides + persistence: `capability_snapshot`, `capability_overrides`, `write_capabilities_snapshot`, `read_capabilities_snapshot`, and `capability_snapshot_matches_binary` let hosts reuse snapshots across processes and fall back to probes when fingerprints diverge.
+//! - Upgrade reporting: `CodexCapabilities::diff` compares two snapshots and returns the version delta (as a `CodexUpdateAdvisory`) plus the `CapabilityFeature`s gained or lost.
 
+mod ansi;
 mod apply_diff;
 mod auth;
 mod builder;
@@ -83,55 +94,76 @@ mod execpolicy;
 mod home;
 pub mod jsonl;
 pub mod mcp;
+mod metrics;
 mod process;
 pub mod rollout_jsonl;
+mod template;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod transcript;
 pub mod wrapper_coverage_manifest;
 
 pub use crate::error::CodexError;
-pub use apply_diff::{ApplyDiffArtifacts, CloudApplyRequest, CloudDiffRequest};
-pub use auth::{AuthSessionHelper, CodexAuthMethod, CodexAuthStatus, CodexLogoutStatus};
+pub use apply_diff::{
+    ApplyConflict, ApplyDiffArtifacts, ApplyReport, CloudApplyRequest, CloudDiffRequest,
+    DiffSummary,
+};
+pub use auth::{
+    AuthSessionHelper, CodexAuthMethod, CodexAuthStatus, CodexLogoutStatus, LoginEvent,
+    LoginSession,
+};
 pub use builder::{
-    ApprovalPolicy, CliOverrides, CliOverridesPatch, CodexClientBuilder, ColorMode, ConfigOverride,
-    FeatureToggles, FlagState, LocalProvider, ModelVerbosity, ReasoningEffort, ReasoningOverrides,
-    ReasoningSummary, ReasoningSummaryFormat, SafetyOverride, SandboxMode,
+    AddDir, ApprovalPolicy, BuilderEnvError, BuilderError, CliOverrides, CliOverridesPatch,
+    CodexClientBuilder, ColorMode, ConfigOverride, DirAccess, FeatureToggles, FlagState,
+    LocalProvider, ModelVerbosity, ReasoningEffort, ReasoningOverrides, ReasoningSummary,
+    ReasoningSummaryFormat, SafetyOverride, SandboxMode,
 };
 pub use bundled_binary::{
     default_bundled_platform_label, resolve_bundled_binary, BundledBinary, BundledBinaryError,
     BundledBinarySpec,
 };
 pub use cli::{
-    AppServerCodegenOutput, AppServerCodegenRequest, AppServerCodegenTarget, CloudExecRequest,
-    CloudListOutput, CloudListRequest, CloudOverviewRequest, CloudStatusRequest, CodexFeature,
-    CodexFeatureStage, DebugAppServerHelpRequest, DebugAppServerRequest,
-    DebugAppServerSendMessageV2Request, DebugCommandRequest, DebugHelpRequest, ExecRequest,
-    ExecReviewCommandRequest, FeaturesCommandRequest, FeaturesDisableRequest,
-    FeaturesEnableRequest, FeaturesListFormat, FeaturesListOutput, FeaturesListRequest,
-    ForkSessionRequest, HelpCommandRequest, HelpScope, McpAddRequest, McpAddTransport,
-    McpGetRequest, McpListOutput, McpListRequest, McpLogoutRequest, McpOauthLoginRequest,
-    McpOverviewRequest, McpRemoveRequest, ResponsesApiProxyHandle, ResponsesApiProxyInfo,
-    ResponsesApiProxyRequest, ResumeSessionRequest, ReviewCommandRequest, SandboxCommandRequest,
-    SandboxPlatform, SandboxRun, StdioToUdsRequest,
+    AppServerCodegenOutput, AppServerCodegenRequest, AppServerCodegenTarget, Attachment,
+    CloudExecOutput, CloudExecRequest, CloudListOutput, CloudListRequest, CloudOverviewRequest,
+    CloudStatusRequest, CodexFeature, CodexFeatureStage, DebugAppServerHelpRequest,
+    DebugAppServerRequest, DebugAppServerSendMessageV2Request, DebugCommandRequest,
+    DebugHelpRequest, ExecOptions, ExecRequest, ExecReviewCommandRequest, FeaturesCommandRequest,
+    FeaturesDisableRequest, FeaturesEnableRequest, FeaturesListFormat, FeaturesListOutput,
+    FeaturesListRequest, ForkSessionRequest, HelpCommandRequest, HelpScope, McpAddRequest,
+    McpAddTransport, McpGetRequest, McpListOutput, McpListRequest, McpLogoutRequest,
+    McpOauthLoginRequest, McpOverviewRequest, McpRemoveRequest, ResponsesApiProxyHandle,
+    ResponsesApiProxyInfo, ResponsesApiProxyRequest, ResumeSessionRequest, ReviewCommandRequest,
+    ReviewFinding, ReviewLocation, ReviewOutcome, ReviewOutputFormat, ReviewSeverity,
+    SandboxCommandRequest, SandboxDecision, SandboxDenial, SandboxPlatform, SandboxRun,
+    SessionHandle, StdioToUdsRequest, UdsBridgeHandle,
 };
 pub use events::{
-    CommandExecutionDelta, CommandExecutionState, EventError, FileChangeDelta, FileChangeKind,
-    FileChangeState, ItemDelta, ItemDeltaPayload, ItemEnvelope, ItemFailure, ItemPayload,
-    ItemSnapshot, ItemStatus, McpToolCallDelta, McpToolCallState, TextContent, TextDelta,
-    ThreadEvent, ThreadStarted, TodoItem, TodoListDelta, TodoListState, ToolCallStatus,
-    TurnCompleted, TurnFailed, TurnStarted, WebSearchDelta, WebSearchState, WebSearchStatus,
+    CommandExecutionDelta, CommandExecutionState, EventError, EventErrorKind, EventFilter,
+    ExtraAccess, FileChangeDelta, FileChangeKind, FileChangeState, IdRemapper, ItemDelta,
+    ItemDeltaPayload, ItemEnvelope, ItemFailure, ItemKind, ItemPayload, ItemSnapshot, ItemStatus,
+    LineParseError, McpToolCallDelta, McpToolCallState, RateLimit, TextContent, TextDelta,
+    ThreadEvent, ThreadStarted, ThreadSummary, TodoItem, TodoListDelta, TodoListState, TokenUsage,
+    ToolCallStatus, TurnCompleted, TurnFailed, TurnStarted, WebSearchDelta, WebSearchResult,
+    WebSearchState, WebSearchStatus, DISPLAY_TEXT_LIMIT,
 };
 pub use exec::{
-    DynExecCompletion, DynThreadEventStream, ExecCompletion, ExecStream, ExecStreamError,
-    ExecStreamRequest, ResumeRequest, ResumeSelector,
+    DynExecCompletion, DynThreadEventStream, ExecAbortHandle, ExecCompletion, ExecResult,
+    ExecStream, ExecStreamError, ExecStreamRequest, MirrorFlush, ParseErrorMode, PromptEstimate,
+    ResumeRequest, ResumeSelector, DEFAULT_RESUME_PROMPT, MAX_AUTO_RESUMES,
 };
 pub use execpolicy::{
     ExecPolicyCheckRequest, ExecPolicyCheckResult, ExecPolicyDecision, ExecPolicyEvaluation,
     ExecPolicyMatch, ExecPolicyNoMatch, ExecPolicyRuleMatch,
 };
-pub use home::{AuthSeedError, AuthSeedOptions, AuthSeedOutcome, CodexHomeLayout};
+pub use home::{
+    AuthSeedError, AuthSeedOptions, AuthSeedOutcome, CodexHomeLayout, PermissionFinding,
+};
 pub use jsonl::{
-    thread_event_jsonl_file, thread_event_jsonl_reader, JsonlThreadEventParser,
+    thread_event_jsonl_file, thread_event_jsonl_reader, JsonLogCompression, JsonlThreadEventParser,
     ThreadEventJsonlFileReader, ThreadEventJsonlReader, ThreadEventJsonlRecord,
 };
+pub use metrics::{ErrorCategory, InMemoryMetrics, Metrics, MetricsSnapshot, NoopMetrics};
+pub use process::CommandOutput;
 pub use rollout_jsonl::{
     find_rollout_file_by_id, find_rollout_files, rollout_jsonl_file, rollout_jsonl_reader,
     RolloutBaseInstructions, RolloutContentPart, RolloutEvent, RolloutEventMsg,
@@ -139,19 +171,22 @@ pub use rollout_jsonl::{
     RolloutJsonlReader, RolloutJsonlRecord, RolloutResponseItem, RolloutResponseItemPayload,
     RolloutSessionMeta, RolloutSessionMetaPayload, RolloutUnknown,
 };
+pub use template::{PromptTemplate, TemplateError};
+pub use transcript::{
+    replay_transcript, TranscriptHeader, TranscriptSink, TRANSCRIPT_SCHEMA_VERSION,
+};
 
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
     time::{Duration, SystemTime},
 };
 
+use futures_util::FutureExt;
 use home::CommandEnvironment;
 use process::command_output_text;
 use tracing::warn;
 
-#[cfg(test)]
-use std::path::Path;
-
 #[cfg(test)]
 use tokio::time;
 
@@ -179,11 +214,12 @@ async fn tee_stream<R>(
     reader: R,
     target: ConsoleTarget,
     mirror_console: bool,
+    options: crate::process::CaptureOptions,
 ) -> Result<Vec<u8>, std::io::Error>
 where
     R: tokio::io::AsyncRead + Unpin,
 {
-    crate::process::tee_stream(reader, target, mirror_console).await
+    crate::process::tee_stream(reader, target, mirror_console, options).await
 }
 
 fn spawn_with_retry(
@@ -230,18 +266,37 @@ pub struct CodexClient {
     command_env: CommandEnvironment,
     model: Option<String>,
     timeout: Duration,
+    graceful_shutdown: Option<Duration>,
+    idle_timeout: Option<Duration>,
     color_mode: ColorMode,
     working_dir: Option<PathBuf>,
-    add_dirs: Vec<PathBuf>,
+    temp_dir_base: Option<PathBuf>,
+    artifact_dir: Option<PathBuf>,
+    keep_artifacts: bool,
+    io_chunk_size: usize,
+    max_output_bytes: Option<usize>,
+    process_slots: Option<Arc<tokio::sync::Semaphore>>,
+    max_attachment_bytes: u64,
+    serialize_console_output: bool,
+    strip_ansi_errors: bool,
+    auto_git_init: bool,
+    prompt_trailing_newline: bool,
+    add_dirs: Vec<AddDir>,
     images: Vec<PathBuf>,
     json_output: bool,
     output_schema: bool,
     quiet: bool,
     mirror_stdout: bool,
     json_event_log: Option<PathBuf>,
+    json_event_log_compression: Option<JsonLogCompression>,
     cli_overrides: CliOverrides,
     capability_overrides: CapabilityOverrides,
     capability_cache_policy: CapabilityCachePolicy,
+    fingerprint_by_hash: bool,
+    loaded_capability_snapshot: Arc<Mutex<Option<CodexCapabilities>>>,
+    login_status_cache: Arc<Mutex<auth::LoginStatusCache>>,
+    guard_skip: capabilities::GuardSkipNotifier,
+    metrics: metrics::MetricsRecorder,
 }
 
 impl CodexClient {
@@ -257,6 +312,40 @@ impl CodexClient {
         self.command_env.codex_home_layout()
     }
 
+    /// Returns MCP request deadlines derived from this client's [`CodexClientBuilder::timeout`],
+    /// ready to plug into [`mcp::StdioServerConfig::request_timeouts`].
+    pub fn mcp_request_timeouts(&self) -> mcp::McpRequestTimeouts {
+        mcp::McpRequestTimeouts::from_client_timeout(self.timeout)
+    }
+
+    /// Returns a copy of this client that uses `model` (blank clears the override).
+    #[must_use]
+    pub fn with_model(&self, model: impl Into<String>) -> CodexClient {
+        let model = model.into();
+        CodexClient {
+            model: (!model.trim().is_empty()).then_some(model),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of this client with a different overall timeout.
+    #[must_use]
+    pub fn with_timeout(&self, timeout: Duration) -> CodexClient {
+        CodexClient {
+            timeout,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of this client that runs Codex in `dir` instead of a temp dir.
+    #[must_use]
+    pub fn with_working_dir(&self, dir: impl Into<PathBuf>) -> CodexClient {
+        CodexClient {
+            working_dir: Some(dir.into()),
+            ..self.clone()
+        }
+    }
+
     /// Probes the configured binary for version/build metadata and supported feature flags.
     ///
     /// Results are cached per canonical binary path and invalidated when file metadata changes.
@@ -271,19 +360,97 @@ impl CodexClient {
             .await
     }
 
+    /// Reads a capability snapshot from disk (see [`read_capabilities_snapshot`]) and uses it
+    /// for subsequent probes in place of [`CapabilityOverrides::snapshot`] until
+    /// [`CodexClient::clear_capability_snapshot`] is called.
+    ///
+    /// The loaded snapshot is shared with clones of this client. On error the previously
+    /// loaded snapshot (if any) stays in place.
+    pub fn load_capability_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+        format: Option<CapabilitySnapshotFormat>,
+    ) -> Result<(), CapabilitySnapshotError> {
+        let snapshot = read_capabilities_snapshot(path, format)?;
+        *self
+            .loaded_capability_snapshot
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some(snapshot);
+        Ok(())
+    }
+
+    /// Drops a snapshot installed via [`CodexClient::load_capability_snapshot`], falling back
+    /// to builder overrides and live probes. Loaded snapshots are never written to the shared
+    /// capability cache, so the next probe does not resurface them.
+    pub fn clear_capability_snapshot(&self) {
+        self.loaded_capability_snapshot
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take();
+    }
+
+    /// Runs only `codex --version` and parses the result.
+    ///
+    /// Lighter than [`CodexClient::probe_capabilities`]: no feature detection runs and the
+    /// capability cache is left untouched. Returns `Ok(None)` when the CLI prints nothing.
+    pub async fn version(&self) -> Result<Option<CodexVersionInfo>, CodexError> {
+        let output = self.run_basic_command(["--version"]).await?;
+        if !output.status.success() {
+            return Err(CodexError::NonZeroExit {
+                command: output.command,
+                exit_code: output.status.code(),
+                status: output.status,
+                stderr: String::from_utf8(output.stderr)?,
+                truncated: false,
+            });
+        }
+        let text = command_output_text(&output);
+        if text.trim().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(version::parse_version_output(&text)))
+    }
+
     /// Probes capabilities with an explicit cache policy.
     pub async fn probe_capabilities_with_policy(
         &self,
         cache_policy: CapabilityCachePolicy,
     ) -> CodexCapabilities {
         let cache_key = capability_cache_key(self.command_env.binary_path());
-        let fingerprint = current_fingerprint(&cache_key);
-        let overrides = &self.capability_overrides;
+        let fingerprint = if self.fingerprint_by_hash {
+            // Hashing reads the whole binary; keep it off the async workers.
+            let key = cache_key.clone();
+            match tokio::task::spawn_blocking(move || current_fingerprint(&key, true)).await {
+                Ok(fingerprint) => fingerprint,
+                Err(_) => current_fingerprint(&cache_key, false),
+            }
+        } else {
+            current_fingerprint(&cache_key, false)
+        };
+        let loaded_snapshot = self
+            .loaded_capability_snapshot
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        let snapshot_loaded = loaded_snapshot.is_some();
+        let loaded_overrides;
+        let overrides = match loaded_snapshot {
+            Some(snapshot) => {
+                loaded_overrides = CapabilityOverrides {
+                    snapshot: Some(snapshot),
+                    ..self.capability_overrides.clone()
+                };
+                &loaded_overrides
+            }
+            None => &self.capability_overrides,
+        };
 
         let cache_reads_enabled = matches!(cache_policy, CapabilityCachePolicy::PreferCache)
             && has_fingerprint_metadata(&fingerprint);
-        let cache_writes_enabled = !matches!(cache_policy, CapabilityCachePolicy::Bypass)
-            && has_fingerprint_metadata(&fingerprint);
+        let cache_writes_enabled = !matches!(
+            cache_policy,
+            CapabilityCachePolicy::Bypass | CapabilityCachePolicy::OfflineSnapshotOnly
+        ) && has_fingerprint_metadata(&fingerprint);
 
         if let Some(snapshot) = overrides.snapshot.clone() {
             let capabilities = finalize_capabilities_with_overrides(
@@ -293,12 +460,31 @@ impl CodexClient {
                 fingerprint.clone(),
                 true,
             );
-            if cache_writes_enabled {
+            // Loaded snapshots stay client-local so `clear_capability_snapshot` fully reverts them.
+            if cache_writes_enabled && !snapshot_loaded {
                 update_capability_cache(capabilities.clone());
             }
             return capabilities;
         }
 
+        if matches!(cache_policy, CapabilityCachePolicy::OfflineSnapshotOnly) {
+            let conservative = CodexCapabilities {
+                cache_key: cache_key.clone(),
+                fingerprint: fingerprint.clone(),
+                version: None,
+                features: CodexFeatureFlags::default(),
+                probe_plan: CapabilityProbePlan::default(),
+                collected_at: SystemTime::now(),
+            };
+            return finalize_capabilities_with_overrides(
+                conservative,
+                overrides,
+                cache_key,
+                fingerprint,
+                true,
+            );
+        }
+
         if cache_reads_enabled {
             if let Some(cached) = cached_capabilities(&cache_key, &fingerprint) {
                 if overrides.is_empty() {
@@ -318,9 +504,13 @@ impl CodexClient {
             }
         }
 
-        let probed = self
-            .probe_capabilities_uncached(&cache_key, fingerprint.clone())
-            .await;
+        let probed = if matches!(cache_policy, CapabilityCachePolicy::Bypass) {
+            self.probe_capabilities_uncached(&cache_key, fingerprint.clone())
+                .await
+        } else {
+            self.probe_capabilities_single_flight(&cache_key, fingerprint.clone())
+                .await
+        };
 
         let capabilities =
             finalize_capabilities_with_overrides(probed, overrides, cache_key, fingerprint, false);
@@ -332,6 +522,43 @@ impl CodexClient {
         capabilities
     }
 
+    /// Runs an uncached probe, joining any in-flight probe for the same binary so
+    /// concurrent callers share a single set of `--version`/`features list` invocations.
+    async fn probe_capabilities_single_flight(
+        &self,
+        cache_key: &CapabilityCacheKey,
+        fingerprint: Option<BinaryFingerprint>,
+    ) -> CodexCapabilities {
+        let probe = {
+            let mut in_flight = in_flight_probes().lock().await;
+            match in_flight.get(cache_key) {
+                Some(existing) => existing.clone(),
+                None => {
+                    let client = self.clone();
+                    let key = cache_key.clone();
+                    let probe =
+                        async move { client.probe_capabilities_uncached(&key, fingerprint).await }
+                            .boxed()
+                            .shared();
+                    in_flight.insert(cache_key.clone(), probe.clone());
+                    probe
+                }
+            }
+        };
+
+        let capabilities = probe.clone().await;
+
+        let mut in_flight = in_flight_probes().lock().await;
+        if in_flight
+            .get(cache_key)
+            .is_some_and(|current| current.ptr_eq(&probe))
+        {
+            in_flight.remove(cache_key);
+        }
+
+        capabilities
+    }
+
     async fn probe_capabilities_uncached(
         &self,
         cache_key: &CapabilityCacheKey,
@@ -444,6 +671,44 @@ impl CodexClient {
             }
         }
 
+        plan.steps.push(CapabilityProbeStep::ExecHelp);
+        match self.run_basic_command(["exec", "--help"]).await {
+            Ok(output) if output.status.success() => {
+                let text = command_output_text(&output);
+                let parsed = version::parse_exec_help_output(&text);
+                version::merge_feature_flags(&mut features, parsed);
+            }
+            Ok(output) => warn!(
+                status = ?output.status,
+                binary = ?cache_key.binary_path,
+                "codex exec --help exited non-zero"
+            ),
+            Err(error) => warn!(
+                ?error,
+                binary = ?cache_key.binary_path,
+                "codex exec --help probe failed"
+            ),
+        }
+
+        plan.steps.push(CapabilityProbeStep::AppServerHelp);
+        match self.run_basic_command(["app-server", "--help"]).await {
+            Ok(output) if output.status.success() => {
+                let text = command_output_text(&output);
+                features.app_server_subcommands =
+                    Some(version::parse_app_server_help_output(&text));
+            }
+            Ok(output) => warn!(
+                status = ?output.status,
+                binary = ?cache_key.binary_path,
+                "codex app-server --help exited non-zero"
+            ),
+            Err(error) => warn!(
+                ?error,
+                binary = ?cache_key.binary_path,
+                "codex app-server --help probe failed"
+            ),
+        }
+
         CodexCapabilities {
             cache_key: cache_key.clone(),
             fingerprint,
@@ -7,6 +7,9 @@
 //!   runtimes, and expose read-only APIs (including pooled app runtimes) without mutating stored
 //!   config or thread metadata.
 //! - Requests may be cancelled via the JSON-RPC `$ /cancelRequest` flow.
+//! - `initialize`, `codex/codex`, and `codex/codex-reply` honor per-method deadlines from
+//!   [`McpRequestTimeouts`]; a stalled request fails with [`McpError::RequestTimeout`], is
+//!   cancelled on the server, and its event stream closes.
 //!
 //! The MCP server exposes two tool entrypoints:
 //! - `codex/codex`: start a new Codex session with a prompt.
@@ -12,7 +12,7 @@ use tokio::sync::Mutex;
 
 use super::{
     runtime::merge_stdio_env, AppRuntimeDefinition, AppRuntimeEntry, ClientInfo, CodexAppServer,
-    McpConfigError, McpConfigManager, McpError, StdioServerConfig,
+    McpConfigError, McpConfigManager, McpError, StdioServerConfig, METHOD_INITIALIZE,
 };
 
 /// Stored app runtime converted into launch-ready config with metadata intact.
@@ -27,6 +27,7 @@ pub struct AppRuntime {
     pub current_dir: Option<PathBuf>,
     pub mirror_stdio: Option<bool>,
     pub startup_timeout_ms: Option<u64>,
+    pub probe_ready: Option<bool>,
     pub binary: Option<PathBuf>,
 }
 
@@ -41,6 +42,7 @@ impl From<AppRuntimeEntry> for AppRuntime {
             current_dir,
             mirror_stdio,
             startup_timeout_ms,
+            probe_ready,
             binary,
             metadata,
         } = definition;
@@ -55,6 +57,7 @@ impl From<AppRuntimeEntry> for AppRuntime {
             current_dir,
             mirror_stdio,
             startup_timeout_ms,
+            probe_ready,
             binary,
         }
     }
@@ -86,6 +89,7 @@ impl AppRuntime {
                 .startup_timeout_ms
                 .map(Duration::from_millis)
                 .unwrap_or(defaults.startup_timeout),
+            request_timeouts: defaults.request_timeouts,
         };
 
         AppRuntimeLauncher {
@@ -93,6 +97,7 @@ impl AppRuntime {
             description: self.description,
             tags: self.tags,
             metadata: self.metadata,
+            probe_ready: self.probe_ready.unwrap_or(false),
             config,
         }
     }
@@ -110,6 +115,9 @@ pub struct AppRuntimeLauncher {
     pub description: Option<String>,
     pub tags: Vec<String>,
     pub metadata: Value,
+    /// Whether starts wait for the `initialize` handshake via
+    /// [`AppRuntimeHandle::launch_and_wait_ready`].
+    pub probe_ready: bool,
     pub config: StdioServerConfig,
 }
 
@@ -144,6 +152,8 @@ pub enum AppRuntimeError {
         #[source]
         source: McpError,
     },
+    #[error("runtime `{name}` did not become ready within {timeout:?}")]
+    StartupTimeout { name: String, timeout: Duration },
     #[error("failed to stop runtime `{name}`: {source}")]
     Stop {
         name: String,
@@ -157,6 +167,7 @@ pub enum AppRuntimeError {
 pub struct AppRuntimeHandle {
     pub name: String,
     pub metadata: Value,
+    pub probe_ready: bool,
     pub config: StdioServerConfig,
 }
 
@@ -192,6 +203,7 @@ impl AppRuntimeHandle {
             name,
             metadata,
             config,
+            ..
         } = self;
 
         let server = CodexAppServer::start(config.clone(), client).await?;
@@ -203,6 +215,27 @@ impl AppRuntimeHandle {
             server,
         })
     }
+
+    /// Launch the app-server and return only once it has answered `initialize`.
+    ///
+    /// The spawn and handshake together are bounded by `config.startup_timeout`; missing it
+    /// (or the `initialize` request timing out) yields [`AppRuntimeError::StartupTimeout`] so
+    /// callers never send `thread/start` to a server that is not ready.
+    pub async fn launch_and_wait_ready(
+        self,
+        client: ClientInfo,
+    ) -> Result<ManagedAppRuntime, AppRuntimeError> {
+        let name = self.name.clone();
+        let timeout = self.config.startup_timeout;
+        match tokio::time::timeout(timeout, self.start(client)).await {
+            Ok(Ok(runtime)) => Ok(runtime),
+            Ok(Err(McpError::RequestTimeout { method, .. })) if method == METHOD_INITIALIZE => {
+                Err(AppRuntimeError::StartupTimeout { name, timeout })
+            }
+            Ok(Err(source)) => Err(AppRuntimeError::Start { name, source }),
+            Err(_) => Err(AppRuntimeError::StartupTimeout { name, timeout }),
+        }
+    }
 }
 
 /// Non-destructive manager for app runtimes backed by launch-ready configs.
@@ -243,17 +276,24 @@ impl AppRuntimeManager {
         Ok(AppRuntimeHandle {
             name: launcher.name,
             metadata: launcher.metadata,
+            probe_ready: launcher.probe_ready,
             config: launcher.config,
         })
     }
 
     /// Start an app-server runtime using the prepared config and metadata.
+    ///
+    /// Runtimes configured with `probe_ready` go through
+    /// [`AppRuntimeHandle::launch_and_wait_ready`].
     pub async fn start(
         &self,
         name: &str,
         client: ClientInfo,
     ) -> Result<ManagedAppRuntime, AppRuntimeError> {
         let handle = self.prepare(name)?;
+        if handle.probe_ready {
+            return handle.launch_and_wait_ready(client).await;
+        }
         handle
             .start(client)
             .await
@@ -262,6 +302,15 @@ impl AppRuntimeManager {
                 source,
             })
     }
+
+    /// Start an app-server runtime and wait for its handshake regardless of `probe_ready`.
+    pub async fn launch_and_wait_ready(
+        &self,
+        name: &str,
+        client: ClientInfo,
+    ) -> Result<ManagedAppRuntime, AppRuntimeError> {
+        self.prepare(name)?.launch_and_wait_ready(client).await
+    }
 }
 
 /// Read-only helpers around [`AppRuntimeManager`] backed by stored config.
@@ -313,6 +362,15 @@ impl AppRuntimeApi {
         self.manager.start(name, client).await
     }
 
+    /// Start an app runtime and return only after its `initialize` handshake succeeded.
+    pub async fn launch_and_wait_ready(
+        &self,
+        name: &str,
+        client: ClientInfo,
+    ) -> Result<ManagedAppRuntime, AppRuntimeError> {
+        self.manager.launch_and_wait_ready(name, client).await
+    }
+
     /// Convenience accessor for the merged stdio config.
     pub fn stdio_config(&self, name: &str) -> Result<StdioServerConfig, AppRuntimeError> {
         self.prepare(name).map(|handle| handle.config)
@@ -2,12 +2,15 @@ use std::{io, sync::Arc, time::Duration};
 
 use serde_json::{json, Value};
 use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::warn;
 
 use super::{
-    AppCallHandle, ApprovalDecision, ClientInfo, CodexCallHandle, CodexCallParams, CodexCallResult,
-    CodexReplyParams, InitializeParams, RequestId, StdioServerConfig, METHOD_CODEX,
-    METHOD_CODEX_APPROVAL, METHOD_THREAD_RESUME, METHOD_THREAD_START, METHOD_TURN_INTERRUPT,
-    METHOD_TURN_START,
+    AppCallHandle, ApprovalDecision, ApprovalHandler, ClientInfo, CodexCallHandle, CodexCallParams,
+    CodexCallResult, CodexEvent, CodexReplyParams, EventStream, InitializeParams, McpNotification,
+    McpRequestTimeouts, RequestId, StdioLauncher, StdioServerConfig, MCP_PROTOCOL_VERSION,
+    METHOD_CODEX, METHOD_CODEX_APPROVAL, METHOD_THREAD_RESUME, METHOD_THREAD_START,
+    METHOD_TURN_INTERRUPT, METHOD_TURN_START,
 };
 
 use super::jsonrpc::{map_response, JsonRpcTransport};
@@ -37,6 +40,8 @@ pub enum McpError {
     Cancelled,
     #[error("timed out after {0:?}")]
     Timeout(Duration),
+    #[error("`{method}` request {id} timed out waiting for a response")]
+    RequestTimeout { method: &'static str, id: RequestId },
     #[error("serialization failed: {0}")]
     Serialization(#[from] serde_json::Error),
     #[error("transport channel closed unexpectedly")]
@@ -46,6 +51,7 @@ pub enum McpError {
 /// Client wrapper around the stdio MCP server.
 pub struct CodexMcpServer {
     transport: Arc<JsonRpcTransport>,
+    request_timeouts: McpRequestTimeouts,
 }
 
 impl CodexMcpServer {
@@ -64,47 +70,73 @@ impl CodexMcpServer {
             Value::Null => Value::Object(Default::default()),
             other => other,
         };
+        let request_timeouts = config.request_timeouts;
         let transport = JsonRpcTransport::spawn_mcp(config).await?;
         let params = InitializeParams {
             client,
-            protocol_version: "2024-11-05".to_string(),
+            protocol_version: MCP_PROTOCOL_VERSION.to_string(),
             capabilities,
         };
 
         transport
-            .initialize(params, transport.startup_timeout())
+            .initialize(params, initialize_timeout(&transport, &request_timeouts))
             .await
-            .map_err(|err| McpError::Handshake(err.to_string()))?;
+            .map_err(handshake_error)?;
 
         Ok(Self {
             transport: Arc::new(transport),
+            request_timeouts,
         })
     }
 
     /// Send a new Codex prompt via `codex/codex`.
     pub async fn codex(&self, params: CodexCallParams) -> Result<CodexCallHandle, McpError> {
-        self.invoke_tool_call("codex", serde_json::to_value(params)?)
-            .await
+        self.invoke_tool_call(
+            "codex",
+            serde_json::to_value(params)?,
+            "codex/codex",
+            self.request_timeouts.codex,
+        )
+        .await
     }
 
     /// Continue an existing conversation via `codex/codex-reply`.
     pub async fn codex_reply(&self, params: CodexReplyParams) -> Result<CodexCallHandle, McpError> {
-        self.invoke_tool_call("codex-reply", serde_json::to_value(params)?)
-            .await
+        self.invoke_tool_call(
+            "codex-reply",
+            serde_json::to_value(params)?,
+            "codex/codex-reply",
+            self.request_timeouts.codex_reply,
+        )
+        .await
     }
 
     /// Send an approval decision back to the MCP server.
     pub async fn send_approval(&self, decision: ApprovalDecision) -> Result<(), McpError> {
-        let (_, rx) = self
-            .transport
-            .request(METHOD_CODEX_APPROVAL, serde_json::to_value(decision)?)
-            .await?;
-
-        match rx.await {
-            Ok(Ok(_)) => Ok(()),
-            Ok(Err(err)) => Err(err),
-            Err(_) => Err(McpError::ChannelClosed),
-        }
+        send_approval(&self.transport, decision).await
+    }
+
+    /// Route every `codex/event` approval prompt through `handler` and reply automatically.
+    ///
+    /// The returned task runs until the server's event stream closes; abort it to stop
+    /// handling approvals.
+    pub async fn spawn_approval_handler<H>(&self, handler: H) -> JoinHandle<()>
+    where
+        H: ApprovalHandler,
+    {
+        let mut events = self.transport.register_codex_listener().await;
+        let transport = Arc::clone(&self.transport);
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                let CodexEvent::ApprovalRequired(request) = event else {
+                    continue;
+                };
+                let decision = handler.decide(request).await;
+                if let Err(error) = send_approval(&transport, decision).await {
+                    warn!(?error, "failed to send approval decision");
+                }
+            }
+        })
     }
 
     /// Request cancellation for a pending call.
@@ -121,13 +153,18 @@ impl CodexMcpServer {
         &self,
         tool_name: &str,
         arguments: Value,
+        method: &'static str,
+        timeout: Duration,
     ) -> Result<CodexCallHandle, McpError> {
-        let events = self.transport.register_codex_listener().await;
+        let (listener, events) = self.transport.register_codex_sink().await;
         let request = json!({
             "name": tool_name,
             "arguments": arguments,
         });
         let (request_id, raw_response) = self.transport.request(METHOD_CODEX, request).await?;
+        let raw_response =
+            self.transport
+                .with_deadline(method, request_id, raw_response, timeout, listener);
         let response = map_response::<CodexCallResult>(raw_response);
 
         Ok(CodexCallHandle {
@@ -138,6 +175,36 @@ impl CodexMcpServer {
     }
 }
 
+fn initialize_timeout(transport: &JsonRpcTransport, timeouts: &McpRequestTimeouts) -> Duration {
+    timeouts
+        .initialize
+        .unwrap_or_else(|| transport.startup_timeout())
+}
+
+/// Keeps [`McpError::RequestTimeout`] intact and folds other `initialize` failures into
+/// [`McpError::Handshake`].
+fn handshake_error(err: McpError) -> McpError {
+    match err {
+        McpError::RequestTimeout { .. } => err,
+        other => McpError::Handshake(other.to_string()),
+    }
+}
+
+async fn send_approval(
+    transport: &JsonRpcTransport,
+    decision: ApprovalDecision,
+) -> Result<(), McpError> {
+    let (_, rx) = transport
+        .request(METHOD_CODEX_APPROVAL, serde_json::to_value(decision)?)
+        .await?;
+
+    match rx.await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(err)) => Err(err),
+        Err(_) => Err(McpError::ChannelClosed),
+    }
+}
+
 /// Client wrapper around the stdio app-server.
 pub struct CodexAppServer {
     transport: Arc<JsonRpcTransport>,
@@ -159,17 +226,18 @@ impl CodexAppServer {
             Value::Null => Value::Object(Default::default()),
             other => other,
         };
+        let request_timeouts = config.request_timeouts;
         let transport = JsonRpcTransport::spawn_app(config).await?;
         let params = InitializeParams {
             client,
-            protocol_version: "2024-11-05".to_string(),
+            protocol_version: MCP_PROTOCOL_VERSION.to_string(),
             capabilities,
         };
 
         transport
-            .initialize(params, transport.startup_timeout())
+            .initialize(params, initialize_timeout(&transport, &request_timeouts))
             .await
-            .map_err(|err| McpError::Handshake(err.to_string()))?;
+            .map_err(handshake_error)?;
 
         Ok(Self {
             transport: Arc::new(transport),
@@ -238,3 +306,81 @@ impl CodexAppServer {
         })
     }
 }
+
+/// Initialized connection to a server started by [`super::McpServerLauncher::spawn`].
+pub struct McpConnection {
+    name: String,
+    transport: Arc<JsonRpcTransport>,
+    initialize_result: Value,
+    buffered: std::sync::Mutex<Option<EventStream<McpNotification>>>,
+}
+
+impl McpConnection {
+    pub(super) async fn connect(name: &str, launcher: &StdioLauncher) -> Result<Self, McpError> {
+        let transport = JsonRpcTransport::spawn_stdio(launcher).await?;
+        let buffered = transport.register_raw_listener().await;
+        let initialize_result = transport
+            .initialize(connection_initialize_params(), launcher.timeout)
+            .await
+            .map_err(handshake_error)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            transport: Arc::new(transport),
+            initialize_result,
+            buffered: std::sync::Mutex::new(Some(buffered)),
+        })
+    }
+
+    /// Name of the launched server.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Result returned by the server for `initialize` (capabilities, server info).
+    pub fn initialize_result(&self) -> &Value {
+        &self.initialize_result
+    }
+
+    /// Send a JSON-RPC request and wait for its result.
+    ///
+    /// No deadline applies; wrap the call in `tokio::time::timeout` when the server may stall.
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, McpError> {
+        let (_, rx) = self.transport.request(method, params).await?;
+        rx.await.map_err(|_| McpError::ChannelClosed)?
+    }
+
+    /// Subscribe to server notifications.
+    ///
+    /// The first call returns every notification received since spawn, including any sent
+    /// while `initialize` was in flight; later calls only see notifications from then on.
+    pub async fn notifications(&self) -> EventStream<McpNotification> {
+        let buffered = self
+            .buffered
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        match buffered {
+            Some(buffered) => buffered,
+            None => self.transport.register_raw_listener().await,
+        }
+    }
+
+    /// Request cancellation for a pending call.
+    pub fn cancel(&self, request_id: RequestId) -> Result<(), McpError> {
+        self.transport.cancel(request_id)
+    }
+
+    /// Gracefully shut down the server.
+    pub async fn shutdown(&self) -> Result<(), McpError> {
+        self.transport.shutdown().await
+    }
+}
+
+fn connection_initialize_params() -> InitializeParams {
+    InitializeParams {
+        client: ClientInfo::default(),
+        protocol_version: MCP_PROTOCOL_VERSION.to_string(),
+        capabilities: Value::Object(Default::default()),
+    }
+}
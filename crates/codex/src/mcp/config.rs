@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     env, fs, io,
     path::{Path, PathBuf},
 };
@@ -44,6 +44,85 @@ pub struct McpServerDefinition {
     pub tools: Option<McpToolConfig>,
 }
 
+impl McpServerDefinition {
+    /// Checks the transport fields for obviously broken values before they are persisted.
+    ///
+    /// Stdio servers need a non-empty command and arguments; streamable HTTP servers need an
+    /// `http(s)://host` URL and, when set, a `bearer_env_var` made of `[A-Za-z0-9_]` that does
+    /// not start with a digit.
+    pub fn validate(&self) -> Result<(), McpConfigError> {
+        match &self.transport {
+            McpTransport::Stdio(stdio) => {
+                if stdio.command.trim().is_empty() {
+                    return Err(McpConfigError::EmptyCommand);
+                }
+                if let Some(index) = stdio.args.iter().position(|arg| arg.is_empty()) {
+                    return Err(McpConfigError::EmptyArgument { index });
+                }
+            }
+            McpTransport::StreamableHttp(http) => {
+                validate_http_url(&http.url).map_err(|reason| McpConfigError::InvalidUrl {
+                    url: http.url.clone(),
+                    reason,
+                })?;
+                if let Some(name) = &http.bearer_env_var {
+                    if !is_portable_env_var_name(name) {
+                        return Err(McpConfigError::InvalidBearerEnvVar { name: name.clone() });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_http_url(url: &str) -> Result<(), &'static str> {
+    if url.chars().any(char::is_whitespace) {
+        return Err("contains whitespace");
+    }
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return Err("missing scheme");
+    };
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return Err("scheme must be http or https");
+    }
+
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let (host, port) = if let Some(bracketed) = host_port.strip_prefix('[') {
+        match bracketed.split_once(']') {
+            Some((host, tail)) => (host, tail.strip_prefix(':')),
+            None => return Err("unterminated IPv6 host"),
+        }
+    } else {
+        match host_port.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (host_port, None),
+        }
+    };
+
+    if host.is_empty() {
+        return Err("missing host");
+    }
+    if let Some(port) = port {
+        if port.parse::<u16>().is_err() {
+            return Err("invalid port");
+        }
+    }
+    Ok(())
+}
+
+fn is_portable_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 /// Supported transport definitions for MCP servers.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "transport", rename_all = "snake_case")]
@@ -72,6 +151,10 @@ pub struct StreamableHttpDefinition {
     pub headers: BTreeMap<String, String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bearer_env_var: Option<String>,
+    /// File holding the bearer token (e.g. a mounted Kubernetes secret); consulted only when
+    /// `bearer_env_var` yields no token. Contents are trimmed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_file: Option<PathBuf>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub connect_timeout_ms: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -87,6 +170,46 @@ pub struct McpToolConfig {
     pub disabled: Vec<String>,
 }
 
+impl McpToolConfig {
+    /// Layers `overlay` on top of `self` (e.g. a project refinement over an org baseline).
+    ///
+    /// The overlay wins any conflict: its `disabled` entries are removed from the base
+    /// `enabled` list and its `enabled` entries are removed from the base `disabled` list. A
+    /// tool listed in both of the overlay's lists stays disabled. Both resulting lists are
+    /// deduplicated and sorted so merged configs serialize deterministically.
+    pub fn merge(&self, overlay: &McpToolConfig) -> McpToolConfig {
+        let overlay_enabled: BTreeSet<&String> = overlay.enabled.iter().collect();
+        let overlay_disabled: BTreeSet<&String> = overlay.disabled.iter().collect();
+
+        let disabled: BTreeSet<String> = self
+            .disabled
+            .iter()
+            .filter(|tool| !overlay_enabled.contains(tool))
+            .chain(overlay_disabled.iter().copied())
+            .cloned()
+            .collect();
+        let enabled: BTreeSet<String> = self
+            .enabled
+            .iter()
+            .chain(overlay_enabled.iter().copied())
+            .filter(|tool| !disabled.contains(*tool))
+            .cloned()
+            .collect();
+
+        McpToolConfig {
+            enabled: enabled.into_iter().collect(),
+            disabled: disabled.into_iter().collect(),
+        }
+    }
+
+    /// Returns whether `name` may be used: it must not be disabled and, when an `enabled`
+    /// allow-list is present, must appear in it.
+    pub fn is_tool_allowed(&self, name: &str) -> bool {
+        !self.disabled.iter().any(|tool| tool == name)
+            && (self.enabled.is_empty() || self.enabled.iter().any(|tool| tool == name))
+    }
+}
+
 /// Stored definition for launching an app-server runtime.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AppRuntimeDefinition {
@@ -104,6 +227,10 @@ pub struct AppRuntimeDefinition {
     pub mirror_stdio: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub startup_timeout_ms: Option<u64>,
+    /// When true, starting the runtime waits for its `initialize` handshake within the startup
+    /// timeout (see [`super::AppRuntimeHandle::launch_and_wait_ready`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probe_ready: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub binary: Option<PathBuf>,
     #[serde(default, skip_serializing_if = "Value::is_null")]
@@ -213,6 +340,20 @@ pub enum McpConfigError {
     MissingBearerEnvVar { server: String },
     #[error("server `{server}` transport does not support login/logout")]
     UnsupportedAuthTransport { server: String },
+    #[error("stdio server command may not be empty")]
+    EmptyCommand,
+    #[error("stdio server argument {index} may not be empty")]
+    EmptyArgument { index: usize },
+    #[error("invalid server url `{url}`: {reason}")]
+    InvalidUrl { url: String, reason: &'static str },
+    #[error("invalid bearer_env_var `{name}`")]
+    InvalidBearerEnvVar { name: String },
+    #[error("failed to read bearer_file {path}: {source}")]
+    BearerFileRead {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
 }
 
 /// Helper to load and mutate MCP + app runtime config stored under `[mcp_servers]` and
@@ -323,18 +464,18 @@ impl McpConfigManager {
             .map(|runtime| runtime.into_launcher(defaults))
     }
 
-    /// Returns runtime-ready configs for all servers, resolving bearer tokens from the environment.
+    /// Returns runtime-ready configs for all servers, resolving bearer tokens from the environment
+    /// or token files.
     pub fn runtime_servers(&self) -> Result<Vec<McpRuntimeServer>, McpConfigError> {
-        Ok(self
-            .list_servers()?
+        self.list_servers()?
             .into_iter()
-            .map(McpRuntimeServer::from)
-            .collect())
+            .map(McpRuntimeServer::try_from)
+            .collect()
     }
 
     /// Returns a runtime-ready config for a single server by name.
     pub fn runtime_server(&self, name: &str) -> Result<McpRuntimeServer, McpConfigError> {
-        self.get_server(name).map(McpRuntimeServer::from)
+        self.get_server(name).and_then(McpRuntimeServer::try_from)
     }
 
     /// Returns prepared launchers/connectors for all runtime servers.
@@ -394,6 +535,7 @@ impl McpConfigManager {
         if request.name.trim().is_empty() {
             return Err(McpConfigError::InvalidServerName);
         }
+        request.definition.validate()?;
 
         let mut env_injections = request.env.clone();
         if let Some(token) = request.bearer_token.take() {
@@ -21,8 +21,8 @@ use tracing::{debug, warn};
 
 use super::{
     AppNotification, ApprovalKind, ApprovalRequest, CodexEvent, EventStream, InitializeParams,
-    McpError, RequestId, StdioServerConfig, METHOD_CANCEL, METHOD_CODEX_EVENT, METHOD_EXIT,
-    METHOD_INITIALIZE, METHOD_SHUTDOWN,
+    McpError, McpNotification, RequestId, StdioLauncher, StdioServerConfig, METHOD_CANCEL,
+    METHOD_CODEX_EVENT, METHOD_EXIT, METHOD_INITIALIZE, METHOD_SHUTDOWN,
 };
 
 #[derive(Clone)]
@@ -33,6 +33,9 @@ enum NotificationHook {
     App {
         sinks: Arc<Mutex<Vec<mpsc::UnboundedSender<AppNotification>>>>,
     },
+    Raw {
+        sinks: Arc<Mutex<Vec<mpsc::UnboundedSender<McpNotification>>>>,
+    },
 }
 
 type PendingRequests = Arc<Mutex<HashMap<RequestId, oneshot::Sender<Result<Value, McpError>>>>>;
@@ -63,6 +66,20 @@ impl JsonRpcTransport {
         Self::spawn_with_subcommand(config, "app-server", hook).await
     }
 
+    /// Spawns an arbitrary stdio MCP server; every notification is forwarded untouched.
+    pub(super) async fn spawn_stdio(launcher: &StdioLauncher) -> Result<Self, McpError> {
+        let hook = NotificationHook::Raw {
+            sinks: Arc::new(Mutex::new(Vec::new())),
+        };
+        Self::spawn_command(
+            launcher.command(),
+            launcher.mirror_stdio,
+            launcher.timeout,
+            hook,
+        )
+        .await
+    }
+
     async fn spawn_with_subcommand(
         config: StdioServerConfig,
         subcommand: &str,
@@ -92,6 +109,21 @@ impl JsonRpcTransport {
             command.env(key, value);
         }
 
+        Self::spawn_command(
+            command,
+            config.mirror_stdio,
+            config.startup_timeout,
+            notification_hook,
+        )
+        .await
+    }
+
+    async fn spawn_command(
+        mut command: Command,
+        mirror_stdio: bool,
+        startup_timeout: Duration,
+        notification_hook: NotificationHook,
+    ) -> Result<Self, McpError> {
         let command_debug = format!("{command:?}");
         let mut backoff = Duration::from_millis(2);
         let mut child = {
@@ -139,11 +171,10 @@ impl JsonRpcTransport {
             stdout,
             pending.clone(),
             notification_hook.clone(),
-            config.mirror_stdio,
+            mirror_stdio,
         ));
 
-        let stderr_handle =
-            stderr.map(|stderr| tokio::spawn(stderr_task(stderr, config.mirror_stdio)));
+        let stderr_handle = stderr.map(|stderr| tokio::spawn(stderr_task(stderr, mirror_stdio)));
 
         let mut tasks = vec![writer_handle, reader_handle];
         if let Some(handle) = stderr_handle {
@@ -157,7 +188,7 @@ impl JsonRpcTransport {
             next_id: AtomicU64::new(1),
             tasks,
             child: Arc::new(Mutex::new(Some(child))),
-            startup_timeout: config.startup_timeout,
+            startup_timeout,
         })
     }
 
@@ -166,10 +197,19 @@ impl JsonRpcTransport {
         params: InitializeParams,
         timeout: Duration,
     ) -> Result<Value, McpError> {
-        let (_, rx) = self
+        let (id, rx) = self
             .request(METHOD_INITIALIZE, serde_json::to_value(params)?)
             .await?;
-        recv_with_timeout(rx, timeout).await
+        match recv_with_timeout(rx, timeout).await {
+            Err(McpError::Timeout(_)) => {
+                self.pending.lock().await.remove(&id);
+                Err(McpError::RequestTimeout {
+                    method: METHOD_INITIALIZE,
+                    id,
+                })
+            }
+            other => other,
+        }
     }
 
     pub(super) async fn request(
@@ -201,17 +241,76 @@ impl JsonRpcTransport {
         Ok((id, rx))
     }
 
+    /// Races `rx` against `timeout`, failing it with [`McpError::RequestTimeout`] when the
+    /// server stays silent.
+    ///
+    /// On expiry the pending entry is dropped, `$/cancelRequest` is sent for `id`, and `listener`
+    /// is unregistered so its event stream ends. A zero `timeout` returns `rx` unchanged.
+    pub(super) fn with_deadline(
+        &self,
+        method: &'static str,
+        id: RequestId,
+        rx: oneshot::Receiver<Result<Value, McpError>>,
+        timeout: Duration,
+        listener: Option<mpsc::UnboundedSender<CodexEvent>>,
+    ) -> oneshot::Receiver<Result<Value, McpError>> {
+        if timeout.is_zero() {
+            return rx;
+        }
+
+        let pending = self.pending.clone();
+        let writer = self.writer.clone();
+        let sinks = match &self.notification_hook {
+            NotificationHook::Codex { sinks } => Some(sinks.clone()),
+            NotificationHook::App { .. } | NotificationHook::Raw { .. } => None,
+        };
+        let (tx, deadline_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let result = match time::timeout(timeout, rx).await {
+                Ok(Ok(result)) => result,
+                Ok(Err(_)) => Err(McpError::ChannelClosed),
+                Err(_) => {
+                    pending.lock().await.remove(&id);
+                    if let Ok(cancel) = serde_json::to_string(&cancel_message(id)) {
+                        let _ = writer.send(cancel);
+                    }
+                    if let (Some(sinks), Some(listener)) = (sinks, listener.as_ref()) {
+                        sinks
+                            .lock()
+                            .await
+                            .retain(|sink| !sink.same_channel(listener));
+                    }
+                    Err(McpError::RequestTimeout { method, id })
+                }
+            };
+            drop(listener);
+            let _ = tx.send(result);
+        });
+        deadline_rx
+    }
+
     pub(super) async fn register_codex_listener(&self) -> EventStream<CodexEvent> {
+        self.register_codex_sink().await.1
+    }
+
+    /// Registers a `codex/event` listener and returns a handle to its sender alongside the
+    /// stream so the caller can unregister it later.
+    pub(super) async fn register_codex_sink(
+        &self,
+    ) -> (
+        Option<mpsc::UnboundedSender<CodexEvent>>,
+        EventStream<CodexEvent>,
+    ) {
         match &self.notification_hook {
             NotificationHook::Codex { sinks } => {
                 let (tx, rx) = mpsc::unbounded_channel();
                 let mut guard = sinks.lock().await;
-                guard.push(tx);
-                rx
+                guard.push(tx.clone());
+                (Some(tx), rx)
             }
             _ => {
                 let (_tx, rx) = mpsc::unbounded_channel();
-                rx
+                (None, rx)
             }
         }
     }
@@ -231,13 +330,22 @@ impl JsonRpcTransport {
         }
     }
 
+    pub(super) async fn register_raw_listener(&self) -> EventStream<McpNotification> {
+        match &self.notification_hook {
+            NotificationHook::Raw { sinks } => {
+                let (tx, rx) = mpsc::unbounded_channel();
+                sinks.lock().await.push(tx);
+                rx
+            }
+            _ => {
+                let (_tx, rx) = mpsc::unbounded_channel();
+                rx
+            }
+        }
+    }
+
     pub(super) fn cancel(&self, request_id: RequestId) -> Result<(), McpError> {
-        let message = serde_json::json!({
-            "jsonrpc": "2.0",
-            "method": METHOD_CANCEL,
-            "params": { "id": request_id }
-        });
-        let serialized = serde_json::to_string(&message)?;
+        let serialized = serde_json::to_string(&cancel_message(request_id))?;
         self.writer
             .send(serialized)
             .map_err(|_| McpError::ChannelClosed)
@@ -280,6 +388,14 @@ impl Drop for JsonRpcTransport {
     }
 }
 
+fn cancel_message(request_id: RequestId) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": METHOD_CANCEL,
+        "params": { "id": request_id }
+    })
+}
+
 #[derive(Debug, Deserialize)]
 struct RpcResponse {
     id: Value,
@@ -358,6 +474,16 @@ async fn reader_task(
                     let event = parse_app_notification(&notification.method, &params);
                     broadcast_app_event(event, sinks).await;
                 }
+                NotificationHook::Raw { sinks } => {
+                    let event = McpNotification {
+                        method: notification.method,
+                        params: notification.params.unwrap_or(Value::Null),
+                    };
+                    sinks
+                        .lock()
+                        .await
+                        .retain(|tx| tx.send(event.clone()).is_ok());
+                }
             },
             None => {
                 warn!("received malformed MCP message");
@@ -397,11 +523,17 @@ pub(super) fn map_response<T: for<'a> Deserialize<'a> + Send + 'static>(
     mapped_rx
 }
 
+/// Awaits `rx` for at most `timeout`; a zero `timeout` waits indefinitely.
 async fn recv_with_timeout(
     rx: oneshot::Receiver<Result<Value, McpError>>,
     timeout: Duration,
 ) -> Result<Value, McpError> {
-    match time::timeout(timeout, rx).await {
+    let received = if timeout.is_zero() {
+        Ok(rx.await)
+    } else {
+        time::timeout(timeout, rx).await
+    };
+    match received {
         Ok(Ok(Ok(value))) => Ok(value),
         Ok(Ok(Err(err))) => Err(err),
         Ok(Err(_)) => Err(McpError::ChannelClosed),
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, ffi::OsString, path::PathBuf, time::Duration};
+use std::{collections::BTreeMap, ffi::OsString, future::Future, path::PathBuf, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -31,6 +31,9 @@ pub const METHOD_TURN_START: &str = "turn/start";
 /// Interrupt an active turn.
 pub const METHOD_TURN_INTERRUPT: &str = "turn/interrupt";
 
+/// MCP protocol revision sent in every `initialize` request.
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
 /// Unique identifier for JSON-RPC calls.
 pub type RequestId = u64;
 
@@ -54,6 +57,45 @@ pub struct StdioServerConfig {
     pub app_server_analytics_default_enabled: bool,
     pub mirror_stdio: bool,
     pub startup_timeout: Duration,
+    /// Response deadlines for `initialize`, `codex/codex`, and `codex/codex-reply`.
+    pub request_timeouts: McpRequestTimeouts,
+}
+
+/// Per-method JSON-RPC response deadlines for the MCP driver.
+///
+/// A request that outlives its deadline fails with [`super::McpError::RequestTimeout`], is
+/// cancelled on the server, and its event stream is closed. `Duration::ZERO` disables the
+/// deadline for that method, including `initialize`. The default bounds only `initialize` (via
+/// the startup timeout) and leaves tool calls unbounded, since a Codex turn can run for a long
+/// time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct McpRequestTimeouts {
+    /// Deadline for the `initialize` handshake; `None` falls back to
+    /// [`StdioServerConfig::startup_timeout`].
+    pub initialize: Option<Duration>,
+    /// Deadline for `codex/codex` tool calls (unbounded by default).
+    pub codex: Duration,
+    /// Deadline for `codex/codex-reply` tool calls (unbounded by default).
+    pub codex_reply: Duration,
+}
+
+impl McpRequestTimeouts {
+    /// Uses `timeout` (typically the [`crate::CodexClientBuilder::timeout`] value) for both tool
+    /// calls and the startup timeout for `initialize`.
+    pub fn from_client_timeout(timeout: Duration) -> Self {
+        Self {
+            initialize: None,
+            codex: timeout,
+            codex_reply: timeout,
+        }
+    }
+}
+
+impl Default for McpRequestTimeouts {
+    /// Bounds `initialize` by the startup timeout and leaves tool calls unbounded.
+    fn default() -> Self {
+        Self::from_client_timeout(Duration::ZERO)
+    }
 }
 
 /// Client metadata attached to the `initialize` request.
@@ -63,6 +105,16 @@ pub struct ClientInfo {
     pub version: String,
 }
 
+impl Default for ClientInfo {
+    /// Identifies this crate (`CARGO_PKG_NAME`/`CARGO_PKG_VERSION`) to the server.
+    fn default() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
 /// Parameters for the initial `initialize` handshake.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct InitializeParams {
@@ -118,18 +170,60 @@ pub struct ApprovalRequest {
     pub payload: Value,
 }
 
+impl ApprovalRequest {
+    /// Command awaiting approval (exec prompts); argv arrays are joined with spaces.
+    pub fn command(&self) -> Option<String> {
+        match self.payload.get("command")? {
+            Value::String(command) => Some(command.clone()),
+            Value::Array(parts) => Some(
+                parts
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Patch/diff awaiting approval (apply prompts).
+    pub fn patch(&self) -> Option<&str> {
+        self.payload
+            .get("patch")
+            .or_else(|| self.payload.get("diff"))
+            .and_then(Value::as_str)
+    }
+
+    /// Server-supplied justification for the approval prompt.
+    pub fn reason(&self) -> Option<&str> {
+        self.payload.get("reason").and_then(Value::as_str)
+    }
+}
+
 /// Decision payload sent back to the MCP server in response to an approval prompt.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ApprovalDecision {
     Approve {
         approval_id: String,
     },
+    /// Approve this request and any identical ones for the rest of the session.
+    ApproveForSession {
+        approval_id: String,
+    },
     Reject {
         approval_id: String,
         reason: Option<String>,
     },
 }
 
+/// Host callback that decides `codex/event` approval prompts.
+///
+/// Register with [`super::CodexMcpServer::spawn_approval_handler`]; each decision is sent back
+/// over `codex/approval` automatically.
+pub trait ApprovalHandler: Send + Sync + 'static {
+    fn decide(&self, request: ApprovalRequest) -> impl Future<Output = ApprovalDecision> + Send;
+}
+
 /// Notification emitted by `codex/event`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -236,6 +330,14 @@ pub enum AppNotification {
     },
 }
 
+/// JSON-RPC notification from a server started via [`super::McpServerLauncher::spawn`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct McpNotification {
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
 /// Handle returned for each app-server call, bundling response and notifications.
 pub struct AppCallHandle {
     pub request_id: RequestId,
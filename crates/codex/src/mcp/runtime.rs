@@ -15,8 +15,9 @@ use tokio::{
 };
 
 use super::{
-    McpConfigError, McpConfigManager, McpServerDefinition, McpServerEntry, McpToolConfig,
-    McpTransport, StdioServerConfig, StdioServerDefinition, StreamableHttpDefinition,
+    McpConfigError, McpConfigManager, McpConnection, McpError, McpServerDefinition, McpServerEntry,
+    McpToolConfig, McpTransport, StdioServerConfig, StdioServerDefinition,
+    StreamableHttpDefinition,
 };
 
 /// Resolved runtime configuration for an MCP server, ready for spawning or connecting.
@@ -40,7 +41,7 @@ pub enum McpRuntimeTransport {
     StreamableHttp(ResolvedStreamableHttpDefinition),
 }
 
-/// HTTP runtime config with bearer tokens resolved from the environment.
+/// HTTP runtime config with bearer tokens resolved from the environment or a token file.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ResolvedStreamableHttpDefinition {
     pub url: String,
@@ -98,8 +99,10 @@ pub struct StreamableHttpConnector {
     pub request_timeout: Option<Duration>,
 }
 
-impl From<McpServerEntry> for McpRuntimeServer {
-    fn from(entry: McpServerEntry) -> Self {
+impl TryFrom<McpServerEntry> for McpRuntimeServer {
+    type Error = McpConfigError;
+
+    fn try_from(entry: McpServerEntry) -> Result<Self, Self::Error> {
         let McpServerEntry { name, definition } = entry;
         McpRuntimeServer::from_definition(name, definition)
     }
@@ -107,7 +110,13 @@ impl From<McpServerEntry> for McpRuntimeServer {
 
 impl McpRuntimeServer {
     /// Builds a runtime config from a stored server definition.
-    pub fn from_definition(name: impl Into<String>, definition: McpServerDefinition) -> Self {
+    ///
+    /// Fails with [`McpConfigError::BearerFileRead`] when an HTTP server's `bearer_file` must be
+    /// consulted but cannot be read.
+    pub fn from_definition(
+        name: impl Into<String>,
+        definition: McpServerDefinition,
+    ) -> Result<Self, McpConfigError> {
         let McpServerDefinition {
             transport,
             description,
@@ -115,13 +124,13 @@ impl McpRuntimeServer {
             tools,
         } = definition;
 
-        Self {
+        Ok(Self {
             name: name.into(),
-            transport: McpRuntimeTransport::from_transport(transport),
+            transport: McpRuntimeTransport::from_transport(transport)?,
             description,
             tags,
             tools,
-        }
+        })
     }
 
     /// Converts a runtime server into a launcher/connector, merging stdio defaults.
@@ -159,50 +168,88 @@ impl McpRuntimeServer {
 }
 
 impl McpRuntimeTransport {
-    fn from_transport(transport: McpTransport) -> Self {
-        match transport {
+    fn from_transport(transport: McpTransport) -> Result<Self, McpConfigError> {
+        Ok(match transport {
             McpTransport::Stdio(definition) => McpRuntimeTransport::Stdio(definition),
             McpTransport::StreamableHttp(definition) => {
-                McpRuntimeTransport::StreamableHttp(resolve_streamable_http(definition))
+                McpRuntimeTransport::StreamableHttp(resolve_streamable_http(definition)?)
             }
-        }
+        })
     }
 }
 
+/// Resolves the bearer token from `bearer_env_var` first, falling back to `bearer_file` when the
+/// variable is unset or empty. An explicit `Authorization` header is never overwritten, and the
+/// file is not read when such a header is present. Tokens are only resolved for `https://` URLs
+/// so credentials are never sent in cleartext.
 fn resolve_streamable_http(
     definition: StreamableHttpDefinition,
-) -> ResolvedStreamableHttpDefinition {
+) -> Result<ResolvedStreamableHttpDefinition, McpConfigError> {
     let StreamableHttpDefinition {
         url,
         headers,
         bearer_env_var,
+        bearer_file,
         connect_timeout_ms,
         request_timeout_ms,
     } = definition;
 
     let mut headers = headers;
-    let mut bearer_token = None;
-    if let Some(env_var) = bearer_env_var.as_deref() {
-        if let Ok(token) = env::var(env_var) {
-            if !token.is_empty() {
-                let has_auth_header = headers
-                    .keys()
-                    .any(|key| key.eq_ignore_ascii_case("authorization"));
-                if !has_auth_header {
-                    headers.insert("Authorization".into(), format!("Bearer {token}"));
-                }
-                bearer_token = Some(token);
-            }
+    let uses_tls = url
+        .get(..8)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"));
+    let mut bearer_token = bearer_env_var
+        .as_deref()
+        .filter(|_| uses_tls)
+        .and_then(|env_var| env::var(env_var).ok())
+        .filter(|token| !token.is_empty());
+    let has_auth_header = headers
+        .keys()
+        .any(|key| key.eq_ignore_ascii_case("authorization"));
+    if uses_tls && bearer_token.is_none() && !has_auth_header {
+        if let Some(path) = bearer_file.as_deref() {
+            let contents =
+                std::fs::read_to_string(path).map_err(|source| McpConfigError::BearerFileRead {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+            let token = contents.trim();
+            bearer_token = (!token.is_empty()).then(|| token.to_string());
+        }
+    }
+    if let Some(token) = bearer_token.as_deref() {
+        if !has_auth_header {
+            headers.insert("Authorization".into(), format!("Bearer {token}"));
         }
     }
 
-    ResolvedStreamableHttpDefinition {
+    Ok(ResolvedStreamableHttpDefinition {
         url,
         headers,
         bearer_env_var,
         bearer_token,
         connect_timeout_ms,
         request_timeout_ms,
+    })
+}
+
+impl McpServerLauncher {
+    /// Connect to the server and return a connection once `initialize` has completed.
+    ///
+    /// Stdio children run with the merged [`StdioLauncher::env`] and must answer the handshake
+    /// within [`StdioLauncher::timeout`], otherwise [`McpError::RequestTimeout`] is returned
+    /// and the process is killed. Only stdio servers can be spawned: the streamable HTTP
+    /// transport is not supported and such launchers fail with [`McpError::Transport`].
+    pub async fn spawn(&self) -> Result<McpConnection, McpError> {
+        match &self.transport {
+            McpServerLauncherTransport::Stdio(launch) => {
+                McpConnection::connect(&self.name, launch).await
+            }
+            McpServerLauncherTransport::StreamableHttp(_) => Err(McpError::Transport(format!(
+                "`{}` uses streamable_http; the streamable HTTP transport is not supported",
+                self.name
+            ))),
+        }
     }
 }
 
@@ -47,6 +47,7 @@ pub(super) fn streamable_definition(url: &str, bearer_var: &str) -> McpServerDef
             url: url.to_string(),
             headers: BTreeMap::new(),
             bearer_env_var: Some(bearer_var.to_string()),
+            bearer_file: None,
             connect_timeout_ms: Some(5000),
             request_timeout_ms: Some(5000),
         }),
@@ -64,6 +65,7 @@ pub(super) fn write_fake_mcp_server() -> (tempfile::TempDir, PathBuf) {
     let script_path = dir.path().join("fake-codex");
     let script = r#"#!/usr/bin/env python3
 import json
+import os
 import sys
 import threading
 import time
@@ -118,6 +120,11 @@ for line in sys.stdin:
     elif method == "$/cancelRequest":
         target = msg.get("params", {}).get("id")
         mark_cancelled(target, reason="client_cancel")
+    elif method == "codex/approval":
+        log_path = os.path.join(os.path.dirname(os.path.abspath(sys.argv[0])), "approvals.log")
+        with open(log_path, "a") as log:
+            log.write(json.dumps(msg.get("params")) + "\n")
+        send({"jsonrpc": "2.0", "id": msg.get("id"), "result": {"ok": True}})
     elif method == "shutdown":
         send({"jsonrpc": "2.0", "id": msg.get("id"), "result": {"ok": True}})
         break
@@ -132,6 +139,44 @@ for line in sys.stdin:
     (dir, script_path)
 }
 
+/// MCP server that answers `initialize` (unless `STALL_INITIALIZE` is set) but never replies to
+/// tool calls; `$/cancelRequest` ids are appended to `cancels.log` next to the script.
+pub(super) fn write_stalling_mcp_server() -> (tempfile::TempDir, PathBuf) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let script_path = dir.path().join("stalling-codex");
+    let script = r#"#!/usr/bin/env python3
+import json
+import os
+import sys
+
+log_path = os.path.join(os.path.dirname(os.path.abspath(sys.argv[0])), "cancels.log")
+
+for line in sys.stdin:
+    if not line.strip():
+        continue
+    msg = json.loads(line)
+    method = msg.get("method")
+    if method == "initialize" and not os.environ.get("STALL_INITIALIZE"):
+        sys.stdout.write(json.dumps({"jsonrpc": "2.0", "id": msg.get("id"), "result": {"ready": True}}) + "\n")
+        sys.stdout.flush()
+    elif method == "$/cancelRequest":
+        with open(log_path, "a") as log:
+            log.write(str(msg.get("params", {}).get("id")) + "\n")
+    elif method == "shutdown":
+        sys.stdout.write(json.dumps({"jsonrpc": "2.0", "id": msg.get("id"), "result": {"ok": True}}) + "\n")
+        sys.stdout.flush()
+        break
+    elif method == "exit":
+        break
+"#;
+
+    fs::write(&script_path, script).expect("write script");
+    let mut perms = fs::metadata(&script_path).expect("metadata").permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&script_path, perms).expect("chmod");
+    (dir, script_path)
+}
+
 pub(super) fn write_fake_app_server() -> (tempfile::TempDir, PathBuf) {
     let dir = tempfile::tempdir().expect("tempdir");
     let script_path = dir.path().join("fake-codex-app");
@@ -194,6 +239,8 @@ for line in sys.stdin:
     msg = json.loads(line)
     method = msg.get("method")
     if method == "initialize":
+        if os.environ.get("NOTIFY_ON_INITIALIZE"):
+            send({"jsonrpc": "2.0", "method": "server/ready", "params": {"transport": "stdio"}})
         send({"jsonrpc": "2.0", "id": msg.get("id"), "result": {"ready": True}})
     elif method == "thread/start":
         params = msg.get("params", {})
@@ -262,6 +309,7 @@ pub(super) fn test_config(binary: PathBuf) -> StdioServerConfig {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(5),
+        request_timeouts: McpRequestTimeouts::default(),
     }
 }
 
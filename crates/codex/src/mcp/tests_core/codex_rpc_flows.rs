@@ -168,3 +168,161 @@ async fn codex_reply_streams_follow_up_notifications() {
 
     let _ = server.shutdown().await;
 }
+
+struct ApproveForSession;
+
+impl ApprovalHandler for ApproveForSession {
+    async fn decide(&self, request: ApprovalRequest) -> ApprovalDecision {
+        ApprovalDecision::ApproveForSession {
+            approval_id: request.approval_id,
+        }
+    }
+}
+
+#[tokio::test]
+async fn approval_handler_replies_automatically() {
+    let (dir, script) = write_fake_mcp_server();
+    let server = CodexMcpServer::start(test_config(script), test_client())
+        .await
+        .expect("spawn mcp server");
+    let handler_task = server.spawn_approval_handler(ApproveForSession).await;
+
+    let params = CodexCallParams {
+        prompt: "needs approval".into(),
+        model: None,
+        cwd: None,
+        sandbox: None,
+        approval_policy: None,
+        profile: None,
+        config: BTreeMap::new(),
+    };
+    let handle = server.codex(params).await.expect("codex call");
+    let expected_approval = format!("ap-{}", handle.request_id);
+    time::timeout(Duration::from_secs(2), handle.response)
+        .await
+        .expect("response timeout")
+        .expect("response recv")
+        .expect("response ok");
+
+    let log_path = dir.path().join("approvals.log");
+    let mut logged = String::new();
+    for _ in 0..50 {
+        logged = fs::read_to_string(&log_path).unwrap_or_default();
+        if !logged.is_empty() {
+            break;
+        }
+        time::sleep(Duration::from_millis(20)).await;
+    }
+    let decision: Value =
+        serde_json::from_str(logged.lines().next().expect("approval sent")).expect("decision json");
+    assert_eq!(
+        decision["ApproveForSession"]["approval_id"],
+        Value::String(expected_approval)
+    );
+
+    handler_task.abort();
+    let _ = server.shutdown().await;
+}
+
+#[test]
+fn approval_request_exposes_command_patch_and_reason() {
+    let request = ApprovalRequest {
+        approval_id: "ap-1".into(),
+        kind: ApprovalKind::Exec,
+        payload: serde_json::json!({
+            "command": ["bash", "-lc", "rm -rf target"],
+            "reason": "cleanup build output"
+        }),
+    };
+    assert_eq!(request.command().as_deref(), Some("bash -lc rm -rf target"));
+    assert_eq!(request.reason(), Some("cleanup build output"));
+    assert!(request.patch().is_none());
+
+    let apply = ApprovalRequest {
+        approval_id: "ap-2".into(),
+        kind: ApprovalKind::Apply,
+        payload: serde_json::json!({"patch": "--- a\n+++ b\n"}),
+    };
+    assert_eq!(apply.patch(), Some("--- a\n+++ b\n"));
+}
+
+#[tokio::test]
+async fn stalled_codex_call_times_out_and_closes_events() {
+    let (dir, script) = write_stalling_mcp_server();
+    let mut config = test_config(script);
+    config.request_timeouts.codex = Duration::from_millis(200);
+    let server = CodexMcpServer::start(config, test_client())
+        .await
+        .expect("spawn mcp server");
+
+    let params = CodexCallParams {
+        prompt: "never answered".into(),
+        model: None,
+        cwd: None,
+        sandbox: None,
+        approval_policy: None,
+        profile: None,
+        config: BTreeMap::new(),
+    };
+    let mut handle = server.codex(params).await.expect("codex call");
+    let request_id = handle.request_id;
+
+    let response = time::timeout(Duration::from_secs(2), handle.response)
+        .await
+        .expect("deadline should fire")
+        .expect("response recv");
+    match response {
+        Err(McpError::RequestTimeout { method, id }) => {
+            assert_eq!(method, "codex/codex");
+            assert_eq!(id, request_id);
+        }
+        other => panic!("expected RequestTimeout, got {other:?}"),
+    }
+
+    let closed = time::timeout(Duration::from_secs(2), handle.events.recv())
+        .await
+        .expect("event stream should close");
+    assert!(closed.is_none());
+
+    let cancels = dir.path().join("cancels.log");
+    let deadline = std::time::Instant::now() + Duration::from_secs(2);
+    while !cancels.exists() && std::time::Instant::now() < deadline {
+        time::sleep(Duration::from_millis(20)).await;
+    }
+    let logged = fs::read_to_string(&cancels).expect("cancel log");
+    assert_eq!(logged.trim(), request_id.to_string());
+
+    let _ = server.shutdown().await;
+}
+
+#[tokio::test]
+async fn stalled_initialize_returns_request_timeout() {
+    let (_dir, script) = write_stalling_mcp_server();
+    let mut config = test_config(script);
+    config
+        .env
+        .push((OsString::from("STALL_INITIALIZE"), OsString::from("1")));
+    config.request_timeouts.initialize = Some(Duration::from_millis(200));
+
+    match CodexMcpServer::start(config, test_client()).await {
+        Err(McpError::RequestTimeout { method, .. }) => assert_eq!(method, "initialize"),
+        Err(other) => panic!("expected RequestTimeout, got {other:?}"),
+        Ok(_) => panic!("initialize should not succeed"),
+    }
+}
+
+#[tokio::test]
+async fn zero_request_timeouts_disable_deadlines() {
+    let defaults = McpRequestTimeouts::default();
+    assert_eq!(defaults.initialize, None);
+    assert!(defaults.codex.is_zero());
+    assert!(defaults.codex_reply.is_zero());
+
+    let (_dir, script) = write_stalling_mcp_server();
+    let mut config = test_config(script);
+    config.request_timeouts.initialize = Some(Duration::ZERO);
+    let server = CodexMcpServer::start(config, test_client())
+        .await
+        .expect("zero initialize deadline waits for the handshake");
+    let _ = server.shutdown().await;
+}
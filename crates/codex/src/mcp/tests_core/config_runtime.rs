@@ -295,6 +295,7 @@ fn runtime_stdio_launcher_merges_env_timeout_and_tools() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: true,
         startup_timeout: Duration::from_secs(5),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let mut definition = StdioServerDefinition {
@@ -369,6 +370,7 @@ fn streamable_http_connector_converts_timeouts_and_headers() {
         url: "https://example.test/stream".into(),
         headers: BTreeMap::new(),
         bearer_env_var: Some(env_var.to_string()),
+        bearer_file: None,
         connect_timeout_ms: Some(1200),
         request_timeout_ms: Some(3400),
     };
@@ -385,7 +387,8 @@ fn streamable_http_connector_converts_timeouts_and_headers() {
                 disabled: vec![],
             }),
         },
-    );
+    )
+    .expect("runtime server");
 
     let defaults = StdioServerConfig {
         binary: PathBuf::from("codex"),
@@ -395,6 +398,7 @@ fn streamable_http_connector_converts_timeouts_and_headers() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(2),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let launcher = runtime.into_launcher(&defaults);
@@ -423,3 +427,223 @@ fn streamable_http_connector_converts_timeouts_and_headers() {
 
     env::remove_var(env_var);
 }
+
+fn resolve_http_bearer(
+    env_var: &str,
+    bearer_file: Option<PathBuf>,
+    headers: BTreeMap<String, String>,
+) -> Result<ResolvedStreamableHttpDefinition, McpConfigError> {
+    let runtime = McpRuntimeServer::from_definition(
+        "remote-file",
+        McpServerDefinition {
+            transport: McpTransport::StreamableHttp(StreamableHttpDefinition {
+                url: "https://example.test/file".into(),
+                headers,
+                bearer_env_var: Some(env_var.to_string()),
+                bearer_file,
+                connect_timeout_ms: None,
+                request_timeout_ms: None,
+            }),
+            description: None,
+            tags: Vec::new(),
+            tools: None,
+        },
+    )?;
+    match runtime.transport {
+        McpRuntimeTransport::StreamableHttp(resolved) => Ok(resolved),
+        other => panic!("expected http transport, got {other:?}"),
+    }
+}
+
+#[test]
+fn streamable_http_bearer_prefers_env_var_over_file() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let token_file = dir.path().join("token");
+    fs::write(&token_file, "token-from-file\n").expect("write token");
+    let env_var = "MCP_HTTP_TOKEN_FILE_ENV";
+    env::set_var(env_var, "token-from-env");
+
+    let resolved =
+        resolve_http_bearer(env_var, Some(token_file), BTreeMap::new()).expect("resolve");
+    assert_eq!(resolved.bearer_token.as_deref(), Some("token-from-env"));
+    assert_eq!(
+        resolved.headers.get("Authorization").map(String::as_str),
+        Some("Bearer token-from-env")
+    );
+
+    // A missing file is not consulted while the env var supplies the token.
+    let resolved = resolve_http_bearer(env_var, Some(dir.path().join("missing")), BTreeMap::new())
+        .expect("resolve");
+    assert_eq!(resolved.bearer_token.as_deref(), Some("token-from-env"));
+    env::remove_var(env_var);
+}
+
+#[test]
+fn streamable_http_bearer_falls_back_to_trimmed_file() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let token_file = dir.path().join("token");
+    fs::write(&token_file, "  token-from-file\n").expect("write token");
+    let env_var = "MCP_HTTP_TOKEN_FILE_FALLBACK";
+    env::set_var(env_var, "");
+
+    let resolved =
+        resolve_http_bearer(env_var, Some(token_file.clone()), BTreeMap::new()).expect("resolve");
+    assert_eq!(resolved.bearer_token.as_deref(), Some("token-from-file"));
+    assert_eq!(
+        resolved.headers.get("Authorization").map(String::as_str),
+        Some("Bearer token-from-file")
+    );
+
+    // An explicit Authorization header makes the file unnecessary, so it is never read.
+    let mut headers = BTreeMap::new();
+    headers.insert("authorization".to_string(), "Basic explicit".to_string());
+    let resolved =
+        resolve_http_bearer(env_var, Some(dir.path().join("missing")), headers).expect("resolve");
+    assert_eq!(resolved.bearer_token, None);
+    assert_eq!(resolved.headers.get("Authorization"), None);
+    assert_eq!(
+        resolved.headers.get("authorization").map(String::as_str),
+        Some("Basic explicit")
+    );
+    env::remove_var(env_var);
+}
+
+#[test]
+fn streamable_http_bearer_file_missing_is_reported() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let missing = dir.path().join("missing-token");
+    let env_var = "MCP_HTTP_TOKEN_FILE_MISSING";
+    env::remove_var(env_var);
+
+    match resolve_http_bearer(env_var, Some(missing.clone()), BTreeMap::new()) {
+        Err(McpConfigError::BearerFileRead { path, source }) => {
+            assert_eq!(path, missing);
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        other => panic!("expected BearerFileRead, got {other:?}"),
+    }
+
+    let resolved = resolve_http_bearer(env_var, None, BTreeMap::new()).expect("resolve");
+    assert_eq!(resolved.bearer_token, None);
+    assert!(resolved.headers.is_empty());
+}
+
+#[test]
+fn streamable_http_bearer_is_not_sent_over_plain_http() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let token_file = dir.path().join("token");
+    fs::write(&token_file, "token-from-file").expect("write token");
+    let env_var = "MCP_HTTP_TOKEN_PLAINTEXT";
+    env::set_var(env_var, "token-from-env");
+
+    let mut definition = streamable_definition("http://example.test/mcp", env_var);
+    if let McpTransport::StreamableHttp(ref mut def) = definition.transport {
+        def.bearer_file = Some(token_file);
+    }
+    let runtime = McpRuntimeServer::from_definition("plain", definition).expect("resolve");
+    match runtime.transport {
+        McpRuntimeTransport::StreamableHttp(resolved) => {
+            assert_eq!(resolved.bearer_token, None);
+            assert_eq!(resolved.headers.get("Authorization"), None);
+        }
+        other => panic!("expected http transport, got {other:?}"),
+    }
+    env::remove_var(env_var);
+}
+
+#[test]
+fn validate_rejects_malformed_definitions() {
+    let mut stdio = stdio_definition("  ");
+    assert!(matches!(
+        stdio.validate(),
+        Err(McpConfigError::EmptyCommand)
+    ));
+    if let McpTransport::Stdio(ref mut def) = stdio.transport {
+        def.command = "my-mcp".into();
+        def.args = vec!["--flag".into(), String::new()];
+    }
+    assert!(matches!(
+        stdio.validate(),
+        Err(McpConfigError::EmptyArgument { index: 1 })
+    ));
+
+    for url in [
+        "example.test/mcp",
+        "ftp://example.test",
+        "https:///mcp",
+        "https://example.test:99999/",
+        "https://exa mple.test",
+    ] {
+        let definition = streamable_definition(url, "MCP_TOKEN");
+        assert!(
+            matches!(
+                definition.validate(),
+                Err(McpConfigError::InvalidUrl { .. })
+            ),
+            "expected {url} to be rejected"
+        );
+    }
+    for url in [
+        "http://localhost:8080/mcp",
+        "https://user@example.test/mcp?x=1",
+        "https://[::1]:9000",
+    ] {
+        streamable_definition(url, "MCP_TOKEN")
+            .validate()
+            .unwrap_or_else(|err| panic!("expected {url} to be accepted: {err}"));
+    }
+
+    let definition = streamable_definition("https://example.test/mcp", "1BAD-NAME");
+    assert!(matches!(
+        definition.validate(),
+        Err(McpConfigError::InvalidBearerEnvVar { name }) if name == "1BAD-NAME"
+    ));
+}
+
+#[test]
+fn add_server_rejects_invalid_definition_without_writing_config() {
+    let (dir, manager) = temp_config_manager();
+
+    let err = manager
+        .add_server(AddServerRequest {
+            name: "broken".into(),
+            definition: streamable_definition("not a url", "MCP_TOKEN_INVALID"),
+            overwrite: false,
+            env: BTreeMap::new(),
+            bearer_token: Some("token".into()),
+        })
+        .unwrap_err();
+
+    assert!(matches!(err, McpConfigError::InvalidUrl { .. }));
+    assert!(!dir.path().join(DEFAULT_CONFIG_FILE).exists());
+    assert!(env::var("MCP_TOKEN_INVALID").is_err());
+}
+
+#[test]
+fn tool_config_merge_prefers_overlay_and_sorts() {
+    let base = McpToolConfig {
+        enabled: vec!["search".into(), "deploy".into(), "search".into()],
+        disabled: vec!["shell".into(), "fetch".into()],
+    };
+    let overlay = McpToolConfig {
+        enabled: vec!["fetch".into(), "lint".into(), "both".into()],
+        disabled: vec!["deploy".into(), "both".into()],
+    };
+
+    let merged = base.merge(&overlay);
+    assert_eq!(merged.enabled, vec!["fetch", "lint", "search"]);
+    assert_eq!(merged.disabled, vec!["both", "deploy", "shell"]);
+
+    assert!(merged.is_tool_allowed("search"));
+    assert!(merged.is_tool_allowed("fetch"));
+    assert!(!merged.is_tool_allowed("deploy"));
+    assert!(!merged.is_tool_allowed("unlisted"));
+
+    let deny_only = McpToolConfig {
+        enabled: Vec::new(),
+        disabled: vec!["shell".into()],
+    };
+    assert!(deny_only.is_tool_allowed("anything"));
+    assert!(!deny_only.is_tool_allowed("shell"));
+    assert_eq!(deny_only.merge(&McpToolConfig::default()), deny_only);
+}
@@ -22,6 +22,7 @@ fn app_runtime_api_lists_and_merges_without_writes() {
                 current_dir: Some(alpha_cwd.clone()),
                 mirror_stdio: Some(true),
                 startup_timeout_ms: Some(4200),
+                probe_ready: None,
                 binary: Some(PathBuf::from("/bin/app-alpha")),
                 metadata: serde_json::json!({"thread": "t-alpha"}),
             },
@@ -43,6 +44,7 @@ fn app_runtime_api_lists_and_merges_without_writes() {
                 current_dir: None,
                 mirror_stdio: None,
                 startup_timeout_ms: None,
+                probe_ready: None,
                 binary: None,
                 metadata: serde_json::json!({"resume": true}),
             },
@@ -65,6 +67,7 @@ fn app_runtime_api_lists_and_merges_without_writes() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(3),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let api = AppRuntimeApi::from_config(&manager, &defaults).expect("app runtime api");
@@ -172,6 +175,7 @@ async fn app_runtime_lifecycle_starts_and_stops_without_mutation() {
                 current_dir: None,
                 mirror_stdio: Some(true),
                 startup_timeout_ms: Some(1500),
+                probe_ready: None,
                 binary: None,
                 metadata: metadata.clone(),
             },
@@ -190,6 +194,7 @@ async fn app_runtime_lifecycle_starts_and_stops_without_mutation() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(3),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let before = fs::read_to_string(manager.config_path()).expect("read config before");
@@ -256,3 +261,44 @@ async fn app_runtime_api_not_found_errors() {
         other => panic!("unexpected start result: {other:?}"),
     }
 }
+
+#[tokio::test]
+async fn app_runtime_probe_ready_waits_for_initialize() {
+    let (_ready_dir, ready_path) = write_fake_app_server();
+    let (_stall_dir, stall_path) = write_stalling_mcp_server();
+    let defaults = test_config(ready_path);
+
+    let runtime = |name: &str, binary: PathBuf, env: BTreeMap<String, String>| AppRuntime {
+        name: name.into(),
+        description: None,
+        tags: Vec::new(),
+        metadata: Value::Null,
+        env,
+        code_home: None,
+        current_dir: None,
+        mirror_stdio: None,
+        startup_timeout_ms: Some(300),
+        probe_ready: Some(true),
+        binary: Some(binary),
+    };
+    let stall_env = BTreeMap::from([("STALL_INITIALIZE".to_string(), "1".to_string())]);
+    let api = AppRuntimeApi::new(vec![
+        runtime("ready", defaults.binary.clone(), BTreeMap::new()).into_launcher(&defaults),
+        runtime("stalled", stall_path, stall_env).into_launcher(&defaults),
+    ]);
+    assert!(api.launcher("stalled").expect("launcher").probe_ready);
+
+    let ready = api
+        .launch_and_wait_ready("ready", test_client())
+        .await
+        .expect("ready runtime");
+    ready.stop().await.expect("shutdown runtime");
+
+    match api.start("stalled", test_client()).await {
+        Err(AppRuntimeError::StartupTimeout { name, timeout }) => {
+            assert_eq!(name, "stalled");
+            assert_eq!(timeout, Duration::from_millis(300));
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
@@ -22,6 +22,7 @@ async fn app_runtime_pool_api_reuses_and_restarts_stdio() {
                 current_dir: None,
                 mirror_stdio: Some(true),
                 startup_timeout_ms: Some(2000),
+                probe_ready: None,
                 binary: None,
                 metadata: metadata.clone(),
             },
@@ -40,6 +41,7 @@ async fn app_runtime_pool_api_reuses_and_restarts_stdio() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(3),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let before = fs::read_to_string(manager.config_path()).expect("read config before");
@@ -193,6 +195,7 @@ async fn app_runtime_pool_api_stop_all_shuts_down_runtimes() {
                 current_dir: None,
                 mirror_stdio: Some(false),
                 startup_timeout_ms: Some(2000),
+                probe_ready: None,
                 binary: None,
                 metadata: alpha_metadata.clone(),
             },
@@ -212,6 +215,7 @@ async fn app_runtime_pool_api_stop_all_shuts_down_runtimes() {
                 current_dir: None,
                 mirror_stdio: Some(false),
                 startup_timeout_ms: Some(2000),
+                probe_ready: None,
                 binary: None,
                 metadata: beta_metadata.clone(),
             },
@@ -227,6 +231,7 @@ async fn app_runtime_pool_api_stop_all_shuts_down_runtimes() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(3),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let before = fs::read_to_string(manager.config_path()).expect("read config before");
@@ -76,6 +76,7 @@ fn runtime_api_lists_launchers_without_changing_config() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: true,
         startup_timeout: Duration::from_secs(3),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let api = McpRuntimeApi::from_config(&manager, &defaults).expect("runtime api");
@@ -208,6 +209,7 @@ fn runtime_api_prepare_http_is_non_destructive() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(2),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let api = McpRuntimeApi::from_config(&manager, &defaults).expect("runtime api");
@@ -17,6 +17,7 @@ async fn runtime_manager_starts_and_stops_stdio() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(5),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let runtime = McpRuntimeServer {
@@ -57,6 +58,94 @@ async fn runtime_manager_starts_and_stops_stdio() {
     handle.stop().await.expect("stop server");
 }
 
+#[tokio::test]
+async fn launcher_spawn_initializes_and_streams_notifications() {
+    let (_dir, script) = write_fake_app_server();
+    let launcher =
+        |script: &PathBuf, env: Vec<(OsString, OsString)>, timeout: Duration| McpServerLauncher {
+            name: "local".into(),
+            transport: McpServerLauncherTransport::Stdio(StdioLauncher {
+                command: script.clone(),
+                args: Vec::new(),
+                env,
+                current_dir: None,
+                timeout,
+                mirror_stdio: false,
+            }),
+            description: None,
+            tags: Vec::new(),
+            tools: None,
+        };
+
+    let env = vec![(OsString::from("NOTIFY_ON_INITIALIZE"), OsString::from("1"))];
+    let connection = launcher(&script, env, Duration::from_secs(5))
+        .spawn()
+        .await
+        .expect("spawn launcher");
+    assert_eq!(connection.name(), "local");
+    assert_eq!(connection.initialize_result()["ready"], Value::Bool(true));
+
+    let mut notifications = connection.notifications().await;
+    let result = connection
+        .call(
+            "turn/start",
+            serde_json::json!({"threadId": "t-1", "turnId": "u-1"}),
+        )
+        .await
+        .expect("turn/start");
+    assert_eq!(result["turn_id"], "u-1");
+
+    let ready = time::timeout(Duration::from_secs(2), notifications.recv())
+        .await
+        .expect("notification timeout")
+        .expect("notification");
+    assert_eq!(ready.method, "server/ready");
+    let first = time::timeout(Duration::from_secs(2), notifications.recv())
+        .await
+        .expect("notification timeout")
+        .expect("notification");
+    assert_eq!(first.method, "task/notification");
+    assert_eq!(first.params["type"], "item");
+    connection.shutdown().await.expect("shutdown");
+
+    let (_stall_dir, stalling) = write_stalling_mcp_server();
+    let env = vec![(OsString::from("STALL_INITIALIZE"), OsString::from("1"))];
+    let err = launcher(&stalling, env, Duration::from_millis(200))
+        .spawn()
+        .await
+        .err()
+        .expect("stalled initialize");
+    assert!(matches!(
+        err,
+        McpError::RequestTimeout {
+            method: METHOD_INITIALIZE,
+            ..
+        }
+    ));
+
+    let http = McpServerLauncher {
+        name: "remote".into(),
+        transport: McpServerLauncherTransport::StreamableHttp(StreamableHttpConnector {
+            url: "https://example.test/mcp".into(),
+            headers: BTreeMap::new(),
+            bearer_env_var: None,
+            bearer_token: None,
+            connect_timeout: None,
+            request_timeout: None,
+        }),
+        description: None,
+        tags: Vec::new(),
+        tools: None,
+    };
+    match http.spawn().await {
+        Err(McpError::Transport(message)) => {
+            assert!(message.contains("streamable HTTP transport is not supported"));
+        }
+        Err(other) => panic!("expected transport error, got {other:?}"),
+        Ok(_) => panic!("streamable HTTP launcher should not spawn"),
+    }
+}
+
 #[test]
 fn runtime_manager_propagates_tool_hints_for_http() {
     let env_var = "MCP_HTTP_TOKEN_E8_HINTS";
@@ -66,6 +155,7 @@ fn runtime_manager_propagates_tool_hints_for_http() {
         url: "https://example.test/hints".into(),
         headers: BTreeMap::new(),
         bearer_env_var: Some(env_var.to_string()),
+        bearer_file: None,
         connect_timeout_ms: Some(1200),
         request_timeout_ms: Some(2400),
     };
@@ -82,7 +172,8 @@ fn runtime_manager_propagates_tool_hints_for_http() {
                 disabled: vec!["beta".into()],
             }),
         },
-    );
+    )
+    .expect("runtime server");
 
     let defaults = StdioServerConfig {
         binary: PathBuf::from("codex"),
@@ -92,6 +183,7 @@ fn runtime_manager_propagates_tool_hints_for_http() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(2),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let launcher = runtime.into_launcher(&defaults);
@@ -137,6 +229,7 @@ fn http_connector_retrieval_is_non_destructive() {
                 url: "https://example.test/reuse".into(),
                 headers: BTreeMap::new(),
                 bearer_env_var: Some(env_var.to_string()),
+                bearer_file: None,
                 connect_timeout_ms: Some(1500),
                 request_timeout_ms: Some(3200),
             }),
@@ -147,7 +240,8 @@ fn http_connector_retrieval_is_non_destructive() {
                 disabled: vec![],
             }),
         },
-    );
+    )
+    .expect("runtime server");
 
     let defaults = StdioServerConfig {
         binary: PathBuf::from("codex"),
@@ -157,6 +251,7 @@ fn http_connector_retrieval_is_non_destructive() {
         app_server_analytics_default_enabled: false,
         mirror_stdio: false,
         startup_timeout: Duration::from_secs(2),
+        request_timeouts: McpRequestTimeouts::default(),
     };
 
     let launcher = runtime.into_launcher(&defaults);
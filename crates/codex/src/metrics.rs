@@ -0,0 +1,210 @@
+use std::{
+    fmt,
+    process::ExitStatus,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{CodexError, ExecStreamError, TokenUsage};
+
+/// Observability hooks invoked by [`crate::CodexClient`] around `codex exec` runs.
+///
+/// Register an implementation with [`crate::CodexClientBuilder::metrics`] to feed counters or
+/// histograms without wrapping every call site. Hooks run inline on the calling task, so keep
+/// them cheap and non-blocking.
+pub trait Metrics: Send + Sync {
+    /// Called once the `codex exec` process exits, with the wall time since spawn.
+    fn record_exec(&self, duration: Duration, status: ExitStatus);
+
+    /// Called with the token usage reported by each `turn.completed` event.
+    fn record_tokens(&self, usage: TokenUsage);
+
+    /// Called when an exec run or stream completion fails.
+    fn record_error(&self, category: ErrorCategory);
+}
+
+/// Coarse classification of a failed exec run passed to [`Metrics::record_error`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorCategory {
+    /// The binary could not be spawned or waited on.
+    Spawn,
+    /// The run exceeded [`crate::CodexClientBuilder::timeout`].
+    Timeout,
+    /// No JSONL event arrived within the idle timeout.
+    IdleTimeout,
+    /// The CLI exited with a non-zero status.
+    NonZeroExit,
+    /// Output could not be decoded or parsed.
+    Parse,
+    /// The request was rejected before spawning (empty prompt, missing files, ...).
+    InvalidRequest,
+    /// The binary or platform lacks a required capability.
+    Unsupported,
+    /// The stream was cancelled by the caller.
+    Cancelled,
+    /// Stdio, filesystem, or task failures.
+    Io,
+}
+
+impl From<&CodexError> for ErrorCategory {
+    fn from(error: &CodexError) -> Self {
+        match error {
+            CodexError::Spawn { .. } | CodexError::Wait { .. } => ErrorCategory::Spawn,
+            CodexError::Timeout { .. } => ErrorCategory::Timeout,
+            CodexError::Stream(err) => err.as_ref().into(),
+            CodexError::NonZeroExit { .. } => ErrorCategory::NonZeroExit,
+            CodexError::InvalidUtf8(_)
+            | CodexError::OutputTruncated { .. }
+            | CodexError::JsonParse { .. }
+            | CodexError::ExecPolicyParse { .. }
+            | CodexError::FeatureListParse { .. }
+            | CodexError::ResponsesApiProxyInfoParse { .. }
+            | CodexError::StructuredOutput { .. } => ErrorCategory::Parse,
+            CodexError::EmptyPrompt
+            | CodexError::EmptySandboxCommand
+            | CodexError::EmptyExecPolicyCommand
+            | CodexError::EmptyApiKey
+            | CodexError::EmptyTaskId
+            | CodexError::EmptyEnvId
+            | CodexError::EmptyMcpServerName
+            | CodexError::EmptyMcpCommand
+            | CodexError::EmptyMcpUrl
+            | CodexError::EmptySocketPath
+            | CodexError::PatchNotFound { .. }
+            | CodexError::AttachmentNotFound { .. }
+            | CodexError::SessionNotFound { .. }
+            | CodexError::AttachmentTooLarge { .. }
+            | CodexError::Template(_) => ErrorCategory::InvalidRequest,
+            CodexError::JsonModeUnsupported { .. }
+            | CodexError::UnsupportedCapability { .. }
+            | CodexError::UnsupportedPlatform { .. } => ErrorCategory::Unsupported,
+            CodexError::ResponsesApiProxyInfoRead { .. }
+            | CodexError::TempDir(_)
+            | CodexError::WorkingDirectory { .. }
+            | CodexError::PrepareOutputDirectory { .. }
+            | CodexError::PrepareArtifactDir { .. }
+            | CodexError::PrepareCodexHome { .. }
+            | CodexError::BackupFile { .. }
+            | CodexError::GitInit { .. }
+            | CodexError::StdoutUnavailable
+            | CodexError::StderrUnavailable
+            | CodexError::StdinUnavailable
+            | CodexError::CaptureIo(_)
+            | CodexError::StdinWrite(_)
+            | CodexError::Join(_) => ErrorCategory::Io,
+        }
+    }
+}
+
+impl From<&ExecStreamError> for ErrorCategory {
+    fn from(error: &ExecStreamError) -> Self {
+        match error {
+            ExecStreamError::Codex(err) => err.into(),
+            ExecStreamError::Parse { .. } | ExecStreamError::Normalize { .. } => {
+                ErrorCategory::Parse
+            }
+            ExecStreamError::IdleTimeout { .. } => ErrorCategory::IdleTimeout,
+            ExecStreamError::ChannelClosed => ErrorCategory::Io,
+            ExecStreamError::Cancelled => ErrorCategory::Cancelled,
+        }
+    }
+}
+
+/// [`Metrics`] implementation that discards every sample; the client default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopMetrics;
+
+impl Metrics for NoopMetrics {
+    fn record_exec(&self, _duration: Duration, _status: ExitStatus) {}
+
+    fn record_tokens(&self, _usage: TokenUsage) {}
+
+    fn record_error(&self, _category: ErrorCategory) {}
+}
+
+/// [`Metrics`] implementation that keeps every sample in memory, intended for tests.
+#[derive(Debug, Default)]
+pub struct InMemoryMetrics {
+    samples: Mutex<MetricsSnapshot>,
+}
+
+/// Samples collected by [`InMemoryMetrics`].
+#[derive(Clone, Debug, Default)]
+pub struct MetricsSnapshot {
+    /// Duration and exit status of each completed exec, in completion order.
+    pub execs: Vec<(Duration, ExitStatus)>,
+    /// Sum of all recorded token usage.
+    pub tokens: TokenUsage,
+    /// Categories of recorded failures, in order.
+    pub errors: Vec<ErrorCategory>,
+}
+
+impl InMemoryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the samples recorded so far.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.samples
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    fn with_samples(&self, f: impl FnOnce(&mut MetricsSnapshot)) {
+        f(&mut self.samples.lock().unwrap_or_else(|err| err.into_inner()));
+    }
+}
+
+impl Metrics for InMemoryMetrics {
+    fn record_exec(&self, duration: Duration, status: ExitStatus) {
+        self.with_samples(|samples| samples.execs.push((duration, status)));
+    }
+
+    fn record_tokens(&self, usage: TokenUsage) {
+        self.with_samples(|samples| samples.tokens.add(&usage));
+    }
+
+    fn record_error(&self, category: ErrorCategory) {
+        self.with_samples(|samples| samples.errors.push(category));
+    }
+}
+
+/// [`Metrics`] sink carried by the builder and client; defaults to [`NoopMetrics`].
+#[derive(Clone)]
+pub(crate) struct MetricsRecorder(pub(crate) Arc<dyn Metrics + Send + Sync>);
+
+impl MetricsRecorder {
+    pub(crate) fn record_exec(&self, duration: Duration, status: ExitStatus) {
+        self.0.record_exec(duration, status);
+    }
+
+    pub(crate) fn record_tokens(&self, usage: TokenUsage) {
+        self.0.record_tokens(usage);
+    }
+
+    /// Records the error category of a failed result; successes are ignored.
+    pub(crate) fn record_result<T, E>(&self, result: &Result<T, E>)
+    where
+        for<'a> &'a E: Into<ErrorCategory>,
+    {
+        if let Err(err) = result {
+            self.0.record_error(err.into());
+        }
+    }
+}
+
+impl Default for MetricsRecorder {
+    fn default() -> Self {
+        Self(Arc::new(NoopMetrics))
+    }
+}
+
+impl fmt::Debug for MetricsRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MetricsRecorder")
+            .field(&"<metrics>")
+            .finish()
+    }
+}
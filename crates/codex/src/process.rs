@@ -1,14 +1,16 @@
 use std::{
+    future::Future,
     io::{self, Write},
     path::Path,
     process::ExitStatus,
+    sync::Mutex,
     time::Duration,
 };
 
 use tokio::{
     io::{AsyncRead, AsyncReadExt},
     process::Command,
-    task,
+    task, time,
 };
 
 use crate::CodexError;
@@ -19,38 +21,145 @@ pub(crate) enum ConsoleTarget {
     Stderr,
 }
 
+/// Per-client settings for capturing child stdout/stderr.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CaptureOptions {
+    /// Read buffer size for each capture task.
+    pub(crate) chunk_size: usize,
+    /// Mirror only whole lines while holding [`console_lock`] so concurrent streams do not tear.
+    pub(crate) serialize_console: bool,
+    /// Cap on captured bytes per stream; the pipe keeps draining (and mirroring) past it.
+    pub(crate) max_output_bytes: Option<usize>,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: crate::defaults::DEFAULT_IO_CHUNK_SIZE,
+            serialize_console: false,
+            max_output_bytes: None,
+        }
+    }
+}
+
+/// Bytes captured from one child stream.
+#[derive(Debug, Default)]
+pub(crate) struct CapturedStream {
+    pub(crate) bytes: Vec<u8>,
+    /// True when output beyond [`CaptureOptions::max_output_bytes`] was discarded.
+    pub(crate) truncated: bool,
+}
+
+/// Process-wide lock shared by every serialized console writer.
+pub(crate) fn console_lock() -> &'static Mutex<()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    &LOCK
+}
+
+fn write_console(target: ConsoleTarget, bytes: &[u8], serialize: bool) -> io::Result<()> {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    task::block_in_place(|| {
+        let _lock = serialize.then(|| console_lock().lock().unwrap_or_else(|err| err.into_inner()));
+        match target {
+            ConsoleTarget::Stdout => {
+                let mut out = io::stdout();
+                out.write_all(bytes)?;
+                out.flush()
+            }
+            ConsoleTarget::Stderr => {
+                let mut out = io::stderr();
+                out.write_all(bytes)?;
+                out.flush()
+            }
+        }
+    })
+}
+
 pub(crate) async fn tee_stream<R>(
-    mut reader: R,
+    reader: R,
     target: ConsoleTarget,
     mirror_console: bool,
+    options: CaptureOptions,
 ) -> Result<Vec<u8>, io::Error>
 where
     R: AsyncRead + Unpin,
 {
-    let mut buffer = Vec::new();
-    let mut chunk = [0u8; 4096];
+    tee_stream_captured(reader, target, mirror_console, options)
+        .await
+        .map(|captured| captured.bytes)
+}
+
+/// Like [`tee_stream`], but also reports whether `max_output_bytes` truncated the capture.
+///
+/// A truncated capture ends on a UTF-8 character boundary, so it may be a few bytes shorter
+/// than the limit.
+pub(crate) async fn tee_stream_captured<R>(
+    mut reader: R,
+    target: ConsoleTarget,
+    mirror_console: bool,
+    options: CaptureOptions,
+) -> Result<CapturedStream, io::Error>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut captured = CapturedStream::default();
+    let mut chunk = vec![0u8; options.chunk_size];
+    // Mirrored-but-not-yet-written tail when serializing whole lines.
+    let mut pending = Vec::new();
     loop {
         let n = reader.read(&mut chunk).await?;
         if n == 0 {
             break;
         }
-        if mirror_console {
-            task::block_in_place(|| match target {
-                ConsoleTarget::Stdout => {
-                    let mut out = io::stdout();
-                    out.write_all(&chunk[..n])?;
-                    out.flush()
-                }
-                ConsoleTarget::Stderr => {
-                    let mut out = io::stderr();
-                    out.write_all(&chunk[..n])?;
-                    out.flush()
-                }
-            })?;
+        let data = &chunk[..n];
+        let keep = match options.max_output_bytes {
+            _ if captured.truncated => 0,
+            Some(limit) => limit.saturating_sub(captured.bytes.len()).min(n),
+            None => n,
+        };
+        captured.bytes.extend_from_slice(&data[..keep]);
+        if keep < n && !captured.truncated {
+            captured.truncated = true;
+            trim_partial_char(&mut captured.bytes);
+        }
+        if !mirror_console {
+            continue;
+        }
+        if options.serialize_console {
+            pending.extend_from_slice(data);
+            if let Some(newline) = pending.iter().rposition(|b| *b == b'\n') {
+                write_console(target, &pending[..=newline], true)?;
+                pending.drain(..=newline);
+            }
+        } else {
+            write_console(target, data, false)?;
         }
-        buffer.extend_from_slice(&chunk[..n]);
     }
-    Ok(buffer)
+    if mirror_console && options.serialize_console {
+        write_console(target, &pending, true)?;
+    }
+    Ok(captured)
+}
+
+/// Drops a multi-byte UTF-8 sequence that truncation left incomplete at the end of `bytes`,
+/// so a capture cut mid-character still decodes.
+fn trim_partial_char(bytes: &mut Vec<u8>) {
+    let tail = bytes.len().saturating_sub(3);
+    let Some(offset) = bytes[tail..].iter().rposition(|b| b & 0xC0 != 0x80) else {
+        return;
+    };
+    let start = tail + offset;
+    let width = match bytes[start] {
+        b if b >= 0xF0 => 4,
+        b if b >= 0xE0 => 3,
+        b if b >= 0xC0 => 2,
+        _ => 1,
+    };
+    if bytes.len() - start < width {
+        bytes.truncate(start);
+    }
 }
 
 pub(crate) fn spawn_with_retry(
@@ -80,6 +189,62 @@ pub(crate) fn spawn_with_retry(
     unreachable!("spawn_with_retry should return before exhausting retries")
 }
 
+/// Drives `task` for at most `timeout`, returning `None` once it expires.
+///
+/// With a `grace` period, the child `pid` is sent SIGTERM on expiry and `task` keeps being
+/// polled for up to `grace` so the CLI can flush before the caller drops it (and
+/// `kill_on_drop` force-kills whatever is left). The run still counts as timed out.
+pub(crate) async fn timeout_with_shutdown<F: Future>(
+    timeout: Duration,
+    task: F,
+    pid: Option<u32>,
+    grace: Option<Duration>,
+) -> Option<F::Output> {
+    tokio::pin!(task);
+    if let Ok(output) = time::timeout(timeout, &mut task).await {
+        return Some(output);
+    }
+    if let (Some(pid), Some(grace)) = (pid, grace) {
+        if terminate(pid) {
+            let _ = time::timeout(grace, &mut task).await;
+        }
+    }
+    None
+}
+
+fn terminate(pid: u32) -> bool {
+    send_signal(pid, false)
+}
+
+/// Force-kills `pid` (SIGKILL on unix; a no-op elsewhere, where callers rely on
+/// `kill_on_drop`).
+pub(crate) fn kill_pid(pid: u32) -> bool {
+    send_signal(pid, true)
+}
+
+#[cfg(unix)]
+fn send_signal(pid: u32, force: bool) -> bool {
+    use nix::{
+        sys::signal::{kill, Signal},
+        unistd::Pid,
+    };
+
+    let Ok(pid) = i32::try_from(pid) else {
+        return false;
+    };
+    let signal = if force {
+        Signal::SIGKILL
+    } else {
+        Signal::SIGTERM
+    };
+    kill(Pid::from_raw(pid), signal).is_ok()
+}
+
+#[cfg(not(unix))]
+fn send_signal(_pid: u32, _force: bool) -> bool {
+    false
+}
+
 pub(crate) fn command_output_text(output: &CommandOutput) -> String {
     let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
     let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
@@ -95,8 +260,8 @@ pub(crate) fn command_output_text(output: &CommandOutput) -> String {
 }
 
 pub(crate) fn preferred_output_channel(output: &CommandOutput) -> String {
-    let stderr = String::from_utf8(output.stderr.clone()).unwrap_or_default();
-    let stdout = String::from_utf8(output.stdout.clone()).unwrap_or_default();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
     if stderr.trim().is_empty() {
         stdout
     } else {
@@ -104,8 +269,109 @@ pub(crate) fn preferred_output_channel(output: &CommandOutput) -> String {
     }
 }
 
-pub(crate) struct CommandOutput {
-    pub(crate) status: ExitStatus,
-    pub(crate) stdout: Vec<u8>,
-    pub(crate) stderr: Vec<u8>,
+/// Arguments longer than this render as `<N chars>` in [`redacted_command_line`].
+const COMMAND_ARG_LIMIT: usize = 120;
+
+const SECRET_MARKERS: [&str; 8] = [
+    "key",
+    "token",
+    "secret",
+    "password",
+    "auth",
+    "bearer",
+    "credential",
+    "cookie",
+];
+
+/// Flags whose values are always redacted: `-c key=value` overrides and `--env KEY=VALUE`
+/// pairs can carry credentials under any name.
+const REDACTED_VALUE_FLAGS: [&str; 3] = ["-c", "--config", "--env"];
+
+fn looks_secret(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+/// Keeps the name of a `name=value` pair so the error still says what was overridden.
+fn redacted_value(value: &str) -> String {
+    match value.split_once('=') {
+        Some((name, _)) => format!("{name}=<redacted>"),
+        None => "<redacted>".to_string(),
+    }
+}
+
+/// Replaces the `user:password@` part of every URL in `arg`.
+fn redact_url_userinfo(arg: &str) -> String {
+    let mut out = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(index) = rest.find("://") {
+        let (head, tail) = rest.split_at(index + 3);
+        out.push_str(head);
+        let authority_end = tail
+            .find(|ch: char| matches!(ch, '/' | '?' | '#') || ch.is_whitespace())
+            .unwrap_or(tail.len());
+        match tail[..authority_end].rfind('@') {
+            Some(at) => {
+                out.push_str("<redacted>");
+                out.push_str(&tail[at..authority_end]);
+            }
+            None => out.push_str(&tail[..authority_end]),
+        }
+        rest = &tail[authority_end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Renders `command` as a single line for [`CodexError::NonZeroExit`].
+///
+/// Values of `-c`/`--config` and `--env`, values passed to credential-looking flags
+/// (`--api-key <value>`, `--token=<value>`), credential-looking `key=value` arguments, and URL
+/// userinfo are replaced with `<redacted>`. Long arguments such as inline prompts collapse to
+/// their length so errors stay loggable.
+pub(crate) fn redacted_command_line(command: &Command) -> String {
+    let command = command.as_std();
+    let mut parts = vec![command.get_program().to_string_lossy().into_owned()];
+    let mut redact_next = false;
+    for arg in command.get_args() {
+        let arg = arg.to_string_lossy();
+        if std::mem::take(&mut redact_next) {
+            parts.push(redacted_value(&arg));
+            continue;
+        }
+        let rendered = match arg.split_once('=') {
+            Some((flag, value)) if REDACTED_VALUE_FLAGS.contains(&flag) => {
+                format!("{flag}={}", redacted_value(value))
+            }
+            Some((name, _)) if looks_secret(name) => format!("{name}=<redacted>"),
+            None if REDACTED_VALUE_FLAGS.contains(&arg.as_ref())
+                || (arg.starts_with('-') && looks_secret(&arg)) =>
+            {
+                redact_next = true;
+                arg.into_owned()
+            }
+            _ if arg.chars().count() > COMMAND_ARG_LIMIT => {
+                format!("<{} chars>", arg.chars().count())
+            }
+            _ if arg.is_empty() || arg.contains(char::is_whitespace) => {
+                format!("{:?}", redact_url_userinfo(&arg))
+            }
+            _ => redact_url_userinfo(&arg),
+        };
+        parts.push(rendered);
+    }
+    parts.join(" ")
+}
+
+/// Captured result of a Codex subprocess.
+#[derive(Clone, Debug)]
+pub struct CommandOutput {
+    /// Command line that produced this output, with credentials redacted.
+    pub command: String,
+    /// Exit status reported by the child.
+    pub status: ExitStatus,
+    /// Raw stdout bytes.
+    pub stdout: Vec<u8>,
+    /// Raw stderr bytes.
+    pub stderr: Vec<u8>,
 }
@@ -335,16 +335,30 @@ pub fn find_rollout_files(root: impl AsRef<Path>) -> Vec<PathBuf> {
     out
 }
 
+/// Session id embedded in a `rollout-YYYY-MM-DDThh-mm-ss-<id>.jsonl` file name.
+fn rollout_file_id(name: &str) -> Option<&str> {
+    const TIMESTAMP_LEN: usize = "YYYY-MM-DDThh-mm-ss".len();
+
+    let stem = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
+    let (timestamp, rest) = (stem.get(..TIMESTAMP_LEN)?, stem.get(TIMESTAMP_LEN..)?);
+    if timestamp.as_bytes().get(10) != Some(&b'T') {
+        return None;
+    }
+    rest.strip_prefix('-').filter(|id| !id.is_empty())
+}
+
 pub fn find_rollout_file_by_id(root: impl AsRef<Path>, id: &str) -> Option<PathBuf> {
     let root = root.as_ref();
     let needle = id.strip_prefix("urn:uuid:").unwrap_or(id);
     let files = find_rollout_files(root);
 
     for path in &files {
-        if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
-            if name.contains(needle) {
-                return Some(path.clone());
-            }
+        let file_id = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .and_then(rollout_file_id);
+        if file_id == Some(needle) {
+            return Some(path.clone());
         }
     }
 
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::{CodexClient, CodexError};
+
+/// Prompt text with `{{name}}` placeholders and optional `{{name|default}}` fallbacks.
+///
+/// Placeholder names are trimmed; defaults are inserted verbatim. Rendering fails instead of
+/// leaving unresolved `{{...}}` markers in the prompt sent to the model.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PromptTemplate {
+    source: String,
+}
+
+/// Errors returned by [`PromptTemplate::render`].
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum TemplateError {
+    #[error("template variable `{name}` has no value and no default")]
+    MissingVariable { name: String },
+    #[error("unterminated placeholder starting at byte {offset}")]
+    Unterminated { offset: usize },
+    #[error("empty placeholder name at byte {offset}")]
+    EmptyName { offset: usize },
+}
+
+impl PromptTemplate {
+    /// Wraps template source text.
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+
+    /// Returns the raw template text.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Substitutes every placeholder using `vars`, falling back to inline defaults.
+    pub fn render(&self, vars: &BTreeMap<&str, String>) -> Result<String, TemplateError> {
+        let mut rendered = String::with_capacity(self.source.len());
+        let mut rest = self.source.as_str();
+        let mut offset = 0;
+
+        while let Some(start) = rest.find("{{") {
+            rendered.push_str(&rest[..start]);
+            let placeholder_offset = offset + start;
+            let after_open = &rest[start + 2..];
+            let Some(end) = after_open.find("}}") else {
+                return Err(TemplateError::Unterminated {
+                    offset: placeholder_offset,
+                });
+            };
+
+            let body = &after_open[..end];
+            let (name, default) = match body.split_once('|') {
+                Some((name, default)) => (name.trim(), Some(default)),
+                None => (body.trim(), None),
+            };
+            if name.is_empty() {
+                return Err(TemplateError::EmptyName {
+                    offset: placeholder_offset,
+                });
+            }
+
+            match (vars.get(name), default) {
+                (Some(value), _) => rendered.push_str(value),
+                (None, Some(default)) => rendered.push_str(default),
+                (None, None) => {
+                    return Err(TemplateError::MissingVariable {
+                        name: name.to_string(),
+                    })
+                }
+            }
+
+            let consumed = start + 2 + end + 2;
+            rest = &rest[consumed..];
+            offset += consumed;
+        }
+
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+}
+
+impl From<&str> for PromptTemplate {
+    fn from(source: &str) -> Self {
+        Self::new(source)
+    }
+}
+
+impl From<String> for PromptTemplate {
+    fn from(source: String) -> Self {
+        Self::new(source)
+    }
+}
+
+impl CodexClient {
+    /// Renders `template` with `vars` and sends the result via [`CodexClient::send_prompt`].
+    pub async fn send_prompt_template(
+        &self,
+        template: &PromptTemplate,
+        vars: &BTreeMap<&str, String>,
+    ) -> Result<String, CodexError> {
+        let prompt = template.render(vars)?;
+        self.send_prompt(prompt).await
+    }
+}
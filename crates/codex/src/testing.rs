@@ -0,0 +1,22 @@
+//! Offline helpers for exercising event handling without a Codex binary.
+//!
+//! Enabled by the `testing` feature so downstream crates can pull it in as a dev-dependency
+//! feature (`codex = { version = "...", features = ["testing"] }`).
+
+use std::path::Path;
+
+use futures_core::Stream;
+
+use crate::{ExecStreamError, ThreadEvent};
+
+/// Replays a JSONL fixture as if it were the stdout of `codex exec --json`.
+///
+/// This is [`crate::replay_transcript`] without the header requirement: blank lines are
+/// skipped, each malformed or context-less line yields an [`ExecStreamError::Parse`] or
+/// [`ExecStreamError::Normalize`] item without ending the stream, and open/read failures yield
+/// a final [`crate::CodexError::CaptureIo`].
+pub fn replay_fixture(
+    path: impl AsRef<Path>,
+) -> impl Stream<Item = Result<ThreadEvent, ExecStreamError>> {
+    crate::transcript::replay_jsonl(path.as_ref(), false)
+}
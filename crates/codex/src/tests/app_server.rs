@@ -92,7 +92,7 @@ exit 5
         .unwrap_err();
 
     match err {
-        CodexError::NonZeroExit { status, stderr } => {
+        CodexError::NonZeroExit { status, stderr, .. } => {
             assert_eq!(status.code(), Some(5));
             assert!(stderr.contains("bad format"));
         }
@@ -101,6 +101,86 @@ exit 5
     assert!(out_dir.is_dir());
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn app_server_codegen_validates_target_and_lists_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let script_path = write_fake_codex(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+echo "$*" >> "$(dirname "$0")/calls.log"
+if [[ "$1" != "app-server" ]]; then
+  exit 0
+fi
+if [[ "$2" == "--help" ]]; then
+  echo "Commands:"
+  echo "  generate-json-schema  Generate JSON Schema for the app-server protocol"
+  echo "  help                  Print this message"
+  exit 0
+fi
+out=""
+while [[ $# -gt 0 ]]; do
+  if [[ $1 == "--out" ]]; then
+    out=$2
+  fi
+  shift
+done
+mkdir -p "$out/v2"
+echo "{}" > "$out/codex_app_server_protocol.schemas.json"
+echo "{}" > "$out/v2/ThreadStartParams.json"
+"#,
+    );
+
+    let client = CodexClient::builder()
+        .binary(&script_path)
+        .mirror_stdout(false)
+        .quiet(true)
+        .capability_cache_policy(CapabilityCachePolicy::Bypass)
+        .build();
+
+    let out_dir = dir.path().join("schema");
+    std_fs::create_dir_all(&out_dir).unwrap();
+    std_fs::write(out_dir.join("README.md"), "kept").unwrap();
+    std::os::unix::fs::symlink(&out_dir, out_dir.join("loop")).unwrap();
+    let output = client
+        .app_server_codegen(AppServerCodegenRequest::json_schema(&out_dir))
+        .await
+        .unwrap();
+    assert_eq!(
+        output.files,
+        vec![
+            out_dir.join("codex_app_server_protocol.schemas.json"),
+            out_dir.join("v2/ThreadStartParams.json"),
+        ]
+    );
+
+    let err = client
+        .app_server_codegen(AppServerCodegenRequest::typescript(dir.path().join("ts")))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        CodexError::UnsupportedCapability {
+            feature: "codex app-server generate-ts"
+        }
+    ));
+    assert!(!dir.path().join("ts").exists());
+
+    let offline = CodexClient::builder()
+        .binary(&script_path)
+        .mirror_stdout(false)
+        .quiet(true)
+        .capability_cache_policy(CapabilityCachePolicy::OfflineSnapshotOnly)
+        .build();
+    std_fs::remove_file(dir.path().join("calls.log")).unwrap();
+    offline
+        .app_server_codegen(AppServerCodegenRequest::typescript(dir.path().join("ts")))
+        .await
+        .unwrap();
+    let calls = std_fs::read_to_string(dir.path().join("calls.log")).unwrap();
+    assert!(!calls.contains("--help"), "{calls}");
+}
+
 #[cfg(unix)]
 #[tokio::test]
 async fn responses_api_proxy_maps_flags_and_parses_server_info() {
@@ -263,6 +343,56 @@ done
     assert!(status.success());
 }
 
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn bridge_stdio_to_uds_resolves_socket_and_kills_on_drop() {
+    let dir = tempfile::tempdir().unwrap();
+    let script_path = write_fake_codex(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+echo "ready"
+exec sleep 30
+"#,
+    );
+
+    let client = CodexClient::builder()
+        .binary(&script_path)
+        .mirror_stdout(false)
+        .quiet(true)
+        .working_dir(dir.path())
+        .build();
+
+    let request = StdioToUdsRequest::new("bridge.sock");
+    let mut bridge = match client.bridge_stdio_to_uds(request.clone()) {
+        Ok(bridge) => bridge,
+        Err(CodexError::Spawn { source, .. }) if source.raw_os_error() == Some(26) => {
+            time::sleep(Duration::from_millis(25)).await;
+            client.bridge_stdio_to_uds(request).unwrap()
+        }
+        Err(other) => panic!("unexpected spawn error: {other:?}"),
+    };
+    assert_eq!(bridge.socket_path(), dir.path().join("bridge.sock"));
+
+    let stdout = bridge.child.stdout.take().unwrap();
+    let ready = BufReader::new(stdout).lines().next_line().await.unwrap();
+    assert_eq!(ready.as_deref(), Some("ready"));
+
+    let pid = bridge.child.id().unwrap();
+    let proc_path = PathBuf::from(format!("/proc/{pid}"));
+    assert!(proc_path.exists());
+
+    drop(bridge);
+
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while proc_path.exists() {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "bridge {pid} was not reaped after dropping the handle"
+        );
+        time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
 #[tokio::test]
 async fn stdio_to_uds_rejects_empty_socket_path() {
     let client = CodexClient::builder().build();
@@ -166,6 +166,63 @@ exit 2
     assert_eq!(log.lines().filter(|line| line == &"login").count(), 1);
 }
 
+#[tokio::test]
+async fn login_status_cached_reuses_result_until_logout() {
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("status.log");
+    let script = format!(
+        r#"#!/usr/bin/env bash
+echo "$@" >> "{log}"
+if [[ "$1" == "login" && "$2" == "status" ]]; then
+  [[ -e "{slow}" ]] && sleep 0.5
+  echo "Logged in using ChatGPT"
+  exit 0
+fi
+if [[ "$1" == "logout" ]]; then
+  echo "Successfully logged out"
+  exit 0
+fi
+exit 2
+"#,
+        log = log_path.display(),
+        slow = temp.path().join("slow").display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder().binary(&binary).build();
+    let status_calls = || {
+        std_fs::read_to_string(&log_path)
+            .unwrap()
+            .lines()
+            .filter(|line| *line == "login status")
+            .count()
+    };
+
+    let ttl = Duration::from_secs(60);
+    let first = client.login_status_cached(ttl).await.unwrap();
+    assert_eq!(first, CodexAuthStatus::LoggedIn(CodexAuthMethod::ChatGpt));
+    assert_eq!(client.login_status_cached(ttl).await.unwrap(), first);
+    assert_eq!(status_calls(), 1);
+
+    client.login_status_cached(Duration::ZERO).await.unwrap();
+    assert_eq!(status_calls(), 2);
+
+    client.logout().await.unwrap();
+    client.login_status_cached(ttl).await.unwrap();
+    assert_eq!(status_calls(), 3);
+
+    // A check that was in flight across a logout must not repopulate the cache.
+    client.logout().await.unwrap();
+    std_fs::write(temp.path().join("slow"), "").unwrap();
+    let (in_flight, _) = tokio::join!(client.login_status_cached(ttl), async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        client.logout().await.unwrap();
+    });
+    in_flight.unwrap();
+    std_fs::remove_file(temp.path().join("slow")).unwrap();
+    client.login_status_cached(ttl).await.unwrap();
+    assert_eq!(status_calls(), 5);
+}
+
 #[test]
 fn parses_chatgpt_login() {
     let message = "Logged in using ChatGPT";
@@ -200,3 +257,97 @@ fn parse_login_accepts_unknown_on_success() {
         CodexAuthStatus::LoggedIn(CodexAuthMethod::Unknown { .. })
     ));
 }
+
+#[test]
+fn parse_login_line_extracts_urls_and_codes() {
+    assert_eq!(
+        parse_login_line(
+            "Starting local login server on http://localhost:1455. Navigate to https://auth.openai.com/oauth/authorize?client_id=abc to authenticate."
+        ),
+        vec![LoginEvent::VerificationUrl(
+            "https://auth.openai.com/oauth/authorize?client_id=abc".to_string()
+        )]
+    );
+    assert_eq!(
+        parse_login_line("Enter this one-time code: ABCD-1234"),
+        vec![LoginEvent::Code("ABCD-1234".to_string())]
+    );
+    assert!(parse_login_line("Successfully logged in").is_empty());
+}
+
+#[tokio::test]
+async fn login_interactive_streams_url_code_and_completion() {
+    let _guard = env_guard_async().await;
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/usr/bin/env bash
+if [[ "$1" == "login" ]]; then
+  echo "Open https://auth.openai.com/codex/device in your browser"
+  echo $'\e[1mEnter this one-time code:\e[0m WXYZ-9876' >&2
+  echo "Successfully logged in"
+  exit 0
+fi
+exit 2
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder().binary(&binary).build();
+
+    let mut session = client.login_interactive().unwrap();
+    let mut events = Vec::new();
+    while let Some(event) = session.next_event().await {
+        events.push(event);
+    }
+
+    assert!(events.contains(&LoginEvent::VerificationUrl(
+        "https://auth.openai.com/codex/device".to_string()
+    )));
+    assert!(events.contains(&LoginEvent::Code("WXYZ-9876".to_string())));
+    assert_eq!(events.last(), Some(&LoginEvent::Completed));
+}
+
+#[tokio::test]
+async fn login_interactive_cancel_kills_helper() {
+    let _guard = env_guard_async().await;
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/usr/bin/env bash
+echo "Open https://auth.openai.com/codex/device"
+exec sleep 30
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder().binary(&binary).build();
+
+    let mut session = client.login_interactive().unwrap();
+    assert!(matches!(
+        session.next_event().await,
+        Some(LoginEvent::VerificationUrl(_))
+    ));
+    session.cancel();
+
+    let terminal = tokio::time::timeout(Duration::from_secs(5), session.next_event())
+        .await
+        .expect("login session should finish after cancel");
+    assert_eq!(
+        terminal,
+        Some(LoginEvent::Failed("login cancelled".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn login_interactive_reports_failure_from_stderr() {
+    let _guard = env_guard_async().await;
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/usr/bin/env bash
+echo "login server failed to bind" >&2
+exit 1
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder().binary(&binary).build();
+
+    let mut session = client.login_interactive().unwrap();
+    assert_eq!(
+        session.next_event().await,
+        Some(LoginEvent::Failed(
+            "login server failed to bind".to_string()
+        ))
+    );
+    assert_eq!(session.next_event().await, None);
+}
@@ -43,6 +43,32 @@ fn builder_collects_images() {
     assert_eq!(client.images[1], PathBuf::from("bar.jpg"));
 }
 
+#[test]
+fn estimate_prompt_counts_chars_and_images() {
+    let plain = CodexClient::builder().build();
+    assert_eq!(plain.estimate_prompt("").approx_input_tokens, 0);
+    assert_eq!(plain.estimate_prompt("abcdefgh").approx_input_tokens, 2);
+    assert_eq!(plain.estimate_prompt("héllo").approx_input_tokens, 2);
+
+    let with_images = CodexClient::builder()
+        .image("foo.png")
+        .image("bar.jpg")
+        .build();
+    let estimate = with_images.estimate_prompt("abcd");
+    assert_eq!(
+        estimate.approx_input_tokens,
+        1 + 2 * PromptEstimate::TOKENS_PER_IMAGE
+    );
+
+    let estimate = plain.estimate_prompt(&"x".repeat(400));
+    let actual = TokenUsage {
+        input_tokens: 250,
+        ..TokenUsage::default()
+    };
+    assert_eq!(estimate.calibration(&actual), Some(2.5));
+    assert_eq!(PromptEstimate::default().calibration(&actual), None);
+}
+
 #[test]
 fn builder_sets_json_flag() {
     let client = CodexClient::builder().json(true).build();
@@ -88,6 +114,60 @@ fn builder_uses_env_binary_when_set() {
     }
 }
 
+#[test]
+fn builder_from_env_seeds_documented_vars() {
+    let vars: HashMap<&str, &str> = HashMap::from([
+        ("CODEX_MODEL", "gpt-5-codex"),
+        ("CODEX_PROFILE", " ci "),
+        ("CODEX_TIMEOUT_SECS", "45"),
+        ("CODEX_IDLE_TIMEOUT_SECS", "0"),
+        ("CODEX_WORKING_DIR", "/srv/repo"),
+        ("CODEX_APPROVAL_POLICY", "on-request"),
+        ("CODEX_SANDBOX_MODE", ""),
+    ]);
+    let builder =
+        CodexClientBuilder::from_env_with(|name| vars.get(name).map(OsString::from)).unwrap();
+    assert_eq!(builder.model.as_deref(), Some("gpt-5-codex"));
+    assert_eq!(builder.cli_overrides.profile.as_deref(), Some("ci"));
+    assert_eq!(builder.timeout, Duration::from_secs(45));
+    assert_eq!(builder.idle_timeout, Some(Duration::ZERO));
+    assert_eq!(builder.working_dir, Some(PathBuf::from("/srv/repo")));
+    assert_eq!(
+        builder.cli_overrides.approval_policy,
+        Some(ApprovalPolicy::OnRequest)
+    );
+    assert!(builder.cli_overrides.sandbox_mode.is_none());
+
+    let defaults = CodexClientBuilder::from_env_with(|_| None).unwrap();
+    assert!(defaults.model.is_none());
+    assert_eq!(defaults.timeout, DEFAULT_TIMEOUT);
+
+    let err = CodexClientBuilder::from_env_with(|name| {
+        (name == "CODEX_TIMEOUT_SECS").then(|| OsString::from("soon"))
+    })
+    .unwrap_err();
+    assert_eq!(
+        err,
+        BuilderEnvError::InvalidValue {
+            var: "CODEX_TIMEOUT_SECS",
+            value: "soon".to_string(),
+            expected: "a non-negative integer number of seconds",
+        }
+    );
+
+    let err = CodexClientBuilder::from_env_with(|name| {
+        (name == "CODEX_SANDBOX_MODE").then(|| OsString::from("yolo"))
+    })
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        BuilderEnvError::InvalidValue {
+            var: "CODEX_SANDBOX_MODE",
+            ..
+        }
+    ));
+}
+
 #[test]
 fn default_binary_falls_back_when_env_missing() {
     let _guard = env_guard();
@@ -274,6 +354,7 @@ fi
     assert!(apply.status.success());
     assert_eq!(apply.stdout.trim(), "applied");
     assert_eq!(apply.stderr.trim(), "apply-stderr");
+    assert!(!apply.has_conflicts());
 
     let diff = client.diff().await.unwrap();
     assert!(!diff.status.success());
@@ -282,6 +363,326 @@ fi
     assert_eq!(diff.stderr.trim(), "diff-stderr");
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn apply_patch_applies_patch_file_with_git() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("foo.txt"), "old\n").unwrap();
+    let patch = dir.path().join("change.patch");
+    std::fs::write(
+        &patch,
+        "--- a/foo.txt\n+++ b/foo.txt\n@@ -1 +1 @@\n-old\n+new\n",
+    )
+    .unwrap();
+
+    let client = CodexClient::builder()
+        .binary(dir.path().join("codex-not-spawned"))
+        .working_dir(dir.path())
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let artifacts = client.apply_patch(&patch).await.unwrap();
+    assert!(artifacts.status.success(), "{}", artifacts.stderr);
+    assert!(artifacts.command.starts_with("git apply"));
+    assert!(artifacts.conflicts.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("foo.txt")).unwrap(),
+        "new\n"
+    );
+
+    let stale = client.apply_patch(&patch).await.unwrap();
+    assert!(!stale.status.success());
+    assert_eq!(stale.conflicts.len(), 1);
+    assert_eq!(stale.conflicts[0].file, "foo.txt");
+
+    let missing = dir.path().join("missing.patch");
+    match client.apply_patch(&missing).await {
+        Err(CodexError::PatchNotFound { path }) => assert_eq!(path, missing),
+        other => panic!("expected PatchNotFound, got {other:?}"),
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn apply_task_parses_conflicts_from_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+echo "Applied patch to 'src/main.rs' with conflicts."
+echo "error: patch failed: src/lib.rs:12" >&2
+echo "error: src/lib.rs: patch does not apply" >&2
+echo "error: docs/new.md: already exists in working directory" >&2
+echo "error: could not apply patch: see above" >&2
+echo "fatal: something unexpected" >&2
+exit 1
+"#,
+    );
+
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let artifacts = client.apply_task("task-1").await.unwrap();
+    assert_eq!(artifacts.status.code(), Some(1));
+    assert!(artifacts.has_conflicts());
+    assert_eq!(
+        artifacts.conflicts,
+        vec![
+            ApplyConflict {
+                file: "src/lib.rs".to_string(),
+                reason: "patch failed at line 12".to_string(),
+            },
+            ApplyConflict {
+                file: "docs/new.md".to_string(),
+                reason: "already exists in working directory".to_string(),
+            },
+            ApplyConflict {
+                file: "src/main.rs".to_string(),
+                reason: "applied with conflicts".to_string(),
+            },
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn diff_events_collects_file_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    };
+    git(&["init", "-q"]);
+    std_fs::create_dir_all(dir.path().join("src")).unwrap();
+    std_fs::write(dir.path().join("src/lib.rs"), "fn a() {}\n").unwrap();
+    std_fs::write(dir.path().join("README.md"), "readme\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "init"]);
+
+    std_fs::write(dir.path().join("src/lib.rs"), "fn a() {}\nfn x() {}\n").unwrap();
+    std_fs::remove_file(dir.path().join("README.md")).unwrap();
+    std_fs::write(dir.path().join("src/new.rs"), "fn new() {}\n").unwrap();
+    git(&["add", "src/new.rs"]);
+    std_fs::write(dir.path().join("untracked.txt"), "ignored\n").unwrap();
+
+    let client = CodexClient::builder()
+        .binary(dir.path().join("codex-not-spawned"))
+        .working_dir(dir.path())
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let changes = client.diff_events().await.unwrap();
+    let paths: Vec<_> = changes.iter().map(|change| change.path.clone()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("README.md"),
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("src/new.rs"),
+        ]
+    );
+    assert!(changes
+        .iter()
+        .all(|change| matches!(change.change, Some(FileChangeKind::Diff))));
+    let lib = changes[1].diff.as_deref().unwrap();
+    assert!(lib.starts_with("diff --git a/src/lib.rs b/src/lib.rs"));
+    assert!(lib.contains("+fn x() {}"));
+    assert!(!lib.contains("README.md"));
+    assert!(changes[0].diff.as_deref().unwrap().contains("-readme"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn apply_with_backup_copies_changed_files_before_applying() {
+    let _guard = env_guard_async().await;
+    let original_task = env::var_os("CODEX_TASK_ID");
+    let dir = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+echo "$*" >> "$(dirname "$0")/calls.log"
+if [[ "$1 $2 $3" == "cloud diff task-escape" ]]; then
+  printf -- '--- a/../outside.txt\n+++ b/../outside.txt\n@@ -1 +1 @@\n-a\n+b\n'
+  exit 0
+fi
+if [[ "$1 $2" == "cloud diff" ]]; then
+cat <<'EOF'
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-original
++patched
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1 @@
++fn new() {}
+EOF
+  exit 0
+fi
+echo "patched" > src/lib.rs
+echo "error: src/new.rs: already exists in working directory" >&2
+exit 1
+"#,
+    );
+    let workdir = dir.path().join("repo");
+    std_fs::create_dir_all(workdir.join("src")).unwrap();
+    std_fs::write(workdir.join("src/lib.rs"), "original\n").unwrap();
+    std_fs::write(dir.path().join("outside.txt"), "a\n").unwrap();
+    let backup_dir = dir.path().join("backup");
+
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .working_dir(&workdir)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    env::remove_var("CODEX_TASK_ID");
+    assert!(matches!(
+        client.apply_with_backup(&backup_dir).await,
+        Err(CodexError::EmptyTaskId)
+    ));
+
+    env::set_var("CODEX_TASK_ID", "task-1");
+    let report = client.apply_with_backup(&backup_dir).await.unwrap();
+    assert_eq!(report.backed_up, vec![backup_dir.join("src/lib.rs")]);
+    assert_eq!(
+        std_fs::read_to_string(backup_dir.join("src/lib.rs")).unwrap(),
+        "original\n"
+    );
+    assert_eq!(
+        std_fs::read_to_string(workdir.join("src/lib.rs")).unwrap(),
+        "patched\n"
+    );
+    assert_eq!(report.artifacts.status.code(), Some(1));
+    assert!(report.artifacts.has_conflicts());
+
+    env::set_var("CODEX_TASK_ID", "task-escape");
+    match client.apply_with_backup(&backup_dir).await {
+        Err(CodexError::BackupFile { path, .. }) => {
+            assert_eq!(path, PathBuf::from("../outside.txt"))
+        }
+        other => panic!("expected BackupFile, got {other:?}"),
+    }
+    assert!(!backup_dir.join("outside.txt").exists());
+
+    match original_task {
+        Some(value) => env::set_var("CODEX_TASK_ID", value),
+        None => env::remove_var("CODEX_TASK_ID"),
+    }
+
+    let calls = std_fs::read_to_string(dir.path().join("calls.log")).unwrap();
+    assert_eq!(
+        calls.lines().collect::<Vec<_>>(),
+        vec![
+            "cloud diff task-1",
+            "apply task-1",
+            "cloud diff task-escape"
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn change_summary_counts_unified_and_jsonl_diffs() {
+    let dir = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+cat <<'EOF'
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+-fn old() {}
++fn new() {}
++fn extra() {}
+ keep();
+diff --git a/logo.png b/logo.png
+Binary files a/logo.png and b/logo.png differ
+--- a/README.md
++++ b/README.md
+@@ -1 +0,0 @@
+--- not a header
+EOF
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let artifacts = client.cloud_diff_task("task-1").await.unwrap();
+    assert_eq!(
+        artifacts.change_summary(),
+        DiffSummary {
+            files_changed: 3,
+            insertions: 2,
+            deletions: 2,
+        }
+    );
+
+    let jsonl = [
+        r#"{"type":"thread.started","thread_id":"t-1"}"#,
+        r#"{"type":"turn.started","thread_id":"t-1","turn_id":"u-1"}"#,
+        r#"{"type":"item.started","thread_id":"t-1","turn_id":"u-1","item_id":"f-1","item_type":"file_change","content":{"path":"src/lib.rs"}}"#,
+        r#"{"type":"item.completed","thread_id":"t-1","turn_id":"u-1","item_id":"f-1","item_type":"file_change","content":{"path":"src/lib.rs","diff":"--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1,2 @@\n-a\n+b\n+c\n"}}"#,
+        r#"{"type":"item.completed","thread_id":"t-1","turn_id":"u-1","item_id":"f-2","item_type":"file_change","content":{"path":"docs/new.md"}}"#,
+        r#"{"type":"turn.completed","thread_id":"t-1","turn_id":"u-1"}"#,
+    ]
+    .join("\n");
+    let json_artifacts = ApplyDiffArtifacts {
+        stdout: jsonl,
+        ..artifacts
+    };
+    assert_eq!(
+        json_artifacts.change_summary(),
+        DiffSummary {
+            files_changed: 2,
+            insertions: 2,
+            deletions: 1,
+        }
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn diff_events_maps_git_failures_to_non_zero_exit() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = CodexClient::builder()
+        .binary(dir.path().join("codex-not-spawned"))
+        .working_dir(dir.path())
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    match client.diff_events().await {
+        Err(CodexError::NonZeroExit {
+            command, stderr, ..
+        }) => {
+            assert!(command.starts_with("git diff"));
+            assert!(!stderr.is_empty());
+        }
+        other => panic!("expected NonZeroExit, got {other:?}"),
+    }
+}
+
 #[cfg(unix)]
 #[tokio::test]
 async fn apply_respects_rust_log_default() {
@@ -396,6 +797,43 @@ fn codex_home_layout_respects_materialization_flag() {
     assert!(layout.logs_dir().is_dir());
 }
 
+#[cfg(unix)]
+#[test]
+fn home_permission_audit_flags_and_tightens_shared_secrets() {
+    let temp = tempfile::tempdir().unwrap();
+    let layout = CodexHomeLayout::new(temp.path());
+    std_fs::write(layout.auth_path(), "auth").unwrap();
+    std_fs::set_permissions(layout.auth_path(), std_fs::Permissions::from_mode(0o644)).unwrap();
+    std_fs::write(layout.credentials_path(), "creds").unwrap();
+    std_fs::set_permissions(
+        layout.credentials_path(),
+        std_fs::Permissions::from_mode(0o600),
+    )
+    .unwrap();
+
+    assert_eq!(
+        layout.audit_permissions(),
+        vec![PermissionFinding {
+            path: layout.auth_path(),
+            mode: 0o644,
+            group_readable: true,
+            world_readable: true,
+        }]
+    );
+
+    assert_eq!(
+        layout.tighten_permissions().unwrap(),
+        vec![layout.auth_path()]
+    );
+    let mode = std_fs::metadata(layout.auth_path())
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+    assert!(layout.audit_permissions().is_empty());
+    assert!(layout.tighten_permissions().unwrap().is_empty());
+}
+
 #[test]
 fn seed_auth_copies_files_and_creates_targets() {
     let temp = tempfile::tempdir().unwrap();
@@ -478,3 +916,383 @@ fn codex_client_returns_configured_home_layout() {
     let client_without_home = CodexClient::builder().build();
     assert!(client_without_home.codex_home_layout().is_none());
 }
+
+#[tokio::test]
+async fn temp_dir_base_hosts_ephemeral_working_dirs() {
+    let temp = tempfile::tempdir().unwrap();
+    let base = temp.path().join("scratch");
+    std_fs::create_dir_all(&base).unwrap();
+    let binary = write_fake_codex(temp.path(), "#!/bin/bash\ncat >/dev/null\npwd\n");
+
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .temp_dir_base(&base)
+        .build();
+    let cwd = client.send_prompt("where am i").await.unwrap();
+    let cwd = PathBuf::from(cwd.trim());
+    assert!(
+        cwd.starts_with(&base) || cwd.starts_with(std_fs::canonicalize(&base).unwrap()),
+        "{cwd:?} not under {base:?}"
+    );
+
+    let missing = CodexClient::builder()
+        .binary(&binary)
+        .temp_dir_base(temp.path().join("missing"))
+        .build();
+    match missing.send_prompt("where am i").await {
+        Err(CodexError::TempDir(_)) => {}
+        other => panic!("expected TempDir error, got {other:?}"),
+    }
+}
+
+#[test]
+fn with_helpers_override_a_single_field() {
+    let client = CodexClient::builder()
+        .binary("/opt/codex")
+        .model("gpt-5")
+        .timeout(Duration::from_secs(30))
+        .capability_cache_policy(CapabilityCachePolicy::Bypass)
+        .build();
+
+    let retargeted = client.with_model("gpt-5-codex");
+    assert_eq!(retargeted.model.as_deref(), Some("gpt-5-codex"));
+    assert_eq!(retargeted.timeout, Duration::from_secs(30));
+    assert_eq!(
+        retargeted.command_env.binary_path(),
+        Path::new("/opt/codex")
+    );
+    assert_eq!(
+        retargeted.capability_cache_policy,
+        CapabilityCachePolicy::Bypass
+    );
+    assert!(client.with_model("  ").model.is_none());
+
+    let slower = client.with_timeout(Duration::from_secs(90));
+    assert_eq!(slower.timeout, Duration::from_secs(90));
+    assert_eq!(slower.model.as_deref(), Some("gpt-5"));
+
+    let pinned = client.with_working_dir("/work");
+    assert_eq!(pinned.working_dir.as_deref(), Some(Path::new("/work")));
+    assert!(client.working_dir.is_none());
+}
+
+#[test]
+fn builder_clamps_io_chunk_size() {
+    assert_eq!(CodexClient::builder().io_chunk_size, DEFAULT_IO_CHUNK_SIZE);
+    assert_eq!(CodexClient::builder().io_chunk_size(16).io_chunk_size, 1024);
+    let client = CodexClient::builder().io_chunk_size(64 * 1024).build();
+    assert_eq!(client.io_chunk_size, 64 * 1024);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn large_stdout_is_captured_intact_with_large_chunks() {
+    let _guard = env_guard_async().await;
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/usr/bin/env bash
+cat >/dev/null
+yes 0123456789abcde | head -c 1048576
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .io_chunk_size(64 * 1024)
+        .build();
+
+    let output = client.send_prompt("big").await.unwrap();
+    let expected = "0123456789abcde\n".repeat(65536);
+    assert_eq!(expected.len(), 1024 * 1024);
+    assert_eq!(output, expected.trim());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn max_output_bytes_caps_capture_but_drains_pipe() {
+    let dir = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+head -c 10485760 /dev/zero | tr '\0' 'a'
+if [[ "$1" == "exec" ]]; then
+  head -c 10485760 /dev/zero | tr '\0' 'e' >&2
+  exit 2
+fi
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(30))
+        .mirror_stdout(false)
+        .quiet(true)
+        .max_output_bytes(Some(1024 * 1024))
+        .build();
+
+    let artifacts = client.apply().await.unwrap();
+    assert!(artifacts.status.success());
+    assert!(artifacts.truncated);
+    assert_eq!(artifacts.stdout.len(), 1024 * 1024);
+
+    match client.send_prompt("hi").await {
+        Err(CodexError::NonZeroExit {
+            stderr, truncated, ..
+        }) => {
+            assert!(truncated);
+            assert_eq!(stderr.len(), 1024 * 1024);
+        }
+        other => panic!("expected NonZeroExit, got {other:?}"),
+    }
+
+    let uncapped = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(30))
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let artifacts = uncapped.apply().await.unwrap();
+    assert!(!artifacts.truncated);
+    assert_eq!(artifacts.stdout.len(), 10 * 1024 * 1024);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn max_output_bytes_truncates_on_char_boundaries() {
+    let dir = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+printf 'ééééé'
+if [[ "$1" == "exec" ]]; then
+  exit 0
+fi
+printf 'ééééé' >&2
+exit 1
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .max_output_bytes(Some(3))
+        .build();
+
+    let artifacts = client.apply().await.unwrap();
+    assert_eq!(artifacts.status.code(), Some(1));
+    assert!(artifacts.truncated);
+    assert_eq!(artifacts.stdout, "é");
+    assert_eq!(artifacts.stderr, "é");
+
+    match client.send_prompt("hi").await {
+        Err(CodexError::OutputTruncated { command, limit }) => {
+            assert!(command.contains("exec"));
+            assert_eq!(limit, 3);
+        }
+        other => panic!("expected OutputTruncated, got {other:?}"),
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn max_concurrent_limits_running_subprocesses() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("runs.log");
+    let binary = write_fake_codex(
+        dir.path(),
+        &format!(
+            r#"#!/usr/bin/env bash
+echo start >> "{log}"
+sleep 0.3
+echo end >> "{log}"
+"#,
+            log = log_path.display()
+        ),
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .max_concurrent(2)
+        .build();
+
+    let shared = client.clone();
+    let (a, b, c, d) = tokio::join!(
+        client.send_prompt("one"),
+        shared.send_prompt("two"),
+        client.apply(),
+        shared.diff(),
+    );
+    a.unwrap();
+    b.unwrap();
+    c.unwrap();
+    d.unwrap();
+
+    let mut running = 0;
+    let mut peak = 0;
+    for line in std_fs::read_to_string(&log_path).unwrap().lines() {
+        running += if line == "start" { 1 } else { -1 };
+        peak = peak.max(running);
+    }
+    assert_eq!(running, 0);
+    assert_eq!(peak, 2);
+}
+
+#[test]
+fn builder_sets_serialize_console_output() {
+    assert!(!CodexClient::builder().serialize_console_output);
+    let client = CodexClient::builder()
+        .serialize_console_output(true)
+        .io_chunk_size(8192)
+        .build();
+    let options = client.capture_options();
+    assert!(options.serialize_console);
+    assert_eq!(options.chunk_size, 8192);
+}
+
+#[test]
+fn try_build_rejects_conflicting_configuration() {
+    assert_eq!(
+        CodexClient::builder().binary("").try_build().unwrap_err(),
+        BuilderError::EmptyBinary
+    );
+    assert_eq!(
+        CodexClient::builder()
+            .full_auto(true)
+            .sandbox_mode(SandboxMode::ReadOnly)
+            .try_build()
+            .unwrap_err(),
+        BuilderError::FullAutoConflict {
+            field: "sandbox_mode"
+        }
+    );
+    assert_eq!(
+        CodexClient::builder()
+            .approval_policy(ApprovalPolicy::Never)
+            .full_auto(true)
+            .try_build()
+            .unwrap_err(),
+        BuilderError::FullAutoConflict {
+            field: "approval_policy"
+        }
+    );
+    assert_eq!(
+        CodexClient::builder().cd("nested").try_build().unwrap_err(),
+        BuilderError::RelativeCdWithoutWorkingDir {
+            cd: PathBuf::from("nested")
+        }
+    );
+    assert_eq!(
+        CodexClient::builder()
+            .enable_feature("alpha")
+            .disable_feature("alpha")
+            .try_build()
+            .unwrap_err(),
+        BuilderError::ConflictingFeatureToggle {
+            name: "alpha".to_string()
+        }
+    );
+}
+
+#[test]
+fn try_build_validates_model_when_enabled() {
+    CodexClient::builder()
+        .model("gpt5-codex")
+        .try_build()
+        .unwrap();
+    for model in ["gpt-5-codex", "gpt-5.1-codex-max", "o3", "o4-mini"] {
+        CodexClient::builder()
+            .model(model)
+            .validate_model(true)
+            .try_build()
+            .unwrap();
+    }
+
+    let err = CodexClient::builder()
+        .model("gpt5-codex")
+        .validate_model(true)
+        .try_build()
+        .unwrap_err();
+    match &err {
+        BuilderError::UnknownModel { model, suggestions } => {
+            assert_eq!(model, "gpt5-codex");
+            assert_eq!(suggestions.first().map(String::as_str), Some("gpt-5-codex"));
+        }
+        other => panic!("expected UnknownModel, got {other:?}"),
+    }
+    assert!(err.to_string().contains("did you mean gpt-5-codex"));
+
+    assert_eq!(
+        CodexClient::builder()
+            .model("my-private-model-with-a-long-name")
+            .validate_model(true)
+            .try_build()
+            .unwrap_err(),
+        BuilderError::UnknownModel {
+            model: "my-private-model-with-a-long-name".to_string(),
+            suggestions: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn try_build_accepts_compatible_configuration() {
+    let temp = tempfile::tempdir().unwrap();
+    CodexClient::builder()
+        .dangerously_bypass_approvals_and_sandbox(true)
+        .sandbox_mode(SandboxMode::WorkspaceWrite)
+        .try_build()
+        .unwrap();
+    CodexClient::builder()
+        .working_dir(temp.path())
+        .cd("nested")
+        .full_auto(true)
+        .enable_feature("alpha")
+        .disable_feature("beta")
+        .try_build()
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "invalid CodexClientBuilder configuration")]
+fn build_panics_on_invalid_configuration() {
+    let _ = CodexClient::builder().binary("").build();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn serialized_tee_waits_for_console_lock_and_keeps_partial_lines() {
+    let (mut writer, reader) = tokio::io::duplex(64);
+    let options = CaptureOptions {
+        chunk_size: 1024,
+        serialize_console: true,
+        ..CaptureOptions::default()
+    };
+    let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    let holder = std::thread::spawn(move || {
+        let _held = crate::process::console_lock().lock().unwrap();
+        locked_tx.send(()).unwrap();
+        let _ = release_rx.recv();
+    });
+    locked_rx.recv().unwrap();
+    let tee = tokio::spawn(tee_stream(reader, ConsoleTarget::Stderr, true, options));
+
+    writer.write_all(b"first line\npartial").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    writer.write_all(b" tail").await.unwrap();
+    writer.shutdown().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(
+        !tee.is_finished(),
+        "whole lines must wait for the console lock"
+    );
+
+    release_tx.send(()).unwrap();
+    holder.join().unwrap();
+    let captured = tokio::time::timeout(Duration::from_secs(5), tee)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(captured, b"first line\npartial tail");
+}
@@ -48,6 +48,7 @@ fn sample_capabilities_snapshot() -> CodexCapabilities {
             canonical_path: Some(PathBuf::from("/tmp/codex")),
             modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(5)),
             len: Some(1234),
+            sha256: None,
         }),
         version: Some(CodexVersionInfo {
             raw: "codex 3.4.5-beta (commit cafe)".to_string(),
@@ -60,6 +61,9 @@ fn sample_capabilities_snapshot() -> CodexCapabilities {
             supports_output_schema: true,
             supports_add_dir: false,
             supports_mcp_login: true,
+            supports_json_output: false,
+            supports_add_dir_read_only: false,
+            app_server_subcommands: None,
         },
         probe_plan: CapabilityProbePlan {
             steps: vec![
@@ -81,6 +85,8 @@ fn sample_capability_overrides() -> CapabilityOverrides {
             supports_output_schema: Some(true),
             supports_add_dir: Some(true),
             supports_mcp_login: None,
+            supports_json_output: None,
+            supports_add_dir_read_only: None,
         },
     }
 }
@@ -44,6 +44,76 @@ fi
     assert!(logged.contains("--output-schema"));
 }
 
+#[tokio::test]
+async fn exec_add_dir_read_only_fails_closed() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("exec.log");
+    let script = format!(
+        r#"#!/bin/bash
+if [[ "$1" == "exec" ]]; then
+  echo "$@" >> "{log}"
+  echo "ok"
+fi
+"#,
+        log = log_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let builder = || {
+        CodexClient::builder()
+            .binary(&binary)
+            .timeout(Duration::from_secs(5))
+            .add_dir("src")
+            .capability_cache_policy(CapabilityCachePolicy::Bypass)
+            .capability_feature_overrides(CapabilityFeatureOverrides {
+                supports_add_dir: Some(true),
+                ..Default::default()
+            })
+            .quiet(true)
+            .mirror_stdout(false)
+    };
+
+    builder().build().send_prompt("hello").await.unwrap();
+    let logged = std_fs::read_to_string(&log_path).unwrap();
+    assert!(logged.contains("--add-dir src"));
+
+    std_fs::remove_file(&log_path).unwrap();
+    let read_only = builder()
+        .add_dir_with_access("docs", DirAccess::ReadOnly)
+        .build();
+    match read_only.send_prompt("hello").await {
+        Err(CodexError::UnsupportedCapability { feature }) => {
+            assert_eq!(feature, "read-only --add-dir")
+        }
+        other => panic!("expected UnsupportedCapability, got {other:?}"),
+    }
+    assert!(matches!(
+        read_only
+            .stream_exec(ExecStreamRequest::new("hello"))
+            .await
+            .err(),
+        Some(ExecStreamError::Codex(
+            CodexError::UnsupportedCapability { .. }
+        ))
+    ));
+    let logged = std_fs::read_to_string(&log_path).unwrap_or_default();
+    assert!(!logged.contains("docs"));
+
+    let confirmed = builder()
+        .add_dir_with_access("docs", DirAccess::ReadOnly)
+        .capability_feature_overrides(CapabilityFeatureOverrides {
+            supports_add_dir: Some(true),
+            supports_add_dir_read_only: Some(true),
+            ..Default::default()
+        })
+        .build();
+    confirmed.send_prompt("hello").await.unwrap();
+    let logged = std_fs::read_to_string(&log_path).unwrap();
+    assert!(logged.contains("--add-dir src --add-dir-read-only docs"));
+}
+
 #[tokio::test]
 async fn exec_skips_guarded_flags_when_unknown() {
     let _guard = env_guard_async().await;
@@ -72,6 +142,8 @@ fi
         log = log_path.display()
     );
     let binary = write_fake_codex(temp.path(), &script);
+    let skipped = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&skipped);
     let client = CodexClient::builder()
         .binary(&binary)
         .timeout(Duration::from_secs(5))
@@ -79,6 +151,7 @@ fi
         .output_schema(true)
         .quiet(true)
         .mirror_stdout(false)
+        .on_guard_skip(move |guard| sink.lock().unwrap().push(guard.feature))
         .build();
 
     let response = client.send_prompt("hello").await.unwrap();
@@ -87,6 +160,10 @@ fi
     let logged = std_fs::read_to_string(&log_path).unwrap();
     assert!(!logged.contains("--add-dir"));
     assert!(!logged.contains("--output-schema"));
+    assert_eq!(
+        *skipped.lock().unwrap(),
+        vec![CapabilityFeature::OutputSchema, CapabilityFeature::AddDir]
+    );
 }
 
 #[tokio::test]
@@ -115,14 +192,18 @@ fi
         log = log_path.display()
     );
     let binary = write_fake_codex(temp.path(), &script);
+    let skipped = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&skipped);
     let client = CodexClient::builder()
         .binary(&binary)
         .timeout(Duration::from_secs(5))
+        .on_guard_skip(move |guard| sink.lock().unwrap().push(guard.feature))
         .build();
 
     let login = client.spawn_mcp_login_process().await.unwrap();
     assert!(login.is_none());
     assert!(!log_path.exists());
+    assert_eq!(*skipped.lock().unwrap(), vec![CapabilityFeature::McpLogin]);
 }
 
 #[tokio::test]
@@ -240,3 +321,110 @@ fi
     assert!(!refreshed.features.supports_mcp_login);
     clear_capability_cache();
 }
+
+fn structured_output_script(features: &str, message: &str) -> String {
+    format!(
+        r#"#!/bin/bash
+if [[ "$1" == "--version" ]]; then
+  echo "codex 1.2.3"
+elif [[ "$1" == "features" && "$2" == "list" && "$3" == "--json" ]]; then
+  echo '{{"features":[{features}]}}'
+elif [[ "$1" == "features" && "$2" == "list" ]]; then
+  echo "{features}"
+elif [[ "$1" == "--help" ]]; then
+  echo "Usage: codex exec"
+elif [[ "$1" == "exec" ]]; then
+  cat >/dev/null
+  while [[ $# -gt 0 ]]; do
+    if [[ "$1" == "--output-last-message" ]]; then
+      printf '%s' '{message}' > "$2"
+    fi
+    shift
+  done
+  echo '{{"type":"thread.started","thread_id":"thread-typed"}}'
+fi
+"#
+    )
+}
+
+#[derive(Debug, serde::Deserialize, PartialEq)]
+struct TypedAnswer {
+    answer: u32,
+}
+
+#[tokio::test]
+async fn send_prompt_typed_deserializes_last_message() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let script = structured_output_script(r#""output_schema""#, r#"{"answer":42}"#);
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .quiet(true)
+        .mirror_stdout(false)
+        .build();
+
+    let typed: TypedAnswer = client
+        .send_prompt_typed("answer?", temp.path().join("schema.json"))
+        .await
+        .unwrap();
+    assert_eq!(typed, TypedAnswer { answer: 42 });
+    clear_capability_cache();
+}
+
+#[tokio::test]
+async fn send_prompt_typed_reports_parse_failures() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let script = structured_output_script(r#""output_schema""#, "not json");
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .quiet(true)
+        .mirror_stdout(false)
+        .build();
+
+    let err = client
+        .send_prompt_typed::<TypedAnswer>("answer?", temp.path().join("schema.json"))
+        .await
+        .unwrap_err();
+    match err {
+        CodexError::StructuredOutput { expected, .. } => assert!(expected.ends_with("TypedAnswer")),
+        other => panic!("expected StructuredOutput, got {other:?}"),
+    }
+    clear_capability_cache();
+}
+
+#[tokio::test]
+async fn send_prompt_typed_requires_output_schema_support() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let script = structured_output_script(r#""add_dir""#, r#"{"answer":1}"#);
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .quiet(true)
+        .mirror_stdout(false)
+        .build();
+
+    let err = client
+        .send_prompt_typed::<TypedAnswer>("answer?", temp.path().join("schema.json"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        CodexError::UnsupportedCapability {
+            feature: "--output-schema"
+        }
+    ));
+    clear_capability_cache();
+}
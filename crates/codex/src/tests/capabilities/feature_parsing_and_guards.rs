@@ -69,6 +69,9 @@ fn capability_guard_reports_detected_support() {
         supports_output_schema: true,
         supports_add_dir: true,
         supports_mcp_login: true,
+        supports_json_output: false,
+        supports_add_dir_read_only: false,
+        app_server_subcommands: None,
     };
     let capabilities = capabilities_with_feature_flags(flags);
 
@@ -94,6 +97,9 @@ fn capability_guard_marks_absent_feature_as_unsupported() {
         supports_output_schema: false,
         supports_add_dir: false,
         supports_mcp_login: false,
+        supports_json_output: false,
+        supports_add_dir_read_only: false,
+        app_server_subcommands: None,
     };
     let capabilities = capabilities_with_feature_flags(flags);
 
@@ -124,3 +130,64 @@ fn capability_guard_returns_unknown_without_feature_list() {
     let features_list = capabilities.guard_features_list();
     assert_eq!(features_list.support, CapabilitySupport::Unknown);
 }
+
+#[test]
+fn capability_summary_explains_probe_plan() {
+    let mut capabilities = capabilities_with_version("codex 1.2.3-beta");
+    capabilities.features = CodexFeatureFlags {
+        supports_features_list: true,
+        supports_output_schema: true,
+        ..CodexFeatureFlags::default()
+    };
+    capabilities.probe_plan.steps = vec![
+        CapabilityProbeStep::VersionFlag,
+        CapabilityProbeStep::FeaturesListJson,
+        CapabilityProbeStep::ManualOverride,
+    ];
+
+    assert_eq!(
+        capabilities.probe_plan.explain(),
+        vec![
+            "Ran `codex --version`",
+            "Parsed `features list --json`",
+            "Applied manual overrides",
+        ]
+    );
+    assert_eq!(
+        capabilities.summary(),
+        "codex 1.2.3 (beta); features: codex features list, --output-schema; probes: Ran `codex --version`, Parsed `features list --json`, Applied manual overrides"
+    );
+
+    assert_eq!(
+        capabilities_without_version().summary(),
+        "codex (version unknown); features: none; probes: none"
+    );
+}
+
+#[test]
+fn exec_help_detects_json_only_from_the_options_table() {
+    let help = r#"Run Codex non-interactively
+
+Usage: codex exec [OPTIONS] [PROMPT] [COMMAND]
+
+Options:
+  -c, --config <key=value>
+          Override a configuration value
+      --skip-git-repo-check
+          Allow running Codex outside a Git repository
+      --json
+          Print events to stdout as JSONL
+      --add-dir-read-only <DIR>
+          Additional directories Codex may read but not write
+"#;
+    let flags = version::parse_exec_help_output(help);
+    assert!(flags.supports_json_output);
+    assert!(flags.supports_add_dir_read_only);
+
+    let echoed =
+        version::parse_exec_help_output("exec --help\nerror: unexpected argument '--json'");
+    assert!(!echoed.supports_json_output);
+    let prose = version::parse_exec_help_output("Pass --json to stream events.");
+    assert!(!prose.supports_json_output);
+    assert!(!prose.supports_add_dir_read_only);
+}
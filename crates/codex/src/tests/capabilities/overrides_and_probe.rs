@@ -27,6 +27,9 @@ exit 99
             supports_output_schema: true,
             supports_add_dir: false,
             supports_mcp_login: true,
+            supports_json_output: false,
+            supports_add_dir_read_only: false,
+            app_server_subcommands: None,
         },
         probe_plan: CapabilityProbePlan::default(),
         collected_at: SystemTime::now(),
@@ -89,6 +92,9 @@ fi
         supports_output_schema: true,
         supports_add_dir: false,
         supports_mcp_login: true,
+        supports_json_output: false,
+        supports_add_dir_read_only: false,
+        app_server_subcommands: None,
     });
 
     let client = CodexClient::builder()
@@ -155,3 +161,49 @@ fi
         CapabilitySupport::Supported
     );
 }
+
+#[test]
+fn capability_overrides_merge_prefers_higher_priority_fields() {
+    let empty = CapabilityOverrides::default();
+    assert!(empty
+        .merged_with(&CapabilityOverrides::default())
+        .is_empty());
+
+    let base = CapabilityOverrides {
+        snapshot: Some(sample_capabilities_snapshot()),
+        version: Some(version::parse_version_output("codex 1.0.0")),
+        features: CapabilityFeatureOverrides {
+            supports_output_schema: Some(true),
+            supports_add_dir: Some(true),
+            ..Default::default()
+        },
+    };
+    let request = CapabilityOverrides {
+        snapshot: None,
+        version: Some(version::parse_version_output("codex 2.0.0")),
+        features: CapabilityFeatureOverrides {
+            supports_add_dir: Some(false),
+            supports_mcp_login: Some(true),
+            ..Default::default()
+        },
+    };
+
+    let merged = base.merged_with(&request);
+    assert_eq!(merged.snapshot, base.snapshot);
+    assert_eq!(
+        merged.version.as_ref().and_then(|v| v.semantic),
+        Some((2, 0, 0))
+    );
+    assert_eq!(
+        merged.features,
+        CapabilityFeatureOverrides {
+            supports_output_schema: Some(true),
+            supports_add_dir: Some(false),
+            supports_mcp_login: Some(true),
+            ..Default::default()
+        }
+    );
+
+    assert_eq!(base.merged_with(&empty), base);
+    assert_eq!(empty.merged_with(&base), base);
+}
@@ -22,6 +22,9 @@ async fn probe_reprobes_when_metadata_missing() {
                     supports_output_schema: true,
                     supports_add_dir: true,
                     supports_mcp_login: true,
+                    supports_json_output: false,
+                    supports_add_dir_read_only: false,
+                    app_server_subcommands: None,
                 },
                 probe_plan: CapabilityProbePlan::default(),
                 collected_at: SystemTime::UNIX_EPOCH,
@@ -87,6 +90,75 @@ fi
     clear_capability_cache();
 }
 
+#[tokio::test]
+async fn probe_fingerprint_by_hash_detects_same_size_rebuild() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let script = |version: &str| {
+        format!(
+            r#"#!/bin/bash
+if [[ "$1" == "--version" ]]; then
+  echo "codex {version}"
+fi
+"#
+        )
+    };
+    let binary = write_fake_codex(temp.path(), &script("1.0.0"));
+    let modified = std_fs::metadata(&binary).unwrap().modified().unwrap();
+
+    let hashed = CodexClient::builder()
+        .binary(&binary)
+        .fingerprint_by_hash(true)
+        .timeout(Duration::from_secs(5))
+        .build();
+    let first = hashed.probe_capabilities().await;
+    assert_eq!(
+        first.version.and_then(|version| version.semantic),
+        Some((1, 0, 0))
+    );
+    let first_hash = first
+        .fingerprint
+        .as_ref()
+        .and_then(|fingerprint| fingerprint.sha256.clone())
+        .expect("hash recorded");
+    assert_eq!(first_hash.len(), 64);
+
+    std_fs::write(&binary, script("2.0.0")).unwrap();
+    std_fs::File::options()
+        .write(true)
+        .open(&binary)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    let cache_key = capability_cache_key(&binary);
+    assert!(fingerprints_match(
+        &first
+            .fingerprint
+            .clone()
+            .map(|fingerprint| BinaryFingerprint {
+                sha256: None,
+                ..fingerprint
+            }),
+        &current_fingerprint(&cache_key, false)
+    ));
+
+    let second = hashed.probe_capabilities().await;
+    assert_eq!(
+        second.version.and_then(|version| version.semantic),
+        Some((2, 0, 0))
+    );
+    assert_ne!(
+        second
+            .fingerprint
+            .and_then(|fingerprint| fingerprint.sha256),
+        Some(first_hash)
+    );
+
+    clear_capability_cache();
+}
+
 #[tokio::test]
 async fn probe_bypass_policy_skips_cache_writes() {
     let _guard = env_guard_async().await;
@@ -116,3 +188,112 @@ fi
     assert!(capability_cache_entry(&binary).is_none());
     clear_capability_cache();
 }
+
+#[tokio::test]
+async fn probe_offline_policy_never_spawns_binary() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("probe.log");
+    let script = format!(
+        r#"#!/bin/bash
+echo "$@" >> "{log}"
+echo "codex 9.9.9"
+"#,
+        log = log_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .build();
+    let conservative = client
+        .probe_capabilities_with_policy(CapabilityCachePolicy::OfflineSnapshotOnly)
+        .await;
+    assert!(conservative.version.is_none());
+    assert_eq!(conservative.features, CodexFeatureFlags::default());
+    assert!(conservative
+        .probe_plan
+        .steps
+        .contains(&CapabilityProbeStep::ManualOverride));
+    assert!(!conservative.guard_output_schema().is_supported());
+
+    let snapshot = CodexCapabilities {
+        cache_key: capability_cache_key(&binary),
+        fingerprint: None,
+        version: Some(version::parse_version_output("codex 1.2.3")),
+        features: CodexFeatureFlags {
+            supports_output_schema: true,
+            ..CodexFeatureFlags::default()
+        },
+        probe_plan: CapabilityProbePlan::default(),
+        collected_at: SystemTime::now(),
+    };
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .capability_snapshot(snapshot)
+        .build();
+    let from_snapshot = client
+        .probe_capabilities_with_policy(CapabilityCachePolicy::OfflineSnapshotOnly)
+        .await;
+    assert!(from_snapshot.features.supports_output_schema);
+    assert_eq!(
+        from_snapshot.version.as_ref().and_then(|v| v.semantic),
+        Some((1, 2, 3))
+    );
+
+    assert!(!log_path.exists(), "offline policy must not spawn codex");
+    assert!(capability_cache_entry(&binary).is_none());
+    clear_capability_cache();
+}
+
+#[tokio::test]
+async fn concurrent_probes_share_single_flight() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("probe.log");
+    let script = format!(
+        r#"#!/bin/bash
+if [[ "$1" == "--version" ]]; then
+  echo "--version" >> "{log}"
+  sleep 0.2
+  echo "codex 1.0.0"
+elif [[ "$1" == "features" && "$2" == "list" && "$3" == "--json" ]]; then
+  echo '{{"features":["output_schema"]}}'
+elif [[ "$1" == "features" && "$2" == "list" ]]; then
+  echo "output_schema"
+fi
+"#,
+        log = log_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .build();
+
+    let handles: Vec<_> = (0..10)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.probe_capabilities().await })
+        })
+        .collect();
+
+    for handle in handles {
+        let capabilities = handle.await.unwrap();
+        assert!(capabilities.features.supports_output_schema);
+    }
+
+    let version_runs = std_fs::read_to_string(&log_path)
+        .unwrap()
+        .lines()
+        .filter(|line| *line == "--version")
+        .count();
+    assert_eq!(version_runs, 1);
+    clear_capability_cache();
+}
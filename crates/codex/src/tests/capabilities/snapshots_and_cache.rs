@@ -34,13 +34,40 @@ fn capability_snapshots_and_overrides_round_trip_via_files() {
     assert_eq!(loaded_overrides, overrides);
 }
 
+#[tokio::test]
+async fn capability_snapshots_round_trip_via_async_io() {
+    let snapshot = sample_capabilities_snapshot();
+    let temp = tempfile::tempdir().unwrap();
+
+    let json_path = temp.path().join("capabilities.json");
+    write_capabilities_snapshot_async(&json_path, &snapshot, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        read_capabilities_snapshot(&json_path, None).unwrap(),
+        snapshot
+    );
+    assert_eq!(
+        read_capabilities_snapshot_async(&json_path, None)
+            .await
+            .unwrap(),
+        snapshot
+    );
+
+    let missing = temp.path().join("missing.toml");
+    match read_capabilities_snapshot_async(&missing, None).await {
+        Err(CapabilitySnapshotError::ReadSnapshot { path, .. }) => assert_eq!(path, missing),
+        other => panic!("expected ReadSnapshot error, got {other:?}"),
+    }
+}
+
 #[test]
 fn capability_snapshot_match_checks_fingerprint() {
     let temp = tempfile::tempdir().unwrap();
     let script = "#!/bin/bash\necho ok";
     let binary = write_fake_codex(temp.path(), script);
     let cache_key = capability_cache_key(&binary);
-    let fingerprint = current_fingerprint(&cache_key);
+    let fingerprint = current_fingerprint(&cache_key, false);
 
     let snapshot = CodexCapabilities {
         cache_key: cache_key.clone(),
@@ -75,7 +102,7 @@ fn capability_cache_entries_exposes_cache_state() {
     let temp = tempfile::tempdir().unwrap();
     let binary = write_fake_codex(temp.path(), "#!/bin/bash\necho ok");
     let cache_key = capability_cache_key(&binary);
-    let fingerprint = current_fingerprint(&cache_key);
+    let fingerprint = current_fingerprint(&cache_key, false);
 
     let snapshot = CodexCapabilities {
         cache_key: cache_key.clone(),
@@ -86,6 +113,9 @@ fn capability_cache_entries_exposes_cache_state() {
             supports_output_schema: true,
             supports_add_dir: false,
             supports_mcp_login: false,
+            supports_json_output: false,
+            supports_add_dir_read_only: false,
+            app_server_subcommands: None,
         },
         probe_plan: CapabilityProbePlan {
             steps: vec![CapabilityProbeStep::VersionFlag],
@@ -115,6 +145,7 @@ fn capability_ttl_decision_reuses_fresh_snapshot() {
             canonical_path: Some(PathBuf::from("/tmp/codex")),
             modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1)),
             len: Some(123),
+            sha256: None,
         }),
     );
 
@@ -136,6 +167,7 @@ fn capability_ttl_decision_refreshes_after_ttl_with_fingerprint() {
             canonical_path: Some(PathBuf::from("/tmp/codex")),
             modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1)),
             len: Some(321),
+            sha256: None,
         }),
     );
 
@@ -161,3 +193,183 @@ fn capability_ttl_decision_bypasses_when_metadata_missing() {
     assert!(decision.should_probe);
     assert_eq!(decision.policy, CapabilityCachePolicy::Bypass);
 }
+
+#[test]
+fn capability_age_and_staleness_follow_collected_at() {
+    let collected_at = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+    let snapshot = capability_snapshot_with_metadata(collected_at, None);
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(70);
+
+    assert_eq!(snapshot.age(now), Some(Duration::from_secs(60)));
+    assert!(!snapshot.is_stale(Duration::from_secs(61), now));
+    assert!(snapshot.is_stale(Duration::from_secs(60), now));
+}
+
+#[test]
+fn capability_age_treats_future_collected_at_as_stale() {
+    let collected_at = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+    let snapshot = capability_snapshot_with_metadata(collected_at, None);
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(40);
+
+    assert_eq!(snapshot.age(now), None);
+    assert!(snapshot.is_stale(Duration::from_secs(3600), now));
+}
+
+#[tokio::test]
+async fn loaded_capability_snapshot_applies_until_cleared() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/bin/bash
+if [[ "$1" == "--version" ]]; then
+  echo "codex 1.0.0"
+elif [[ "$1" == "features" ]]; then
+  echo '{"features":[]}'
+fi
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .capability_cache_policy(CapabilityCachePolicy::Bypass)
+        .build();
+
+    let mut pinned = capabilities_with_version("codex 9.9.9");
+    pinned.features.supports_output_schema = true;
+    let snapshot_path = temp.path().join("pinned.json");
+    write_capabilities_snapshot(&snapshot_path, &pinned, None).unwrap();
+
+    assert!(
+        !client
+            .probe_capabilities()
+            .await
+            .features
+            .supports_output_schema
+    );
+
+    client
+        .load_capability_snapshot(&snapshot_path, None)
+        .unwrap();
+    let loaded = client.clone().probe_capabilities().await;
+    assert!(loaded.features.supports_output_schema);
+    assert_eq!(
+        loaded.version.and_then(|version| version.semantic),
+        Some((9, 9, 9))
+    );
+
+    let missing = client.load_capability_snapshot(temp.path().join("missing.json"), None);
+    assert!(matches!(
+        missing,
+        Err(CapabilitySnapshotError::ReadSnapshot { .. })
+    ));
+    assert!(
+        client
+            .probe_capabilities()
+            .await
+            .features
+            .supports_output_schema
+    );
+
+    client.clear_capability_snapshot();
+    let probed = client.probe_capabilities().await;
+    assert!(!probed.features.supports_output_schema);
+    assert_eq!(
+        probed.version.and_then(|version| version.semantic),
+        Some((1, 0, 0))
+    );
+    clear_capability_cache();
+}
+
+#[tokio::test]
+async fn loaded_capability_snapshot_is_not_shared_through_the_cache() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/bin/bash
+if [[ "$1" == "--version" ]]; then
+  echo "codex 1.0.0"
+elif [[ "$1" == "features" ]]; then
+  echo '{"features":[]}'
+fi
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .capability_cache_policy(CapabilityCachePolicy::PreferCache)
+        .build();
+
+    let pinned = capabilities_with_version("codex 9.9.9");
+    let snapshot_path = temp.path().join("pinned.json");
+    write_capabilities_snapshot(&snapshot_path, &pinned, None).unwrap();
+    client
+        .load_capability_snapshot(&snapshot_path, None)
+        .unwrap();
+
+    let loaded = client.probe_capabilities().await;
+    assert_eq!(
+        loaded.version.and_then(|version| version.semantic),
+        Some((9, 9, 9))
+    );
+    assert!(capability_cache_entry(&binary).is_none());
+
+    client.clear_capability_snapshot();
+    let probed = client.probe_capabilities().await;
+    assert_eq!(
+        probed.version.and_then(|version| version.semantic),
+        Some((1, 0, 0))
+    );
+    clear_capability_cache();
+}
+
+#[tokio::test]
+async fn capabilities_file_is_used_only_while_fingerprint_matches() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/bin/bash
+if [[ "$1" == "--version" ]]; then
+  echo "codex 1.0.0"
+elif [[ "$1" == "features" ]]; then
+  echo '{"features":[]}'
+fi
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let cache_key = capability_cache_key(&binary);
+    let mut shipped = capabilities_with_version("codex 9.9.9");
+    shipped.fingerprint = current_fingerprint(&cache_key, false);
+    shipped.cache_key = cache_key;
+    shipped.features.supports_output_schema = true;
+    let snapshot_path = temp.path().join("capabilities.json");
+    write_capabilities_snapshot(&snapshot_path, &shipped, None).unwrap();
+
+    let build = |file: &Path| {
+        CodexClient::builder()
+            .binary(&binary)
+            .capabilities_file(file)
+            .capability_cache_policy(CapabilityCachePolicy::Bypass)
+            .build()
+    };
+
+    let loaded = build(&snapshot_path).probe_capabilities().await;
+    assert!(loaded.features.supports_output_schema);
+    assert_eq!(
+        loaded.version.and_then(|version| version.semantic),
+        Some((9, 9, 9))
+    );
+
+    let missing = build(&temp.path().join("missing.json"))
+        .probe_capabilities()
+        .await;
+    assert_eq!(
+        missing.version.and_then(|version| version.semantic),
+        Some((1, 0, 0))
+    );
+
+    write_fake_codex(temp.path(), &format!("{script}# upgraded\n"));
+    let stale = build(&snapshot_path).probe_capabilities().await;
+    assert!(!stale.features.supports_output_schema);
+    assert_eq!(
+        stale.version.and_then(|version| version.semantic),
+        Some((1, 0, 0))
+    );
+    clear_capability_cache();
+}
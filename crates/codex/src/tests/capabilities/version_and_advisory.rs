@@ -12,6 +12,96 @@ fn parses_version_output_fields() {
     );
 }
 
+#[test]
+fn version_info_is_at_least_and_satisfies_ranges() {
+    let beta = version::parse_version_output("codex 1.2.3-beta.2");
+    assert!(beta.is_at_least(1, 2, 3));
+    assert!(beta.is_at_least(1, 2, 2));
+    assert!(beta.is_at_least(0, 9, 9));
+    assert!(!beta.is_at_least(1, 2, 4));
+    assert!(!beta.is_at_least(1, 3, 0));
+
+    let req = |text: &str| semver::VersionReq::parse(text).unwrap();
+    assert!(!beta.satisfies(&req(">=1.2.3")));
+    assert!(beta.satisfies(&req(">=1.2.3-beta")));
+    assert!(!beta.satisfies(&req(">=1.2.3-rc")));
+
+    let stable = version::parse_version_output("codex-cli 1.2.3");
+    assert!(stable.satisfies(&req(">=1.2.3, <2")));
+    assert!(stable.satisfies(&req("^1.2")));
+    assert!(!stable.satisfies(&req(">=1.2.4")));
+
+    let unknown = version::parse_version_output("codex custom build");
+    assert!(!unknown.is_at_least(0, 0, 0));
+    assert!(!unknown.satisfies(&req("*")));
+}
+
+#[test]
+fn parses_release_candidate_and_alpha_channels() {
+    let rc = version::parse_version_output("codex 1.2.0-rc.1");
+    assert_eq!(rc.semantic, Some((1, 2, 0)));
+    assert_eq!(rc.channel, CodexReleaseChannel::ReleaseCandidate);
+
+    let alpha = version::parse_version_output("codex 2.0.0-alpha+build");
+    assert_eq!(alpha.semantic, Some((2, 0, 0)));
+    assert_eq!(alpha.channel, CodexReleaseChannel::Alpha);
+
+    for raw in ["codex 0.9.0-dev.3", "codex 0.9.0-preview"] {
+        assert_eq!(
+            version::parse_version_output(raw).channel,
+            CodexReleaseChannel::Alpha,
+            "{raw}"
+        );
+    }
+    assert_eq!(
+        version::parse_version_output("codex 1.0.0-source.1").channel,
+        CodexReleaseChannel::Custom
+    );
+}
+
+#[test]
+fn update_advisory_falls_back_from_alpha_through_rc_and_beta() {
+    let capabilities = capabilities_with_version("codex 2.0.0-alpha+build");
+    let mut latest = CodexLatestReleases {
+        stable: Some(Version::parse("1.9.0").unwrap()),
+        beta: Some(Version::parse("2.0.0-beta.1").unwrap()),
+        release_candidate: Some(Version::parse("2.0.0-rc.1").unwrap()),
+        ..Default::default()
+    };
+
+    let advisory = update_advisory_from_capabilities(&capabilities, &latest);
+    assert_eq!(
+        advisory.comparison_channel,
+        CodexReleaseChannel::ReleaseCandidate
+    );
+    assert_eq!(advisory.status, CodexUpdateStatus::UpdateRecommended);
+    assert!(advisory
+        .notes
+        .iter()
+        .any(|note| note.contains("No latest alpha release")));
+
+    latest.release_candidate = None;
+    let advisory = update_advisory_from_capabilities(&capabilities, &latest);
+    assert_eq!(advisory.comparison_channel, CodexReleaseChannel::Beta);
+
+    latest.beta = None;
+    let advisory = update_advisory_from_capabilities(&capabilities, &latest);
+    assert_eq!(advisory.comparison_channel, CodexReleaseChannel::Stable);
+    assert_eq!(advisory.status, CodexUpdateStatus::LocalNewerThanKnown);
+
+    let rc = capabilities_with_version("codex 1.2.0-rc.1");
+    let latest = CodexLatestReleases {
+        release_candidate: Some(Version::parse("1.2.0-rc.2").unwrap()),
+        ..Default::default()
+    };
+    let advisory = update_advisory_from_capabilities(&rc, &latest);
+    assert_eq!(
+        advisory.comparison_channel,
+        CodexReleaseChannel::ReleaseCandidate
+    );
+    assert_eq!(advisory.status, CodexUpdateStatus::UpdateRecommended);
+}
+
 #[test]
 fn update_advisory_detects_newer_release() {
     let capabilities = capabilities_with_version("codex 1.0.0");
@@ -103,3 +193,146 @@ fn update_advisory_handles_missing_latest_metadata() {
         .iter()
         .any(|note| note.contains("advisory unavailable")));
 }
+
+#[test]
+fn capabilities_diff_reports_upgrade_and_feature_transitions() {
+    let mut before = capabilities_with_version("codex 1.0.0");
+    before.features = CodexFeatureFlags {
+        supports_features_list: true,
+        supports_mcp_login: true,
+        ..Default::default()
+    };
+    let mut after = capabilities_with_version("codex 1.2.0");
+    after.features = CodexFeatureFlags {
+        supports_features_list: true,
+        supports_output_schema: true,
+        supports_json_output: true,
+        app_server_subcommands: None,
+        ..Default::default()
+    };
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.version.status, CodexUpdateStatus::UpdateRecommended);
+    assert_eq!(
+        diff.gained,
+        vec![
+            CapabilityFeature::OutputSchema,
+            CapabilityFeature::JsonOutput
+        ]
+    );
+    assert_eq!(diff.lost, vec![CapabilityFeature::McpLogin]);
+    assert!(!diff.features_unchanged());
+
+    let reverse = after.diff(&before);
+    assert_eq!(
+        reverse.version.status,
+        CodexUpdateStatus::LocalNewerThanKnown
+    );
+    assert_eq!(reverse.gained, diff.lost);
+    assert_eq!(reverse.lost, diff.gained);
+}
+
+#[test]
+fn capabilities_diff_handles_identical_and_unversioned_snapshots() {
+    let snapshot = capabilities_with_version("codex 2.0.0-beta");
+    let diff = snapshot.diff(&snapshot.clone());
+    assert_eq!(diff.version.status, CodexUpdateStatus::UpToDate);
+    assert_eq!(diff.version.comparison_channel, CodexReleaseChannel::Beta);
+    assert!(diff.features_unchanged());
+
+    let diff = capabilities_without_version().diff(&snapshot);
+    assert_eq!(diff.version.status, CodexUpdateStatus::UnknownLocalVersion);
+    let diff = snapshot.diff(&capabilities_without_version());
+    assert_eq!(diff.version.status, CodexUpdateStatus::UnknownLatestVersion);
+}
+
+#[tokio::test]
+async fn version_fast_path_skips_feature_probes_and_cache() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("version.log");
+    let script = format!(
+        r#"#!/bin/bash
+echo "$@" >> "{log}"
+if [[ "$1" == "--version" ]]; then
+  echo "codex 2.3.4 (commit abc1234)"
+fi
+"#,
+        log = log_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .build();
+
+    let version = client.version().await.unwrap().expect("version parsed");
+    assert_eq!(version.semantic, Some((2, 3, 4)));
+    assert_eq!(version.commit.as_deref(), Some("abc1234"));
+
+    let logged = std_fs::read_to_string(&log_path).unwrap();
+    assert_eq!(logged.trim(), "--version");
+    assert!(capability_cache_entry(&binary).is_none());
+}
+
+#[tokio::test]
+async fn version_fast_path_reports_non_zero_exit() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(temp.path(), "#!/bin/bash\necho boom >&2\nexit 3\n");
+    let client = CodexClient::builder().binary(&binary).build();
+
+    match client.version().await {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
+            assert_eq!(status.code(), Some(3));
+            assert_eq!(stderr.trim(), "boom");
+        }
+        other => panic!("expected NonZeroExit, got {other:?}"),
+    }
+}
+
+#[test]
+fn latest_releases_parse_github_payload() {
+    let payload = r#"[
+      {"tag_name": "rust-v0.47.0-alpha.2", "name": "0.47.0-alpha.2", "draft": false, "prerelease": true, "html_url": "https://github.com/openai/codex/releases/tag/rust-v0.47.0-alpha.2", "assets": []},
+      {"tag_name": "rust-v0.46.0", "name": "0.46.0", "draft": false, "prerelease": false, "assets": [{"name": "codex-x86_64-unknown-linux-musl.tar.gz"}]},
+      {"tag_name": "rust-v0.47.0-nightly.20251014", "draft": false, "prerelease": true},
+      {"tag_name": "rust-v0.47.0-nightly.20251015", "draft": false, "prerelease": true},
+      {"tag_name": "rust-v0.48.0", "draft": true, "prerelease": false},
+      {"tag_name": "rust-v0.45.0", "draft": false, "prerelease": false},
+      {"tag_name": "rust-v0.47.0-alpha.1", "draft": false, "prerelease": true},
+      {"tag_name": "rust-v0.47.0-rc.1", "draft": false, "prerelease": true},
+      {"tag_name": "rust-v0.47.0-rc.2", "draft": false, "prerelease": true},
+      {"tag_name": "rust-v0.47.0-beta.3", "draft": false, "prerelease": true},
+      {"tag_name": "latest-build", "draft": false, "prerelease": false}
+    ]"#;
+
+    let latest = CodexLatestReleases::from_github_releases(payload).unwrap();
+    assert_eq!(latest.stable, Some(Version::parse("0.46.0").unwrap()));
+    assert_eq!(latest.beta, Some(Version::parse("0.47.0-beta.3").unwrap()));
+    assert_eq!(
+        latest.release_candidate,
+        Some(Version::parse("0.47.0-rc.2").unwrap())
+    );
+    assert_eq!(
+        latest.alpha,
+        Some(Version::parse("0.47.0-alpha.2").unwrap())
+    );
+    assert_eq!(
+        latest.nightly,
+        Some(Version::parse("0.47.0-nightly.20251015").unwrap())
+    );
+}
+
+#[test]
+fn latest_releases_reject_non_array_payloads() {
+    assert!(matches!(
+        CodexLatestReleases::from_github_releases(r#"{"message": "API rate limit exceeded"}"#),
+        Err(ReleaseParseError::Json { .. })
+    ));
+    assert_eq!(
+        CodexLatestReleases::from_github_releases("[]").unwrap(),
+        CodexLatestReleases::default()
+    );
+}
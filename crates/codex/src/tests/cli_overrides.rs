@@ -415,6 +415,111 @@ fi
     assert!(!logged[1].contains("--search"));
 }
 
+#[tokio::test]
+async fn stream_exec_applies_cd_and_profile_overrides() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("stream.log");
+    let cwd_path = temp.path().join("stream.cwd");
+    let process_dir = temp.path().join("process-dir");
+    let cd_dir = temp.path().join("cd-dir");
+    std_fs::create_dir_all(&process_dir).unwrap();
+    let script = format!(
+        r#"#!/bin/bash
+echo "$@" >> "{log}"
+pwd > "{cwd}"
+if [[ "$1" == "exec" ]]; then
+  echo '{{"type":"thread.started","thread_id":"thread-1"}}'
+fi
+"#,
+        log = log_path.display(),
+        cwd = cwd_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .mirror_stdout(false)
+        .quiet(true)
+        .working_dir(&process_dir)
+        .cd(&cd_dir)
+        .profile("streaming")
+        .build();
+
+    let stream = client
+        .stream_exec(ExecStreamRequest::new("hello"))
+        .await
+        .unwrap();
+    let events: Vec<_> = stream.events.collect().await;
+    assert_eq!(events.len(), 1);
+    stream.completion.await.unwrap();
+
+    let logged = std_fs::read_to_string(&log_path).unwrap();
+    assert!(logged.contains("--cd"));
+    assert!(logged.contains(&cd_dir.display().to_string()));
+    assert!(logged.contains("--profile streaming"));
+
+    let cwd = std_fs::read_to_string(&cwd_path).unwrap();
+    assert_eq!(
+        std_fs::canonicalize(cwd.trim()).unwrap(),
+        std_fs::canonicalize(&process_dir).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn send_prompt_with_options_layers_over_client_defaults() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("exec.log");
+    let script = format!(
+        r#"#!/bin/bash
+echo "$@" >> "{log}"
+echo "ok"
+"#,
+        log = log_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .timeout(Duration::from_secs(5))
+        .mirror_stdout(false)
+        .quiet(true)
+        .auto_reasoning_defaults(false)
+        .model("base-model")
+        .profile("base")
+        .config_override("foo", "bar")
+        .build();
+
+    let options = ExecOptions::new()
+        .profile("team")
+        .model("alt-model")
+        .config_override("extra", "value");
+    let response = client
+        .send_prompt_with_options("first", options)
+        .await
+        .unwrap();
+    assert_eq!(response.trim(), "ok");
+    client
+        .send_prompt_with_options("second", ExecOptions::default())
+        .await
+        .unwrap();
+
+    let logged = std_fs::read_to_string(&log_path).unwrap();
+    let lines: Vec<_> = logged.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("--profile team"));
+    assert!(lines[0].contains("--model alt-model"));
+    assert!(lines[0].contains("foo=bar"));
+    assert!(lines[0].contains("extra=value"));
+    assert!(lines[1].contains("--profile base"));
+    assert!(lines[1].contains("--model base-model"));
+    assert!(!lines[1].contains("extra=value"));
+}
+
 #[tokio::test]
 async fn apply_respects_cli_overrides_without_search() {
     let _guard = env_guard_async().await;
@@ -458,3 +563,81 @@ fn color_mode_strings_are_stable() {
     assert_eq!(ColorMode::Always.as_str(), "always");
     assert_eq!(ColorMode::Never.as_str(), "never");
 }
+
+#[tokio::test]
+async fn prompt_with_attachments_routes_images_and_text_files() {
+    let _guard = env_guard_async().await;
+    clear_capability_cache();
+
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("exec.log");
+    let script = format!(
+        r#"#!/bin/bash
+if [[ "$1" == "exec" ]]; then
+  printf '%s\n' "$@" >> "{log}"
+fi
+echo "ok"
+"#,
+        log = log_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let files = temp.path().join("files");
+    std_fs::create_dir_all(&files).unwrap();
+    let image = files.join("diagram.png");
+    let notes = files.join("notes.md");
+    std_fs::write(&image, b"png").unwrap();
+    std_fs::write(&notes, "remember the milk\n").unwrap();
+    let files = std_fs::canonicalize(&files).unwrap();
+
+    let builder = || {
+        CodexClient::builder()
+            .binary(&binary)
+            .timeout(Duration::from_secs(5))
+            .mirror_stdout(false)
+            .quiet(true)
+            .capability_cache_policy(CapabilityCachePolicy::Bypass)
+    };
+    let attachments = vec![
+        Attachment::Image(image.clone()),
+        Attachment::TextFile(notes.clone()),
+    ];
+
+    let client = builder()
+        .capability_feature_overrides(CapabilityFeatureOverrides {
+            supports_add_dir: Some(true),
+            ..Default::default()
+        })
+        .build();
+    client
+        .prompt_with_attachments("summarize", attachments)
+        .await
+        .unwrap();
+    let logged = std_fs::read_to_string(&log_path).unwrap();
+    assert!(logged.contains(&format!(
+        "--image\n{}\n",
+        files.join("diagram.png").display()
+    )));
+    assert!(!logged.contains("--add-dir"));
+    assert!(logged.contains(&format!("--- {} ---", files.join("notes.md").display())));
+    assert!(logged.contains("remember the milk"));
+
+    let capped = builder().max_attachment_bytes(4).build();
+    match capped
+        .prompt_with_attachments("summarize", vec![Attachment::TextFile(notes)])
+        .await
+    {
+        Err(CodexError::AttachmentTooLarge { size, limit, .. }) => {
+            assert_eq!((size, limit), (18, 4));
+        }
+        other => panic!("expected AttachmentTooLarge, got {other:?}"),
+    }
+    let missing = temp.path().join("missing.txt");
+    match capped
+        .prompt_with_attachments("summarize", vec![Attachment::TextFile(missing.clone())])
+        .await
+    {
+        Err(CodexError::AttachmentNotFound { path }) => assert_eq!(path, missing),
+        other => panic!("expected AttachmentNotFound, got {other:?}"),
+    }
+    clear_capability_cache();
+}
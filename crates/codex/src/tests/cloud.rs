@@ -79,6 +79,30 @@ printf "%s\n" "$@"
         ]
     );
 
+    assert_eq!(output.task_id, None);
+
+    let created_dir = tempfile::tempdir().unwrap();
+    let created_path = write_fake_codex(
+        created_dir.path(),
+        r#"#!/usr/bin/env bash
+echo "Submitting task..."
+echo "https://chatgpt.com/codex/tasks/task_e_123abc"
+"#,
+    );
+    let created = CodexClient::builder()
+        .binary(&created_path)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build()
+        .cloud_exec(CloudExecRequest::new("env-1").query("hello"))
+        .await
+        .unwrap();
+    assert_eq!(
+        created.url.as_deref(),
+        Some("https://chatgpt.com/codex/tasks/task_e_123abc")
+    );
+    assert_eq!(created.task_id.as_deref(), Some("task_e_123abc"));
+
     let err = client
         .cloud_exec(CloudExecRequest::new("  "))
         .await
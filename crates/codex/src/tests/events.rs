@@ -0,0 +1,519 @@
+use super::*;
+use serde_json::Value;
+use std::{borrow::Cow, collections::BTreeMap};
+
+fn file_change(path: &str, diff: Option<&str>) -> FileChangeState {
+    FileChangeState {
+        path: PathBuf::from(path),
+        change: Some(FileChangeKind::Apply),
+        diff: diff.map(str::to_string),
+        exit_code: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        extra: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn ordinary_edits_are_not_destructive() {
+    let edit = file_change(
+        "src/main.rs",
+        Some("--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,2 +1,2 @@\n-fn foo() {}\n+fn bar() {}\n"),
+    );
+    assert!(!edit.is_destructive());
+    assert_eq!(edit.destructive_reason(), None);
+
+    let trimmed = file_change(
+        "src/lib.rs",
+        Some("--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -3,2 +3,1 @@\n-unused();\n keep();\n"),
+    );
+    assert!(!trimmed.is_destructive());
+
+    let env_docs = file_change("docs/environment.md", None);
+    assert!(!env_docs.is_destructive());
+    let gitignore = file_change(".gitignore", None);
+    assert!(!gitignore.is_destructive());
+}
+
+#[test]
+fn file_deletions_are_destructive() {
+    let dev_null = file_change(
+        "src/old.rs",
+        Some("--- a/src/old.rs\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-fn old() {}\n-fn older() {}\n"),
+    );
+    assert_eq!(dev_null.destructive_reason(), Some("deletes file"));
+
+    let git_header = file_change(
+        "notes.txt",
+        Some("diff --git a/notes.txt b/notes.txt\ndeleted file mode 100644\n"),
+    );
+    assert!(git_header.is_destructive());
+
+    let emptied = file_change("data.csv", Some("@@ -1,3 +0,0 @@\n-a\n-b\n-c\n"));
+    assert_eq!(emptied.destructive_reason(), Some("deletes file"));
+}
+
+#[test]
+fn sensitive_paths_are_destructive() {
+    let git = file_change(".git/config", None);
+    assert_eq!(git.destructive_reason(), Some("touches .git directory"));
+
+    let env_file = file_change("service/.env.production", None);
+    assert_eq!(
+        env_file.destructive_reason(),
+        Some("touches environment file")
+    );
+
+    let lockfile = file_change("Cargo.lock", None);
+    assert_eq!(lockfile.destructive_reason(), Some("touches lockfile"));
+}
+
+#[test]
+fn thread_event_parses_single_lines() {
+    let event = ThreadEvent::parse_line("{\"type\":\"thread.started\",\"thread_id\":\"t-1\"}\n")
+        .expect("valid line parses");
+    assert!(matches!(
+        event,
+        ThreadEvent::ThreadStarted(ThreadStarted { ref thread_id, .. }) if thread_id == "t-1"
+    ));
+
+    let via_try_from =
+        ThreadEvent::try_from(r#"{"type":"turn.started","thread_id":"t-1","turn_id":"u-1"}"#)
+            .expect("try_from parses");
+    assert!(matches!(via_try_from, ThreadEvent::TurnStarted(_)));
+}
+
+#[test]
+fn thread_event_exposes_thread_and_turn_ids() {
+    let cases = [
+        (
+            r#"{"type":"thread.started","thread_id":"t-1"}"#,
+            Some("t-1"),
+            None,
+        ),
+        (
+            r#"{"type":"turn.started","thread_id":"t-1","turn_id":"u-1"}"#,
+            Some("t-1"),
+            Some("u-1"),
+        ),
+        (
+            r#"{"type":"turn.completed","thread_id":"t-1","turn_id":"u-2"}"#,
+            Some("t-1"),
+            Some("u-2"),
+        ),
+        (
+            r#"{"type":"turn.failed","thread_id":"t-2","turn_id":"u-3","error":{"message":"boom"}}"#,
+            Some("t-2"),
+            Some("u-3"),
+        ),
+        (
+            r#"{"type":"item.started","thread_id":"t-1","turn_id":"u-4","item_id":"i-1","item_type":"agent_message","content":{"text":"hi"}}"#,
+            Some("t-1"),
+            Some("u-4"),
+        ),
+        (
+            r#"{"type":"item.delta","thread_id":"t-1","turn_id":"u-5","item_id":"i-1","item_type":"agent_message","delta":{"text_delta":"!"}}"#,
+            Some("t-1"),
+            Some("u-5"),
+        ),
+        (
+            r#"{"type":"item.completed","thread_id":"t-3","turn_id":"u-6","item_id":"i-1","item_type":"agent_message","content":{"text":"hi!"}}"#,
+            Some("t-3"),
+            Some("u-6"),
+        ),
+        (
+            r#"{"type":"item.failed","thread_id":"t-1","turn_id":"u-7","item_id":"i-2","error":{"message":"exit 1"}}"#,
+            Some("t-1"),
+            Some("u-7"),
+        ),
+        (r#"{"type":"error","message":"rate limited"}"#, None, None),
+    ];
+    for (line, thread_id, turn_id) in cases {
+        let event = ThreadEvent::parse_line(line).expect(line);
+        assert_eq!(event.thread_id(), thread_id, "{line}");
+        assert_eq!(event.turn_id(), turn_id, "{line}");
+    }
+
+    let summary = ThreadEvent::ThreadCompleted(ThreadSummary {
+        thread_id: Some("t-9".to_string()),
+        ..Default::default()
+    });
+    assert_eq!(summary.thread_id(), Some("t-9"));
+    assert_eq!(summary.turn_id(), None);
+
+    let heartbeat = ThreadEvent::Heartbeat {
+        elapsed: std::time::Duration::from_secs(1),
+    };
+    assert_eq!(heartbeat.thread_id(), None);
+    assert_eq!(heartbeat.turn_id(), None);
+}
+
+#[test]
+fn id_remapper_rewrites_ids_consistently_across_stream() {
+    let lines = [
+        r#"{"type":"thread.started","thread_id":"thread_abc"}"#,
+        r#"{"type":"turn.started","thread_id":"thread_abc","turn_id":"turn_x"}"#,
+        r#"{"type":"item.completed","thread_id":"thread_abc","turn_id":"turn_x","item_id":"i-1","item_type":"agent_message","content":{"text":"hi"}}"#,
+        r#"{"type":"turn.completed","thread_id":"thread_abc","turn_id":"turn_x"}"#,
+        r#"{"type":"turn.started","thread_id":"thread_abc","turn_id":"turn_y"}"#,
+        r#"{"type":"error","message":"rate limited"}"#,
+        r#"{"type":"turn.started","thread_id":"thread_def","turn_id":"turn_x"}"#,
+    ];
+    let mut remapper = IdRemapper::new();
+    let ids: Vec<_> = lines
+        .iter()
+        .map(|line| remapper.remap(ThreadEvent::parse_line(line).unwrap()))
+        .map(|event| {
+            (
+                event.thread_id().map(str::to_string),
+                event.turn_id().map(str::to_string),
+            )
+        })
+        .collect();
+    let id = |value: &str| Some(value.to_string());
+    assert_eq!(
+        ids,
+        vec![
+            (id("thread-1"), None),
+            (id("thread-1"), id("turn-1")),
+            (id("thread-1"), id("turn-1")),
+            (id("thread-1"), id("turn-1")),
+            (id("thread-1"), id("turn-2")),
+            (None, None),
+            (id("thread-2"), id("turn-1")),
+        ]
+    );
+    assert_eq!(remapper.thread_id("thread_def"), Some("thread-2"));
+    assert_eq!(remapper.turn_id("turn_y"), Some("turn-2"));
+    assert_eq!(remapper.turn_id("missing"), None);
+
+    let mut summary = ThreadEvent::ThreadCompleted(ThreadSummary {
+        thread_id: Some("thread_abc".to_string()),
+        ..Default::default()
+    });
+    IdRemapper::with_prefixes("t", "u").remap_in_place(&mut summary);
+    assert_eq!(summary.thread_id(), Some("t1"));
+}
+
+#[test]
+fn thread_event_parse_errors_carry_line_and_message() {
+    let err = ThreadEvent::parse_line("not json\r\n").unwrap_err();
+    assert_eq!(err.line, "not json");
+    assert!(!err.message.is_empty());
+    assert!(err.to_string().contains("`not json`"));
+
+    let err = ThreadEvent::try_from(r#"{"type":"bogus"}"#).unwrap_err();
+    assert!(err.message.contains("bogus"), "{err}");
+
+    for synthetic in [
+        r#"{"type":"thread.completed","turns":1}"#,
+        r#"{"type":"wrapper.heartbeat","elapsed":{"secs":1,"nanos":0}}"#,
+        r#"{"type":"wrapper.resumed","thread_id":"t-1","attempt":1}"#,
+    ] {
+        assert!(ThreadEvent::parse_line(synthetic).is_err(), "{synthetic}");
+    }
+}
+
+fn web_search(results: Option<Value>) -> WebSearchState {
+    WebSearchState {
+        query: "rust async".to_string(),
+        results,
+        status: WebSearchStatus::Completed,
+        extra: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn web_search_parses_common_result_shapes() {
+    let state = web_search(Some(json!([
+        {"title": "Tokio", "url": "https://tokio.rs", "snippet": "runtime", "rank": 1},
+        {"title": "Async book", "link": "https://rust-lang.github.io/async-book"}
+    ])));
+    let parsed = state.parsed_results().expect("array shape parses");
+    assert_eq!(
+        parsed,
+        vec![
+            WebSearchResult {
+                title: "Tokio".to_string(),
+                url: "https://tokio.rs".to_string(),
+                snippet: Some("runtime".to_string()),
+            },
+            WebSearchResult {
+                title: "Async book".to_string(),
+                url: "https://rust-lang.github.io/async-book".to_string(),
+                snippet: None,
+            },
+        ]
+    );
+    assert!(state.results.as_ref().unwrap()[0].get("rank").is_some());
+
+    let wrapped = web_search(Some(json!({
+        "results": [{"title": "Tokio", "url": "https://tokio.rs"}]
+    })));
+    assert_eq!(wrapped.parsed_results().unwrap().len(), 1);
+}
+
+#[test]
+fn web_search_returns_none_for_unrecognized_results() {
+    assert!(web_search(None).parsed_results().is_none());
+    assert!(web_search(Some(json!("plain text")))
+        .parsed_results()
+        .is_none());
+    assert!(web_search(Some(json!([{"headline": "missing url"}])))
+        .parsed_results()
+        .is_none());
+}
+
+#[test]
+fn item_status_parses_cancelled_and_reports_terminal_states() {
+    let snapshot: ItemSnapshot = serde_json::from_value(json!({
+        "item_id": "cmd-1",
+        "status": "cancelled",
+        "item_type": "command_execution",
+        "content": {"command": "sleep 10"}
+    }))
+    .unwrap();
+    assert_eq!(snapshot.status, ItemStatus::Cancelled);
+
+    let canceled: ItemStatus = serde_json::from_value(json!("canceled")).unwrap();
+    assert_eq!(canceled, ItemStatus::Cancelled);
+    assert_eq!(
+        serde_json::to_value(ItemStatus::Cancelled).unwrap(),
+        json!("cancelled")
+    );
+
+    assert!(ItemStatus::Completed.is_terminal());
+    assert!(ItemStatus::Failed.is_terminal());
+    assert!(ItemStatus::Cancelled.is_terminal());
+    assert!(!ItemStatus::InProgress.is_terminal());
+    assert!(!ItemStatus::Unknown.is_terminal());
+}
+
+#[test]
+fn item_failure_status_defaults_to_failed() {
+    let failed: ItemFailure = serde_json::from_value(json!({
+        "item_id": "cmd-1",
+        "error": {"message": "exit 1"}
+    }))
+    .unwrap();
+    assert_eq!(failed.status, ItemStatus::Failed);
+
+    let interrupted: ItemFailure = serde_json::from_value(json!({
+        "item_id": "cmd-2",
+        "status": "cancelled",
+        "error": {"message": "turn interrupted"}
+    }))
+    .unwrap();
+    assert_eq!(interrupted.status, ItemStatus::Cancelled);
+    assert!(interrupted.extra.is_empty());
+}
+
+#[test]
+fn command_execution_plain_output_strips_ansi() {
+    let state = CommandExecutionState {
+        command: "cargo test".to_string(),
+        exit_code: Some(0),
+        stdout: "\u{1b}[1;32mok\u{1b}[0m \u{1b}]8;;https://example.com\u{7}link\u{1b}]8;;\u{1b}\\ \u{1b}(Bdone"
+            .to_string(),
+        stderr: "plain warning".to_string(),
+        extra: BTreeMap::new(),
+    };
+
+    assert_eq!(state.plain_stdout(), "ok link done");
+    assert!(state.stdout.contains('\u{1b}'));
+    assert!(matches!(
+        state.plain_stderr(),
+        Cow::Borrowed("plain warning")
+    ));
+}
+
+fn event_error(message: &str, code: Option<&str>) -> EventError {
+    EventError {
+        message: message.to_string(),
+        code: code.map(str::to_string),
+        extra: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn event_error_kind_classifies_codes_before_messages() {
+    let cases = [
+        (
+            event_error("slow down", Some("rate_limit_exceeded")),
+            EventErrorKind::RateLimit,
+        ),
+        (
+            event_error("boom", Some("context_length_exceeded")),
+            EventErrorKind::ContextLengthExceeded,
+        ),
+        (
+            event_error("boom", Some("invalid_api_key")),
+            EventErrorKind::Auth,
+        ),
+        (
+            event_error("boom", Some("tool_error")),
+            EventErrorKind::ToolError,
+        ),
+        (
+            event_error("Rate limit reached", Some("tool_error")),
+            EventErrorKind::ToolError,
+        ),
+        (
+            event_error("Too Many Requests", None),
+            EventErrorKind::RateLimit,
+        ),
+        (
+            event_error("input exceeds the context window", Some("E42")),
+            EventErrorKind::ContextLengthExceeded,
+        ),
+        (event_error("401 Unauthorized", None), EventErrorKind::Auth),
+        (
+            event_error("something else", Some("server_error")),
+            EventErrorKind::Unknown,
+        ),
+    ];
+    for (error, expected) in cases {
+        assert_eq!(error.kind(), expected, "{error:?}");
+    }
+
+    let error = event_error("slow down", Some("Rate-Limit"));
+    assert_eq!(error.kind(), EventErrorKind::RateLimit);
+    assert_eq!(error.code.as_deref(), Some("Rate-Limit"));
+    assert!(EventErrorKind::RateLimit.is_retryable());
+    assert!(!EventErrorKind::Auth.is_retryable());
+    assert!(!EventErrorKind::Unknown.is_retryable());
+}
+
+#[test]
+fn rate_limit_reads_retry_hints_from_error_and_turn_extras() {
+    let parse = |line: &str| match serde_json::from_str::<ThreadEvent>(line).unwrap() {
+        ThreadEvent::TurnFailed(failed) => failed,
+        other => panic!("expected turn.failed, got {other:?}"),
+    };
+
+    let failed = parse(
+        r#"{"type":"turn.failed","thread_id":"t","turn_id":"u","error":{"message":"slow down","code":"rate_limit_exceeded","retry_after_ms":1500}}"#,
+    );
+    assert_eq!(
+        failed.error.rate_limit(),
+        Some(RateLimit {
+            retry_after: Some(Duration::from_millis(1500)),
+        })
+    );
+    assert_eq!(failed.rate_limit(), failed.error.rate_limit());
+
+    let failed = parse(
+        r#"{"type":"turn.failed","thread_id":"t","turn_id":"u","error":{"message":"Too Many Requests"},"retry_after":"2.5"}"#,
+    );
+    assert_eq!(failed.error.rate_limit(), Some(RateLimit::default()));
+    assert_eq!(
+        failed.rate_limit().unwrap().retry_after,
+        Some(Duration::from_millis(2500))
+    );
+
+    let failed = parse(
+        r#"{"type":"turn.failed","thread_id":"t","turn_id":"u","error":{"message":"try later","retry_after":3}}"#,
+    );
+    assert_eq!(failed.error.kind(), EventErrorKind::RateLimit);
+    assert_eq!(
+        failed.rate_limit().unwrap().retry_after,
+        Some(Duration::from_secs(3))
+    );
+
+    let mut error = event_error("slow down", Some("429"));
+    error.extra.insert(
+        "retry_after".to_string(),
+        serde_json::json!("Wed, 21 Oct 2026 07:28:00 GMT"),
+    );
+    assert_eq!(error.rate_limit(), Some(RateLimit { retry_after: None }));
+    error
+        .extra
+        .insert("retry_after_ms".to_string(), serde_json::json!(-5));
+    assert_eq!(error.rate_limit().unwrap().retry_after, None);
+
+    let mut error = event_error("boom", Some("tool_error"));
+    error
+        .extra
+        .insert("retry_after_ms".to_string(), serde_json::json!(100));
+    assert_eq!(error.kind(), EventErrorKind::ToolError);
+    assert_eq!(error.rate_limit(), None);
+    assert_eq!(event_error("boom", None).rate_limit(), None);
+}
+
+#[test]
+fn extra_get_path_resolves_json_pointers_into_unknown_fields() {
+    let event = ThreadEvent::parse_line(
+        r#"{"type":"turn.completed","thread_id":"t","turn_id":"u","metadata":{"cost":{"usd":0.25},"tags":["a","b"]},"a/b":1}"#,
+    )
+    .unwrap();
+    let ThreadEvent::TurnCompleted(completed) = event else {
+        panic!("expected turn.completed");
+    };
+
+    assert_eq!(
+        completed.extra_get_path("/metadata/cost/usd"),
+        Some(&json!(0.25))
+    );
+    assert_eq!(
+        completed.extra_get_path("/metadata/tags/1"),
+        Some(&json!("b"))
+    );
+    assert_eq!(completed.extra_get_path("/a~1b"), Some(&json!(1)));
+    assert!(completed.extra_get_path("/metadata/missing").is_none());
+    assert!(completed.extra_get_path("metadata").is_none());
+    assert!(completed.extra_get_path("").is_none());
+
+    let event = ThreadEvent::parse_line(
+        r#"{"type":"item.completed","thread_id":"t","turn_id":"u","item_id":"i","item_type":"agent_message","content":{"text":"hi"},"trace":{"span":"s-1"}}"#,
+    )
+    .unwrap();
+    let ThreadEvent::ItemCompleted(envelope) = event else {
+        panic!("expected item.completed");
+    };
+    assert_eq!(envelope.extra_get_path("/trace/span"), Some(&json!("s-1")));
+}
+
+#[test]
+fn display_renders_one_human_line_per_event() {
+    let parse = |line: &str| ThreadEvent::parse_line(line).unwrap();
+
+    let message = parse(
+        r#"{"type":"item.completed","thread_id":"t-1","turn_id":"turn-1","item_id":"m-1","item_type":"agent_message","content":{"text":"Hello\nthere"}}"#,
+    );
+    assert_eq!(
+        message.to_string(),
+        r#"[turn-1] agent_message: "Hello\nthere""#
+    );
+
+    let command = parse(
+        r#"{"type":"item.completed","thread_id":"t-1","turn_id":"turn-1","item_id":"c-1","item_type":"command_execution","content":{"command":"cargo build","exit_code":0}}"#,
+    );
+    assert_eq!(
+        command.to_string(),
+        "[turn-1] command_execution exit=0: cargo build"
+    );
+
+    let started = parse(r#"{"type":"thread.started","thread_id":"t-1"}"#);
+    assert_eq!(started.to_string(), "[t-1] thread.started");
+
+    let long = "x".repeat(DISPLAY_TEXT_LIMIT + 20);
+    let reasoning = parse(&format!(
+        r#"{{"type":"item.delta","thread_id":"t-1","turn_id":"turn-1","item_id":"r-1","item_type":"reasoning","delta":{{"text_delta":"{long}"}}}}"#
+    ));
+    assert_eq!(
+        reasoning.to_string(),
+        format!(
+            r#"[turn-1] reasoning delta: "{}"… ({} chars)"#,
+            "x".repeat(DISPLAY_TEXT_LIMIT),
+            DISPLAY_TEXT_LIMIT + 20
+        )
+    );
+    assert_eq!(
+        format!("{message:.3}"),
+        r#"[turn-1] agent_message: "Hel"… (11 chars)"#
+    );
+
+    let error = parse(r#"{"type":"error","message":"stream disconnected"}"#);
+    assert_eq!(error.to_string(), "error: stream disconnected");
+    assert_eq!(format!("{error:.6}"), "error: stream… (19 chars)");
+}
@@ -33,7 +33,15 @@ async fn json_stream_preserves_order_and_parses_tool_calls() {
 
     let (mut writer, reader) = tokio::io::duplex(4096);
     let (tx, rx) = mpsc::channel(8);
-    let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(reader, tx, false, None));
+    let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(
+        reader,
+        tx,
+        None,
+        Vec::new(),
+        CaptureOptions::default(),
+        ParseErrorMode::Fail,
+        None,
+    ));
 
     for line in &lines {
         writer.write_all(line.as_bytes()).await.unwrap();
@@ -94,7 +102,15 @@ async fn json_stream_preserves_order_and_parses_tool_calls() {
 async fn json_stream_propagates_parse_errors() {
     let (mut writer, reader) = tokio::io::duplex(1024);
     let (tx, rx) = mpsc::channel(4);
-    let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(reader, tx, false, None));
+    let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(
+        reader,
+        tx,
+        None,
+        Vec::new(),
+        CaptureOptions::default(),
+        ParseErrorMode::Fail,
+        None,
+    ));
 
     writer
         .write_all(br#"{"type":"thread.started","thread_id":"thread-err"}"#)
@@ -132,14 +148,17 @@ async fn json_stream_tees_logs_before_forwarding() {
 
     let (mut writer, reader) = tokio::io::duplex(2048);
     let (tx, rx) = mpsc::channel(4);
-    let log_sink = crate::jsonl::JsonLogSink::new(log_path.clone())
+    let log_sink = crate::jsonl::JsonLogSink::new(log_path.clone(), None)
         .await
         .unwrap();
     let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(
         reader,
         tx,
-        false,
-        Some(log_sink),
+        None,
+        vec![log_sink],
+        CaptureOptions::default(),
+        ParseErrorMode::Fail,
+        None,
     ));
 
     let stream = crate::jsonl::EventChannelStream::new(rx, None);
@@ -219,14 +238,17 @@ async fn json_event_log_captures_apply_diff_and_tool_payloads() {
 
     let (mut writer, reader) = tokio::io::duplex(4096);
     let (tx, rx) = mpsc::channel(8);
-    let log_sink = crate::jsonl::JsonLogSink::new(log_path.clone())
+    let log_sink = crate::jsonl::JsonLogSink::new(log_path.clone(), None)
         .await
         .unwrap();
     let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(
         reader,
         tx,
-        false,
-        Some(log_sink),
+        None,
+        vec![log_sink],
+        CaptureOptions::default(),
+        ParseErrorMode::Fail,
+        None,
     ));
 
     for line in &lines {
@@ -246,6 +268,99 @@ async fn json_event_log_captures_apply_diff_and_tool_payloads() {
     assert_eq!(log_contents, lines.join("\n") + "\n");
 }
 
+#[tokio::test]
+async fn json_log_gzip_is_inferred_from_extension_and_finalized() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("events.jsonl.gz");
+    let lines = [
+        r#"{"type":"thread.started","thread_id":"thread-1"}"#,
+        r#"{"type":"turn.started","turn_id":"turn-1"}"#,
+    ];
+
+    for _ in 0..2 {
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        let (tx, rx) = mpsc::channel(8);
+        let log_sink = crate::jsonl::JsonLogSink::new(log_path.clone(), None)
+            .await
+            .unwrap();
+        let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(
+            reader,
+            tx,
+            None,
+            vec![log_sink],
+            CaptureOptions::default(),
+            ParseErrorMode::Fail,
+            None,
+        ));
+        for line in lines {
+            writer.write_all(line.as_bytes()).await.unwrap();
+            writer.write_all(b"\n").await.unwrap();
+        }
+        writer.shutdown().await.unwrap();
+        let stream = crate::jsonl::EventChannelStream::new(rx, None);
+        let _: Vec<_> = stream.collect().await;
+        forward_handle.await.unwrap().unwrap();
+    }
+
+    let compressed = std_fs::read(&log_path).unwrap();
+    let mut decoded = String::new();
+    std::io::Read::read_to_string(
+        &mut flate2::read::MultiGzDecoder::new(compressed.as_slice()),
+        &mut decoded,
+    )
+    .unwrap();
+    let once = lines.join("\n") + "\n";
+    assert_eq!(decoded, once.repeat(2));
+}
+
+#[tokio::test]
+async fn json_log_gzip_lines_survive_without_finish() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("events.log");
+    let mut sink = crate::jsonl::JsonLogSink::new(log_path.clone(), Some(JsonLogCompression::Gzip))
+        .await
+        .unwrap();
+    sink.write_line(r#"{"type":"thread.started","thread_id":"t"}"#)
+        .await
+        .unwrap();
+    drop(sink);
+
+    let compressed = std_fs::read(&log_path).unwrap();
+    let mut decoder = flate2::read::GzDecoder::new(compressed.as_slice());
+    let mut recovered = Vec::new();
+    let mut buf = [0u8; 256];
+    loop {
+        match std::io::Read::read(&mut decoder, &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => recovered.extend_from_slice(&buf[..n]),
+        }
+    }
+    assert_eq!(
+        String::from_utf8(recovered).unwrap(),
+        "{\"type\":\"thread.started\",\"thread_id\":\"t\"}\n"
+    );
+}
+
+#[tokio::test]
+async fn json_log_compression_can_be_disabled_for_gz_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("plain.gz");
+    let mut sink = crate::jsonl::JsonLogSink::new(log_path.clone(), Some(JsonLogCompression::None))
+        .await
+        .unwrap();
+    sink.write_line("{}").await.unwrap();
+    sink.finish().await.unwrap();
+    assert_eq!(std_fs::read_to_string(&log_path).unwrap(), "{}\n");
+    assert_eq!(
+        JsonLogCompression::for_path(Path::new("events.JSONL.GZ")),
+        JsonLogCompression::Gzip
+    );
+    assert_eq!(
+        JsonLogCompression::for_path(Path::new("events.jsonl")),
+        JsonLogCompression::None
+    );
+}
+
 #[tokio::test]
 async fn event_channel_stream_times_out_when_idle() {
     let (_tx, rx) = mpsc::channel(1);
@@ -261,6 +376,41 @@ async fn event_channel_stream_times_out_when_idle() {
     }
 }
 
+#[tokio::test]
+async fn event_channel_stream_emits_heartbeats_until_closed() {
+    let (tx, rx) = mpsc::channel(1);
+    let stream = crate::jsonl::EventChannelStream::new(rx, None)
+        .with_heartbeat(Some(Duration::from_millis(5)));
+    pin_mut!(stream);
+
+    for _ in 0..2 {
+        match stream.next().await {
+            Some(Ok(ThreadEvent::Heartbeat { elapsed })) => {
+                assert!(elapsed >= Duration::from_millis(5), "elapsed: {elapsed:?}");
+            }
+            other => panic!("expected heartbeat, got {other:?}"),
+        }
+    }
+
+    tx.send(Ok(ThreadEvent::TurnStarted(TurnStarted {
+        thread_id: "thread-1".to_string(),
+        turn_id: "turn-1".to_string(),
+        input_text: None,
+        extra: Default::default(),
+    })))
+    .await
+    .unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(ThreadEvent::TurnStarted(_)))
+    ));
+
+    drop(tx);
+    assert!(stream.next().await.is_none());
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(stream.next().await.is_none());
+}
+
 #[test]
 fn normalize_stream_infers_missing_thread_and_turn() {
     let mut context = crate::jsonl::StreamContext::default();
@@ -305,3 +455,1018 @@ fn normalize_stream_errors_without_context() {
         other => panic!("unexpected error: {other:?}"),
     }
 }
+
+#[tokio::test]
+async fn stream_exec_uses_builder_idle_timeout_by_default() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(temp.path(), "#!/bin/bash\ncat >/dev/null\nsleep 2\n");
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .idle_timeout(Duration::from_millis(50))
+        .build();
+
+    let request = ExecStreamRequest::new("hello");
+    let mut stream = client.stream_exec(request.clone()).await.unwrap();
+    match stream.events.next().await {
+        Some(Err(ExecStreamError::IdleTimeout { idle_for })) => {
+            assert_eq!(idle_for, Duration::from_millis(50));
+        }
+        other => panic!("expected idle timeout, got {other:?}"),
+    }
+
+    let mut stream = client
+        .stream_exec(request.idle_timeout(Duration::from_millis(20)))
+        .await
+        .unwrap();
+    match stream.events.next().await {
+        Some(Err(ExecStreamError::IdleTimeout { idle_for })) => {
+            assert_eq!(idle_for, Duration::from_millis(20));
+        }
+        other => panic!("expected idle timeout, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn stream_exec_numbers_artifacts_and_cleans_up_unless_kept() {
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/bin/bash
+cat >/dev/null
+while [ "$#" -gt 0 ]; do
+  if [ "$1" = "--output-last-message" ]; then
+    printf 'done' > "$2"
+  fi
+  shift
+done
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let artifacts = temp.path().join("artifacts");
+    let request = ExecStreamRequest::new("hello");
+
+    let mut paths = Vec::new();
+    for keep in [false, true] {
+        let client = CodexClient::builder()
+            .binary(&binary)
+            .mirror_stdout(false)
+            .quiet(true)
+            .artifact_dir(&artifacts)
+            .keep_artifacts(keep)
+            .build();
+        let stream = client.stream_exec(request.clone()).await.unwrap();
+        let completion = stream.completion.await.unwrap();
+        let path = completion.last_message_path.unwrap();
+        assert_eq!(path.parent(), Some(artifacts.as_path()));
+        assert_eq!(completion.last_message.as_deref(), Some("done"));
+        assert_eq!(path.exists(), keep);
+        paths.push(path);
+    }
+    assert_ne!(paths[0], paths[1]);
+}
+
+#[tokio::test]
+async fn last_message_json_deserializes_structured_replies() {
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    struct Verdict {
+        ok: bool,
+        files: Vec<String>,
+    }
+
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/bin/bash
+prompt=$(cat)
+while [ "$#" -gt 0 ]; do
+  if [ "$1" = "--output-last-message" ] && [ "$prompt" != "silent" ]; then
+    printf '%s\n' "$prompt" > "$2"
+  fi
+  shift
+done
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let run = |prompt: &str| client.stream_exec(ExecStreamRequest::new(prompt));
+
+    let completion = run(r#"{"ok":true,"files":["a.rs"]}"#)
+        .await
+        .unwrap()
+        .completion
+        .await
+        .unwrap();
+    assert_eq!(
+        completion.last_message_json::<Verdict>().unwrap(),
+        Some(Verdict {
+            ok: true,
+            files: vec!["a.rs".to_string()],
+        })
+    );
+
+    let completion = run("not json").await.unwrap().completion.await.unwrap();
+    assert!(completion.last_message_json::<Verdict>().is_err());
+
+    let completion = run("silent").await.unwrap().completion.await.unwrap();
+    assert_eq!(completion.last_message, None);
+    assert_eq!(completion.last_message_json::<Verdict>().unwrap(), None);
+}
+
+#[tokio::test]
+async fn abort_handle_cancels_stream_after_first_event() {
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/bin/bash
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"thread-1"}'
+sleep 5
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let request = ExecStreamRequest::new("hello");
+
+    let started = std::time::Instant::now();
+    let mut stream = client.stream_exec(request.clone()).await.unwrap();
+    let handle = stream.abort_handle();
+    assert!(matches!(
+        stream.events.next().await,
+        Some(Ok(ThreadEvent::ThreadStarted(_)))
+    ));
+
+    tokio::spawn({
+        let handle = handle.clone();
+        async move { handle.abort() }
+    })
+    .await
+    .unwrap();
+    assert!(handle.is_aborted());
+    assert!(matches!(
+        stream.events.next().await,
+        Some(Err(ExecStreamError::Cancelled))
+    ));
+    assert!(stream.events.next().await.is_none());
+    assert!(matches!(
+        stream.completion.await,
+        Err(ExecStreamError::Cancelled)
+    ));
+    assert!(started.elapsed() < Duration::from_secs(4));
+
+    let finished_binary = write_fake_codex(temp.path(), "#!/bin/bash\ncat >/dev/null\n");
+    let client = CodexClient::builder()
+        .binary(&finished_binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let stream = client.stream_exec(request).await.unwrap();
+    let handle = stream.abort_handle();
+    let completion = stream.completion.await.unwrap();
+    assert!(completion.status.success());
+    handle.abort();
+    assert!(!handle.is_aborted());
+}
+
+#[tokio::test]
+async fn exec_stream_run_stops_on_error_unless_continuing() {
+    let temp = tempfile::tempdir().unwrap();
+    let script = r#"#!/bin/bash
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"thread-1"}'
+echo 'not json'
+echo '{"type":"turn.started","thread_id":"thread-1","turn_id":"turn-1"}'
+"#;
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let request = ExecStreamRequest::new("hello");
+
+    let mut seen = Vec::new();
+    let err = client
+        .stream_exec(request.clone())
+        .await
+        .unwrap()
+        .run(|event| seen.push(event))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ExecStreamError::Parse { line, .. } if line == "not json"));
+    assert_eq!(seen.len(), 1);
+
+    let mut seen = Vec::new();
+    let completion = client
+        .stream_exec(request)
+        .await
+        .unwrap()
+        .run_with(true, |event| seen.push(event))
+        .await
+        .unwrap();
+    assert!(completion.status.success());
+    assert!(matches!(
+        seen.as_slice(),
+        [ThreadEvent::ThreadStarted(_), ThreadEvent::TurnStarted(_)]
+    ));
+}
+
+#[tokio::test]
+async fn stream_exec_auto_resume_continues_thread_after_crash() {
+    let temp = tempfile::tempdir().unwrap();
+    let log_path = temp.path().join("args.log");
+    let script = format!(
+        r#"#!/bin/bash
+stdin=$(cat)
+echo "$@ <$stdin>" >> "{}"
+if [[ " $* " == *" resume t-1 "* ]]; then
+  if [[ -z "$stdin" ]]; then
+    echo "error: no prompt provided" >&2
+    exit 1
+  fi
+  echo '{{"type":"thread.resumed","thread_id":"t-1"}}'
+  echo '{{"type":"turn.started","thread_id":"t-1","turn_id":"turn-1"}}'
+  echo '{{"type":"turn.completed","thread_id":"t-1","turn_id":"turn-1"}}'
+  exit 0
+fi
+echo '{{"type":"thread.started","thread_id":"t-1"}}'
+echo '{{"type":"turn.started","thread_id":"t-1","turn_id":"turn-1"}}'
+exit 1
+"#,
+        log_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let request = ExecStreamRequest::new("hello").auto_resume(true);
+
+    let stream = client
+        .stream_exec(request.clone().emit_summary(true))
+        .await
+        .unwrap();
+    let events: Vec<_> = stream.events.map(Result::unwrap).collect().await;
+    let completion = stream.completion.await.unwrap();
+    assert!(completion.status.success());
+    assert!(matches!(
+        events.as_slice(),
+        [
+            ThreadEvent::ThreadStarted(_),
+            ThreadEvent::TurnStarted(_),
+            ThreadEvent::Resumed { thread_id, attempt: 1 },
+            ThreadEvent::ThreadStarted(_),
+            ThreadEvent::TurnStarted(_),
+            ThreadEvent::TurnCompleted(_),
+            ThreadEvent::ThreadCompleted(summary),
+        ] if thread_id == "t-1" && summary.turns == 1
+    ));
+    let logged = std_fs::read_to_string(&log_path).unwrap();
+    assert_eq!(logged.lines().count(), 2);
+    assert!(logged
+        .lines()
+        .nth(1)
+        .unwrap()
+        .ends_with(&format!("resume t-1 - <{DEFAULT_RESUME_PROMPT}>")));
+
+    let crashing = write_fake_codex(
+        temp.path(),
+        r#"#!/bin/bash
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"t-2"}'
+exit 1
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&crashing)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let stream = client.stream_exec(request.clone()).await.unwrap();
+    let events: Vec<_> = stream.events.collect().await;
+    let resumes = events
+        .iter()
+        .filter(|event| matches!(event, Ok(ThreadEvent::Resumed { .. })))
+        .count();
+    assert_eq!(resumes, MAX_AUTO_RESUMES as usize);
+    assert!(matches!(
+        stream.completion.await,
+        Err(ExecStreamError::Codex(CodexError::NonZeroExit { .. }))
+    ));
+
+    let stream = client
+        .stream_exec(request.clone().auto_resume(false))
+        .await
+        .unwrap();
+    assert_eq!(stream.events.collect::<Vec<_>>().await.len(), 1);
+    assert!(stream.completion.await.is_err());
+
+    let resume_log = temp.path().join("resume.log");
+    let failing_resume = write_fake_codex(
+        temp.path(),
+        &format!(
+            r#"#!/bin/bash
+stdin=$(cat)
+if [[ " $* " == *" resume "* ]]; then
+  echo "<$stdin>" >> "{}"
+  exit 1
+fi
+echo '{{"type":"thread.started","thread_id":"t-3"}}'
+exit 1
+"#,
+            resume_log.display()
+        ),
+    );
+    let client = CodexClient::builder()
+        .binary(&failing_resume)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let stream = client
+        .stream_exec(request.resume_prompt("keep going"))
+        .await
+        .unwrap();
+    let resumes = stream
+        .events
+        .filter(|event| std::future::ready(matches!(event, Ok(ThreadEvent::Resumed { .. }))))
+        .count()
+        .await;
+    assert_eq!(resumes, 1);
+    assert!(stream.completion.await.is_err());
+    assert_eq!(
+        std_fs::read_to_string(&resume_log).unwrap(),
+        "<keep going>\n"
+    );
+}
+
+#[tokio::test]
+async fn stream_exec_transcript_is_replayable_across_runs() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        temp.path(),
+        r#"#!/bin/bash
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"thread-1"}'
+echo '{"type":"turn.started","turn_id":"turn-1"}'
+echo '{"type":"turn.completed"}'
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let transcript_path = temp.path().join("runs").join("transcript.jsonl");
+
+    for prompt in ["first", "second"] {
+        let stream = client
+            .stream_exec(
+                ExecStreamRequest::new(prompt).transcript(TranscriptSink::new(&transcript_path)),
+            )
+            .await
+            .unwrap();
+        let _: Vec<_> = stream.events.collect().await;
+        stream.completion.await.unwrap();
+    }
+
+    let raw = std_fs::read_to_string(&transcript_path).unwrap();
+    let header: serde_json::Value = serde_json::from_str(raw.lines().next().unwrap()).unwrap();
+    assert_eq!(
+        header["codex_transcript"]["version"],
+        TRANSCRIPT_SCHEMA_VERSION
+    );
+    assert_eq!(
+        header["codex_transcript"]["prompt_hash"],
+        crate::transcript::prompt_hash("first")
+    );
+    assert_eq!(raw.lines().count(), 8);
+
+    let replayed: Vec<_> = replay_transcript(&transcript_path).collect().await;
+    assert_eq!(replayed.len(), 6);
+    for (index, event) in replayed.iter().enumerate() {
+        let event = event.as_ref().expect("replayed event");
+        match index % 3 {
+            0 => assert!(matches!(event, ThreadEvent::ThreadStarted(_))),
+            1 => assert!(matches!(event, ThreadEvent::TurnStarted(_))),
+            _ => assert!(matches!(event, ThreadEvent::TurnCompleted(_))),
+        }
+    }
+}
+
+#[tokio::test]
+async fn replay_transcript_rejects_headerless_files() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("plain.jsonl");
+    std_fs::write(&path, "{\"type\":\"thread.started\",\"thread_id\":\"t\"}\n").unwrap();
+
+    let replayed: Vec<_> = replay_transcript(&path).collect().await;
+    assert_eq!(replayed.len(), 1);
+    assert!(matches!(
+        replayed[0],
+        Err(ExecStreamError::Normalize { .. })
+    ));
+
+    let missing: Vec<_> = replay_transcript(temp.path().join("missing.jsonl"))
+        .collect()
+        .await;
+    assert!(matches!(
+        missing.as_slice(),
+        [Err(ExecStreamError::Codex(CodexError::CaptureIo(_)))]
+    ));
+}
+
+#[test]
+fn transcript_prompt_hash_is_stable() {
+    assert_eq!(
+        crate::transcript::prompt_hash(""),
+        "fnv1a64:cbf29ce484222325"
+    );
+    assert_eq!(
+        crate::transcript::prompt_hash("a"),
+        "fnv1a64:af63dc4c8601ec8c"
+    );
+}
+
+#[tokio::test]
+async fn json_stream_tracks_last_agent_message() {
+    let lines = [
+        r#"{"type":"thread.started","thread_id":"thread-1"}"#,
+        r#"{"type":"turn.started","thread_id":"thread-1","turn_id":"turn-1"}"#,
+        r#"{"type":"item.completed","item":{"id":"msg-1","type":"agent_message","text":"first"}}"#,
+        r#"{"type":"item.completed","item":{"id":"msg-2","type":"agent_message","text":"final answer"}}"#,
+        r#"{"type":"item.completed","item":{"id":"cmd-1","type":"command_execution","command":"ls"}}"#,
+    ];
+
+    let (mut writer, reader) = tokio::io::duplex(4096);
+    let (tx, rx) = mpsc::channel(8);
+    let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(
+        reader,
+        tx,
+        None,
+        Vec::new(),
+        CaptureOptions::default(),
+        ParseErrorMode::Fail,
+        None,
+    ));
+
+    for line in &lines {
+        writer.write_all(line.as_bytes()).await.unwrap();
+        writer.write_all(b"\n").await.unwrap();
+    }
+    writer.shutdown().await.unwrap();
+
+    let stream = crate::jsonl::EventChannelStream::new(rx, None);
+    pin_mut!(stream);
+    let events: Vec<_> = stream.collect().await;
+    assert_eq!(events.len(), lines.len());
+
+    let forwarded = forward_handle.await.unwrap().unwrap();
+    assert_eq!(forwarded.agent_message.as_deref(), Some("final answer"));
+}
+
+#[tokio::test]
+async fn json_stream_without_agent_message_reports_none() {
+    let (mut writer, reader) = tokio::io::duplex(1024);
+    let (tx, rx) = mpsc::channel(4);
+    let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(
+        reader,
+        tx,
+        None,
+        Vec::new(),
+        CaptureOptions::default(),
+        ParseErrorMode::Fail,
+        None,
+    ));
+
+    writer
+        .write_all(b"{\"type\":\"thread.started\",\"thread_id\":\"thread-1\"}\n")
+        .await
+        .unwrap();
+    writer.shutdown().await.unwrap();
+
+    let stream = crate::jsonl::EventChannelStream::new(rx, None);
+    pin_mut!(stream);
+    let _: Vec<_> = stream.collect().await;
+    assert!(forward_handle
+        .await
+        .unwrap()
+        .unwrap()
+        .agent_message
+        .is_none());
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn dropping_exec_stream_kills_and_reaps_child() {
+    let temp = tempfile::tempdir().unwrap();
+    let pid_path = temp.path().join("child.pid");
+    let script = format!(
+        r#"#!/bin/bash
+echo $$ > "{pid}"
+echo '{{"type":"thread.started","thread_id":"thread-1"}}'
+exec sleep 30
+"#,
+        pid = pid_path.display()
+    );
+    let binary = write_fake_codex(temp.path(), &script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let mut stream = client
+        .stream_exec(ExecStreamRequest::new("hang"))
+        .await
+        .unwrap();
+    let first = stream.events.next().await.unwrap().unwrap();
+    assert!(matches!(first, ThreadEvent::ThreadStarted(_)));
+
+    let pid = std_fs::read_to_string(&pid_path)
+        .unwrap()
+        .trim()
+        .to_string();
+    let proc_path = PathBuf::from(format!("/proc/{pid}"));
+    assert!(proc_path.exists());
+
+    drop(stream);
+
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while proc_path.exists() {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "child {pid} was not reaped after dropping the stream"
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[tokio::test]
+async fn event_channel_stream_emits_summary_when_enabled() {
+    let lines = [
+        json!({"type": "thread.started", "thread_id": "thread-1"}),
+        json!({"type": "turn.started", "turn_id": "turn-1"}),
+        json!({
+            "type": "item.completed",
+            "item": {"id": "msg-1", "type": "agent_message", "text": "hi"}
+        }),
+        json!({
+            "type": "turn.completed",
+            "usage": {"input_tokens": 10, "cached_input_tokens": 4, "output_tokens": 3}
+        }),
+        json!({"type": "turn.started", "turn_id": "turn-2"}),
+        json!({"type": "turn.failed", "error": {"message": "boom"}}),
+    ];
+
+    let (mut writer, reader) = tokio::io::duplex(4096);
+    let (tx, rx) = mpsc::channel(8);
+    let forward_handle = tokio::spawn(crate::jsonl::forward_json_events(
+        reader,
+        tx,
+        None,
+        Vec::new(),
+        CaptureOptions::default(),
+        ParseErrorMode::Fail,
+        None,
+    ));
+    for line in &lines {
+        writer.write_all(line.to_string().as_bytes()).await.unwrap();
+        writer.write_all(b"\n").await.unwrap();
+    }
+    writer.shutdown().await.unwrap();
+
+    let stream = crate::jsonl::EventChannelStream::new(rx, None).with_summary(true);
+    pin_mut!(stream);
+    let events: Vec<_> = stream.collect().await;
+    forward_handle.await.unwrap().unwrap();
+
+    assert_eq!(events.len(), lines.len() + 1, "events: {events:?}");
+    match events.last() {
+        Some(Ok(ThreadEvent::ThreadCompleted(summary))) => {
+            assert_eq!(
+                summary,
+                &ThreadSummary {
+                    thread_id: Some("thread-1".to_string()),
+                    turns: 2,
+                    items: 1,
+                    any_turn_failed: true,
+                    usage: TokenUsage {
+                        input_tokens: 10,
+                        cached_input_tokens: 4,
+                        output_tokens: 3,
+                    },
+                }
+            );
+        }
+        other => panic!("expected thread summary, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn event_channel_stream_summary_is_opt_in() {
+    let (tx, rx) = mpsc::channel(1);
+    drop(tx);
+
+    let stream = crate::jsonl::EventChannelStream::new(rx, None);
+    pin_mut!(stream);
+    assert!(stream.next().await.is_none());
+
+    let (tx, rx) = mpsc::channel(1);
+    drop(tx);
+    let stream = crate::jsonl::EventChannelStream::new(rx, None).with_summary(true);
+    pin_mut!(stream);
+    match stream.next().await {
+        Some(Ok(ThreadEvent::ThreadCompleted(summary))) => {
+            assert_eq!(summary, ThreadSummary::default())
+        }
+        other => panic!("expected empty summary, got {other:?}"),
+    }
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn replay_fixture_yields_events_with_stream_error_semantics() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/replay_basic.jsonl");
+    let events: Vec<_> = testing::replay_fixture(&fixture).collect().await;
+    assert_eq!(events.len(), 4);
+    assert!(matches!(events[0], Ok(ThreadEvent::ThreadStarted(_))));
+    match &events[2] {
+        Ok(ThreadEvent::ItemCompleted(envelope)) => match &envelope.item.payload {
+            ItemPayload::AgentMessage(content) => assert_eq!(content.text, "Fixture reply."),
+            other => panic!("expected agent message, got {other:?}"),
+        },
+        other => panic!("expected item.completed, got {other:?}"),
+    }
+    assert!(matches!(events[3], Ok(ThreadEvent::TurnCompleted(_))));
+
+    let temp = tempfile::tempdir().unwrap();
+    let broken = temp.path().join("broken.jsonl");
+    std_fs::write(
+        &broken,
+        "{\"type\":\"thread.started\",\"thread_id\":\"t\"}\nnot json\n{\"type\":\"turn.started\",\"turn_id\":\"turn-1\"}\n",
+    )
+    .unwrap();
+    let events: Vec<_> = testing::replay_fixture(&broken).collect().await;
+    assert_eq!(events.len(), 3);
+    assert!(matches!(events[1], Err(ExecStreamError::Parse { .. })));
+    assert!(matches!(events[2], Ok(ThreadEvent::TurnStarted(_))));
+
+    let missing: Vec<_> = testing::replay_fixture(temp.path().join("missing.jsonl"))
+        .collect()
+        .await;
+    assert!(matches!(
+        missing.as_slice(),
+        [Err(ExecStreamError::Codex(CodexError::CaptureIo(_)))]
+    ));
+}
+
+#[tokio::test]
+async fn metrics_record_exec_tokens_and_errors() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        temp.path(),
+        r#"#!/bin/bash
+read -r prompt
+if [[ "$prompt" == "fail" ]]; then
+  echo "boom" >&2
+  exit 3
+fi
+echo '{"type":"thread.started","thread_id":"thread-1"}'
+echo '{"type":"turn.started","thread_id":"thread-1","turn_id":"turn-1"}'
+echo '{"type":"turn.completed","thread_id":"thread-1","turn_id":"turn-1","usage":{"input_tokens":10,"cached_input_tokens":4,"output_tokens":3}}'
+"#,
+    );
+    let metrics = Arc::new(InMemoryMetrics::new());
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .json(true)
+        .metrics(metrics.clone())
+        .build();
+
+    let stream = client
+        .stream_exec(ExecStreamRequest::new("hello"))
+        .await
+        .unwrap();
+    let events: Vec<_> = stream.events.collect().await;
+    assert_eq!(events.len(), 3);
+    stream.completion.await.unwrap();
+
+    client.send_prompt("hello").await.unwrap();
+    let err = client.send_prompt("fail").await.unwrap_err();
+    assert!(matches!(err, CodexError::NonZeroExit { .. }));
+
+    let snapshot = metrics.snapshot();
+    let statuses: Vec<_> = snapshot
+        .execs
+        .iter()
+        .map(|(_, status)| status.code())
+        .collect();
+    assert_eq!(statuses, vec![Some(0), Some(0), Some(3)]);
+    assert_eq!(
+        snapshot.tokens,
+        TokenUsage {
+            input_tokens: 20,
+            cached_input_tokens: 8,
+            output_tokens: 6,
+        }
+    );
+    assert_eq!(snapshot.errors, vec![ErrorCategory::NonZeroExit]);
+}
+
+#[tokio::test]
+async fn send_prompt_with_progress_forwards_agent_message_text_only() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        temp.path(),
+        r#"#!/bin/bash
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"t-1"}'
+echo '{"type":"turn.started","thread_id":"t-1","turn_id":"u-1"}'
+echo '{"type":"item.completed","thread_id":"t-1","turn_id":"u-1","item_id":"r-1","item_type":"reasoning","content":{"text":"thinking"}}'
+echo '{"type":"item.delta","thread_id":"t-1","turn_id":"u-1","item_id":"msg-1","item_type":"agent_message","delta":{"text_delta":"Hel"}}'
+echo '{"type":"item.delta","thread_id":"t-1","turn_id":"u-1","item_id":"msg-1","item_type":"agent_message","delta":{"text_delta":"lo"}}'
+echo '{"type":"item.completed","thread_id":"t-1","turn_id":"u-1","item_id":"msg-1","item_type":"agent_message","content":{"text":"Hello!"}}'
+echo '{"type":"turn.completed","thread_id":"t-1","turn_id":"u-1"}'
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let mut chunks = Vec::new();
+    let reply = client
+        .send_prompt_with_progress("hi", |chunk| chunks.push(chunk.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(reply, "Hello!");
+    assert_eq!(chunks, vec!["Hel", "lo", "!"]);
+
+    let mut chunks = String::new();
+    let mut other = Vec::new();
+    let reply = client
+        .send_prompt_with_progress_and_events(
+            "hi",
+            |chunk| chunks.push_str(chunk),
+            |event| {
+                other.push(match event {
+                    ThreadEvent::ItemCompleted(_) => "item",
+                    ThreadEvent::ThreadStarted(_) => "thread",
+                    ThreadEvent::TurnStarted(_) | ThreadEvent::TurnCompleted(_) => "turn",
+                    _ => "other",
+                })
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(reply, "Hello!");
+    assert_eq!(chunks, "Hello!");
+    assert_eq!(other, vec!["thread", "turn", "item", "turn"]);
+}
+
+#[tokio::test]
+async fn send_prompt_with_progress_keeps_stream_errors_matchable() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        temp.path(),
+        r#"#!/bin/bash
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"t-1"}'
+echo 'not json'
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let err = client
+        .send_prompt_with_progress("hi", |_| {})
+        .await
+        .unwrap_err();
+    match &err {
+        CodexError::Stream(source) => {
+            assert!(
+                matches!(source.as_ref(), ExecStreamError::Parse { line, .. } if line == "not json")
+            );
+        }
+        other => panic!("expected Stream, got {other:?}"),
+    }
+    assert_eq!(ErrorCategory::from(&err), ErrorCategory::Parse);
+}
+
+#[tokio::test]
+async fn exec_collect_returns_raw_events_message_and_usage() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        temp.path(),
+        r#"#!/bin/bash
+[[ " $* " == *" --json "* ]] || exit 9
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"t-1"}'
+echo '{"type":"turn.started","thread_id":"t-1","turn_id":"u-1"}'
+echo 'not json'
+echo '{"type":"item.completed","thread_id":"t-1","turn_id":"u-1","item_id":"msg-1","item_type":"agent_message","content":{"text":"first"}}'
+echo '{"type":"item.completed","thread_id":"t-1","turn_id":"u-1","item_id":"msg-2","item_type":"agent_message","content":{"text":"done"}}'
+echo '{"type":"turn.completed","thread_id":"t-1","turn_id":"u-1","usage":{"input_tokens":10,"cached_input_tokens":2,"output_tokens":5}}'
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let result = client.exec_collect("hi").await.unwrap();
+    assert!(result.status.success());
+    assert_eq!(result.raw.lines().count(), 6);
+    assert_eq!(result.events.len(), 5);
+    assert!(matches!(
+        result.events.last(),
+        Some(ThreadEvent::TurnCompleted(_))
+    ));
+    assert_eq!(result.last_message.as_deref(), Some("done"));
+    assert_eq!(
+        result.usage,
+        Some(TokenUsage {
+            input_tokens: 10,
+            cached_input_tokens: 2,
+            output_tokens: 5,
+        })
+    );
+    assert_eq!(result.parse_errors.len(), 1);
+    assert_eq!(result.parse_errors[0].line, "not json");
+
+    assert!(matches!(
+        client.exec_collect("  ").await,
+        Err(CodexError::EmptyPrompt)
+    ));
+}
+
+#[tokio::test]
+async fn stream_exec_parse_error_modes_skip_or_collect_bad_lines() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        temp.path(),
+        r#"#!/bin/bash
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"t-1"}'
+echo 'WARNING: proxy settings ignored'
+echo '{"type":"turn.started","thread_id":"t-1","turn_id":"u-1"}'
+echo '{"type":"turn.completed","thread_id":"t-1","turn_id":"u-1"}'
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let request = ExecStreamRequest::new("hello");
+
+    let stream = client.stream_exec(request.clone()).await.unwrap();
+    let events: Vec<_> = stream.events.collect().await;
+    assert_eq!(events.len(), 4);
+    assert!(matches!(events[1], Err(ExecStreamError::Parse { .. })));
+    assert!(stream.completion.await.unwrap().parse_errors.is_empty());
+
+    for mode in [ParseErrorMode::Skip, ParseErrorMode::Collect] {
+        let stream = client
+            .stream_exec(request.clone().on_parse_error(mode))
+            .await
+            .unwrap();
+        let events: Vec<_> = stream.events.collect().await;
+        assert_eq!(events.len(), 3, "{mode:?}");
+        assert!(events.iter().all(Result::is_ok), "{mode:?}");
+
+        let completion = stream.completion.await.unwrap();
+        if mode == ParseErrorMode::Collect {
+            assert_eq!(completion.parse_errors.len(), 1);
+            assert_eq!(
+                completion.parse_errors[0].line,
+                "WARNING: proxy settings ignored"
+            );
+            assert!(!completion.parse_errors[0].message.is_empty());
+        } else {
+            assert!(completion.parse_errors.is_empty());
+        }
+    }
+}
+
+#[tokio::test]
+async fn stream_exec_event_filter_drops_items_at_source() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = write_fake_codex(
+        temp.path(),
+        r#"#!/bin/bash
+cat >/dev/null
+echo '{"type":"thread.started","thread_id":"t-1"}'
+echo '{"type":"turn.started","thread_id":"t-1","turn_id":"u-1"}'
+echo '{"type":"item.started","thread_id":"t-1","turn_id":"u-1","item_id":"r-1","item_type":"reasoning","content":{"text":""}}'
+echo '{"type":"item.delta","thread_id":"t-1","turn_id":"u-1","item_id":"r-1","item_type":"reasoning","delta":{"text_delta":"thinking"}}'
+echo '{"type":"item.completed","thread_id":"t-1","turn_id":"u-1","item_id":"r-1","item_type":"reasoning","content":{"text":"thinking"}}'
+echo '{"type":"item.completed","thread_id":"t-1","turn_id":"u-1","item_id":"msg-1","item_type":"agent_message","content":{"text":"Hello!"}}'
+echo '{"type":"turn.completed","thread_id":"t-1","turn_id":"u-1"}'
+"#,
+    );
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+    let request =
+        ExecStreamRequest::new("hello").event_filter(EventFilter::except([ItemKind::Reasoning]));
+
+    let stream = client.stream_exec(request.clone()).await.unwrap();
+    let events: Vec<_> = stream.events.map(|event| event.unwrap()).collect().await;
+    assert_eq!(events.len(), 4);
+    assert!(matches!(events[0], ThreadEvent::ThreadStarted(_)));
+    assert!(matches!(events[1], ThreadEvent::TurnStarted(_)));
+    assert!(matches!(
+        &events[2],
+        ThreadEvent::ItemCompleted(envelope) if envelope.item.payload.kind() == ItemKind::AgentMessage
+    ));
+    assert!(matches!(events[3], ThreadEvent::TurnCompleted(_)));
+
+    let completion = stream.completion.await.unwrap();
+    assert_eq!(completion.agent_message.as_deref(), Some("Hello!"));
+    assert_eq!(
+        completion.filtered_events,
+        std::collections::BTreeMap::from([(ItemKind::Reasoning, 3)])
+    );
+
+    let stream = client
+        .stream_exec(request.event_filter(EventFilter::only([])))
+        .await
+        .unwrap();
+    assert_eq!(stream.events.collect::<Vec<_>>().await.len(), 3);
+    let completion = stream.completion.await.unwrap();
+    assert_eq!(
+        completion.filtered_events,
+        std::collections::BTreeMap::from([(ItemKind::Reasoning, 3), (ItemKind::AgentMessage, 1)])
+    );
+}
+
+#[tokio::test]
+async fn mirror_batch_coalesces_lines_until_interval_elapses() {
+    use crate::jsonl::MirrorBatch;
+
+    let mut per_line = MirrorBatch::new(MirrorFlush::PerLine);
+    assert_eq!(per_line.push("a").as_deref(), Some("a\n"));
+    assert_eq!(per_line.deadline(), None);
+    assert_eq!(per_line.take(), None);
+
+    let mut batched = MirrorBatch::new(MirrorFlush::Batched(Duration::from_millis(30)));
+    assert_eq!(batched.push("a"), None);
+    assert_eq!(batched.push("b"), None);
+    let deadline = batched.deadline().expect("deadline armed by first line");
+    tokio::time::sleep_until(deadline).await;
+    assert_eq!(batched.push("c").as_deref(), Some("a\nb\nc\n"));
+    assert_eq!(batched.deadline(), None);
+
+    assert_eq!(batched.push("d"), None);
+    assert_eq!(batched.take().as_deref(), Some("d\n"));
+    assert_eq!(batched.take(), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_completion_into_result_prefers_agent_message() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let completion = |raw_status: i32, agent: Option<&str>, last: Option<&str>| ExecCompletion {
+        status: std::process::ExitStatus::from_raw(raw_status),
+        last_message_path: None,
+        last_message: last.map(str::to_string),
+        agent_message: agent.map(str::to_string),
+        schema_path: None,
+        parse_errors: Vec::new(),
+        filtered_events: std::collections::BTreeMap::new(),
+    };
+
+    assert_eq!(
+        completion(0, Some("agent"), Some("last"))
+            .into_result()
+            .unwrap(),
+        "agent"
+    );
+    assert_eq!(
+        completion(0, None, Some("last")).into_result().unwrap(),
+        "last"
+    );
+    assert_eq!(completion(0, None, None).into_result().unwrap(), "");
+
+    match completion(2 << 8, Some("agent"), None).into_result() {
+        Err(CodexError::NonZeroExit {
+            command, exit_code, ..
+        }) => {
+            assert_eq!(command, "codex exec");
+            assert_eq!(exit_code, Some(2));
+        }
+        other => panic!("expected NonZeroExit, got {other:?}"),
+    }
+}
@@ -1,10 +1,11 @@
 use super::*;
-use crate::auth::parse_login_success;
+use crate::auth::{parse_login_line, parse_login_success};
 use crate::builder::ResolvedCliOverrides;
 use crate::defaults::{
     default_binary_path, default_rust_log_value, CODEX_BINARY_ENV, CODEX_HOME_ENV,
-    DEFAULT_RUST_LOG, DEFAULT_TIMEOUT, RUST_LOG_ENV,
+    DEFAULT_IO_CHUNK_SIZE, DEFAULT_RUST_LOG, DEFAULT_TIMEOUT, RUST_LOG_ENV,
 };
+use crate::process::CaptureOptions;
 use futures_util::{pin_mut, StreamExt};
 use semver::Version;
 use serde_json::json;
@@ -30,6 +31,8 @@ mod capabilities;
 mod cli;
 mod cli_overrides;
 mod cloud;
+mod events;
 mod jsonl;
 mod mcp;
 mod sandbox_execpolicy;
+mod template;
@@ -178,6 +178,70 @@ exit 7
     assert_eq!(run.stdout.trim(), "failing");
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn run_sandbox_with_denials_parses_denials_and_decision() {
+    let dir = tempfile::tempdir().unwrap();
+    let script_path = write_fake_codex(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+printf "%s\n" "$@"
+if [[ "$2" == "macos" ]]; then
+  echo "touch: /etc/hosts: Operation not permitted" >&2
+  printf "\n=== Sandbox denials ===\n(touch) file-write-data /etc/hosts\n(touch) file-write-xattr /etc/hosts\n" >&2
+  exit 1
+fi
+"#,
+    );
+
+    let client = CodexClient::builder()
+        .binary(&script_path)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let denied = client
+        .run_sandbox_with_denials(
+            vec!["touch".to_string(), "/etc/hosts".to_string()],
+            SandboxPlatform::Macos,
+        )
+        .await
+        .unwrap();
+    let args: Vec<_> = denied.stdout.lines().collect();
+    assert_eq!(
+        args,
+        vec![
+            "sandbox",
+            "macos",
+            "--log-denials",
+            "--",
+            "touch",
+            "/etc/hosts"
+        ]
+    );
+    assert_eq!(denied.decision, SandboxDecision::Denied);
+    assert_eq!(
+        denied.denials,
+        vec![
+            SandboxDenial {
+                process: "touch".to_string(),
+                capability: "file-write-data /etc/hosts".to_string(),
+            },
+            SandboxDenial {
+                process: "touch".to_string(),
+                capability: "file-write-xattr /etc/hosts".to_string(),
+            },
+        ]
+    );
+
+    let allowed = client
+        .run_sandbox_with_denials(vec!["true".to_string()], SandboxPlatform::Linux)
+        .await
+        .unwrap();
+    assert_eq!(allowed.decision, SandboxDecision::Allowed);
+    assert!(allowed.denials.is_empty());
+}
+
 #[cfg(unix)]
 #[tokio::test]
 async fn execpolicy_maps_policies_and_overrides() {
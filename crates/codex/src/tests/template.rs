@@ -0,0 +1,74 @@
+use super::*;
+use std::collections::BTreeMap;
+
+#[test]
+fn prompt_template_substitutes_values_and_defaults() {
+    let template = PromptTemplate::new("Review {{ file }} for {{focus|correctness}}.");
+    let mut vars = BTreeMap::new();
+    vars.insert("file", "src/lib.rs".to_string());
+
+    assert_eq!(
+        template.render(&vars).unwrap(),
+        "Review src/lib.rs for correctness."
+    );
+
+    vars.insert("focus", "performance".to_string());
+    assert_eq!(
+        template.render(&vars).unwrap(),
+        "Review src/lib.rs for performance."
+    );
+}
+
+#[test]
+fn prompt_template_rejects_unresolved_placeholders() {
+    let vars = BTreeMap::new();
+
+    assert_eq!(
+        PromptTemplate::new("Hello {{name}}").render(&vars),
+        Err(TemplateError::MissingVariable {
+            name: "name".to_string()
+        })
+    );
+    assert_eq!(
+        PromptTemplate::new("Hello {{name").render(&vars),
+        Err(TemplateError::Unterminated { offset: 6 })
+    );
+    assert_eq!(
+        PromptTemplate::new("{{ |x}}").render(&vars),
+        Err(TemplateError::EmptyName { offset: 0 })
+    );
+    assert_eq!(
+        PromptTemplate::new("no placeholders } {")
+            .render(&vars)
+            .unwrap(),
+        "no placeholders } {"
+    );
+}
+
+#[tokio::test]
+async fn send_prompt_template_renders_before_spawning() {
+    let _guard = env_guard_async().await;
+    let temp = tempfile::tempdir().unwrap();
+    let script = "#!/usr/bin/env bash\nprintf '%s\\n' \"${@: -1}\"\n";
+    let binary = write_fake_codex(temp.path(), script);
+    let client = CodexClient::builder()
+        .binary(&binary)
+        .mirror_stdout(false)
+        .quiet(true)
+        .build();
+
+    let template = PromptTemplate::from("Summarize {{topic}}");
+    let mut vars = BTreeMap::new();
+    vars.insert("topic", "the diff".to_string());
+    let output = client.send_prompt_template(&template, &vars).await.unwrap();
+    assert_eq!(output, "Summarize the diff");
+
+    let err = client
+        .send_prompt_template(&template, &BTreeMap::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        CodexError::Template(TemplateError::MissingVariable { .. })
+    ));
+}
@@ -0,0 +1,163 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures_core::Stream;
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs,
+    io::{AsyncBufReadExt, BufReader, Lines},
+};
+
+use crate::{
+    jsonl::{JsonLogCompression, JsonLogSink},
+    CodexError, ExecStreamError, JsonlThreadEventParser, ThreadEvent,
+};
+
+/// Schema version written into every [`TranscriptHeader`].
+pub const TRANSCRIPT_SCHEMA_VERSION: u32 = 1;
+
+/// Self-describing transcript target for [`crate::ExecStreamRequest::transcript`].
+///
+/// Each run appends one header line (see [`TranscriptHeader`]) followed by the raw JSONL event
+/// lines, so a file may hold several runs back to back. Unlike `json_event_log`, the header
+/// makes the file replayable via [`replay_transcript`] without out-of-band metadata.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TranscriptSink {
+    path: PathBuf,
+}
+
+impl TranscriptSink {
+    /// Targets `path`; the file and its parent directories are created by the first run and
+    /// appended to by later ones.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// File the transcript is written to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) async fn open(&self, prompt: &str) -> Result<JsonLogSink, std::io::Error> {
+        let mut sink = JsonLogSink::new(self.path.clone(), Some(JsonLogCompression::None)).await?;
+        let header = TranscriptRecord {
+            codex_transcript: TranscriptHeader::for_prompt(prompt),
+        };
+        let line = serde_json::to_string(&header).map_err(std::io::Error::other)?;
+        sink.write_line(&line).await?;
+        Ok(sink)
+    }
+}
+
+/// Metadata line that starts every transcript segment.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptHeader {
+    /// Transcript format version ([`TRANSCRIPT_SCHEMA_VERSION`] when written by this crate).
+    pub version: u32,
+    /// Milliseconds since the Unix epoch when the run started.
+    pub created_at_ms: u64,
+    /// `fnv1a64:<hex>` digest of the prompt, for correlating runs without storing the prompt.
+    pub prompt_hash: String,
+}
+
+impl TranscriptHeader {
+    fn for_prompt(prompt: &str) -> Self {
+        let created_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        Self {
+            version: TRANSCRIPT_SCHEMA_VERSION,
+            created_at_ms,
+            prompt_hash: prompt_hash(prompt),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TranscriptRecord {
+    codex_transcript: TranscriptHeader,
+}
+
+/// Stable, dependency-free prompt digest (64-bit FNV-1a).
+pub(crate) fn prompt_hash(prompt: &str) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let hash = prompt.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    format!("fnv1a64:{hash:016x}")
+}
+
+struct ReplayState {
+    lines: Option<Lines<BufReader<fs::File>>>,
+    path: PathBuf,
+    parser: JsonlThreadEventParser,
+    require_header: bool,
+    seen_header: bool,
+}
+
+/// Replays a transcript written through [`TranscriptSink`] as typed events.
+///
+/// Header lines are consumed (resetting thread/turn context for each appended run) and not
+/// yielded. A file that does not start with a header yields a single
+/// [`ExecStreamError::Normalize`] error; open/read failures surface as
+/// [`CodexError::CaptureIo`].
+pub fn replay_transcript(
+    path: impl AsRef<Path>,
+) -> impl Stream<Item = Result<ThreadEvent, ExecStreamError>> {
+    replay_jsonl(path.as_ref(), true)
+}
+
+/// Shared replay loop; `require_header: false` accepts raw `codex exec --json` output.
+pub(crate) fn replay_jsonl(
+    path: &Path,
+    require_header: bool,
+) -> impl Stream<Item = Result<ThreadEvent, ExecStreamError>> {
+    let state = ReplayState {
+        lines: None,
+        path: path.to_path_buf(),
+        parser: JsonlThreadEventParser::new(),
+        require_header,
+        seen_header: false,
+    };
+    stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        if state.lines.is_none() {
+            match fs::File::open(&state.path).await {
+                Ok(file) => state.lines = Some(BufReader::new(file).lines()),
+                Err(err) => return Some((Err(CodexError::CaptureIo(err).into()), None)),
+            }
+        }
+        loop {
+            let line = match state.lines.as_mut()?.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => return None,
+                Err(err) => return Some((Err(CodexError::CaptureIo(err).into()), None)),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            if serde_json::from_str::<TranscriptRecord>(&line).is_ok() {
+                state.seen_header = true;
+                state.parser.reset();
+                continue;
+            }
+            if state.require_header && !state.seen_header {
+                let err = ExecStreamError::Normalize {
+                    line,
+                    message: "transcript is missing its header line".to_string(),
+                };
+                return Some((Err(err), None));
+            }
+            match state.parser.parse_line(&line) {
+                Ok(Some(event)) => return Some((Ok(event), Some(state))),
+                Ok(None) => continue,
+                Err(err) => return Some((Err(err), Some(state))),
+            }
+        }
+    })
+}
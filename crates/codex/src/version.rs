@@ -56,29 +56,40 @@ fn release_channel_for_version(version: &Version) -> CodexReleaseChannel {
     if version.pre.is_empty() {
         CodexReleaseChannel::Stable
     } else {
-        let prerelease = version.pre.as_str().to_ascii_lowercase();
-        if prerelease.contains("beta") {
-            CodexReleaseChannel::Beta
-        } else if prerelease.contains("nightly") {
-            CodexReleaseChannel::Nightly
-        } else {
-            CodexReleaseChannel::Custom
-        }
+        infer_release_channel(version.pre.as_str())
     }
 }
 
-fn infer_release_channel(raw: &str) -> CodexReleaseChannel {
+pub(crate) fn infer_release_channel(raw: &str) -> CodexReleaseChannel {
     let lower = raw.to_ascii_lowercase();
     if lower.contains("beta") {
         CodexReleaseChannel::Beta
     } else if lower.contains("nightly") {
         CodexReleaseChannel::Nightly
+    } else if has_prerelease_tag(&lower, &["rc"]) {
+        CodexReleaseChannel::ReleaseCandidate
+    } else if has_prerelease_tag(&lower, &["alpha", "dev", "preview"]) {
+        CodexReleaseChannel::Alpha
     } else {
         CodexReleaseChannel::Custom
     }
 }
 
-fn codex_semver(info: &CodexVersionInfo) -> Option<Version> {
+/// Matches whole alphanumeric tokens like `rc`, `rc1`, or `alpha2` so that short tags such
+/// as `rc` do not fire on unrelated words (e.g. "source").
+fn has_prerelease_tag(lower: &str, tags: &[&str]) -> bool {
+    lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|token| {
+            tags.iter().any(|tag| {
+                token
+                    .strip_prefix(tag)
+                    .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+            })
+        })
+}
+
+pub(crate) fn codex_semver(info: &CodexVersionInfo) -> Option<Version> {
     if let Some(parsed) = parse_semver_from_raw(&info.raw) {
         return Some(parsed);
     }
@@ -92,13 +103,19 @@ fn codex_semver(info: &CodexVersionInfo) -> Option<Version> {
             CodexReleaseChannel::Nightly => {
                 version.pre = Prerelease::new("nightly").ok()?;
             }
+            CodexReleaseChannel::ReleaseCandidate => {
+                version.pre = Prerelease::new("rc").ok()?;
+            }
+            CodexReleaseChannel::Alpha => {
+                version.pre = Prerelease::new("alpha").ok()?;
+            }
             CodexReleaseChannel::Stable | CodexReleaseChannel::Custom => {}
         }
     }
     Some(version)
 }
 
-fn codex_release_from_info(info: &CodexVersionInfo) -> Option<CodexRelease> {
+pub(crate) fn codex_release_from_info(info: &CodexVersionInfo) -> Option<CodexRelease> {
     let version = codex_semver(info)?;
     Some(CodexRelease {
         channel: info.channel,
@@ -154,10 +171,8 @@ pub(super) fn parse_features_from_json(output: &str) -> Option<CodexFeatureFlags
 
 fn collect_feature_tokens(value: &Value, tokens: &mut HashSet<String>) {
     match value {
-        Value::String(value) => {
-            if !value.trim().is_empty() {
-                tokens.insert(value.clone());
-            }
+        Value::String(value) if !value.trim().is_empty() => {
+            tokens.insert(value.clone());
         }
         Value::Array(items) => {
             for item in items {
@@ -194,7 +209,6 @@ pub(super) fn parse_features_from_text(output: &str) -> CodexFeatureFlags {
     if lower.contains("login") && lower.contains("mcp") {
         flags.supports_mcp_login = true;
     }
-
     for token in lower
         .split(|c: char| c.is_ascii_whitespace() || c == ',' || c == ';' || c == '|')
         .filter(|token| !token.is_empty())
@@ -213,11 +227,58 @@ pub(super) fn parse_help_output(output: &str) -> CodexFeatureFlags {
     flags
 }
 
+/// Parses `codex exec --help` and reports exec-only flags declared in its options table.
+///
+/// Only option rows count, so prose or echoed argv mentioning a flag does not enable it.
+pub(super) fn parse_exec_help_output(output: &str) -> CodexFeatureFlags {
+    let declared = help_flag_table(output);
+    CodexFeatureFlags {
+        supports_json_output: declared.contains("--json"),
+        supports_add_dir_read_only: declared.contains("--add-dir-read-only"),
+        ..CodexFeatureFlags::default()
+    }
+}
+
+fn help_flag_table(output: &str) -> HashSet<String> {
+    let mut declared = HashSet::new();
+    for line in output.lines() {
+        let row = line.trim_start();
+        if !row.starts_with('-') {
+            continue;
+        }
+        let spec = row.split("  ").next().unwrap_or_default();
+        for token in spec.split(|c: char| c.is_ascii_whitespace() || c == ',') {
+            let name = token.split(['=', '[', '<']).next().unwrap_or_default();
+            if name.len() > 2 && name.starts_with("--") {
+                declared.insert(name.to_string());
+            }
+        }
+    }
+    declared
+}
+
+/// Collects the subcommand names listed under `Commands:` in `codex app-server --help`.
+pub(super) fn parse_app_server_help_output(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| line.trim() != "Commands:")
+        .skip(1)
+        .take_while(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty())
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
 pub(super) fn merge_feature_flags(target: &mut CodexFeatureFlags, update: CodexFeatureFlags) {
     target.supports_features_list |= update.supports_features_list;
     target.supports_output_schema |= update.supports_output_schema;
     target.supports_add_dir |= update.supports_add_dir;
     target.supports_mcp_login |= update.supports_mcp_login;
+    target.supports_json_output |= update.supports_json_output;
+    target.supports_add_dir_read_only |= update.supports_add_dir_read_only;
+    if update.app_server_subcommands.is_some() {
+        target.app_server_subcommands = update.app_server_subcommands;
+    }
 }
 
 pub(super) fn detected_feature_flags(flags: &CodexFeatureFlags) -> bool {
@@ -261,6 +322,12 @@ fn apply_feature_token(flags: &mut CodexFeatureFlags, token: &str) {
     {
         flags.supports_mcp_login = true;
     }
+    if normalized.contains("json_output") || compact.contains("jsonoutput") {
+        flags.supports_json_output = true;
+    }
+    if normalized.contains("add_dir_read_only") || compact.contains("adddirreadonly") {
+        flags.supports_add_dir_read_only = true;
+    }
 }
 
 pub(super) fn parse_feature_list_output(
@@ -71,6 +71,9 @@ pub fn wrapper_crate_version() -> &'static str {
 /// This value is consumed by `xtask codex-wrapper-coverage` to generate
 /// `cli_manifests/codex/wrapper_coverage.json`.
 pub fn wrapper_coverage_manifest() -> WrapperCoverageManifestV1 {
+    const RESUME_TUI_NOTE: &str =
+        "Interactive TUI resume; `CodexClient::resume_session` streams via `codex exec resume`.";
+
     fn flag(key: &str, level: CoverageLevel) -> WrapperFlagCoverageV1 {
         WrapperFlagCoverageV1 {
             key: key.to_string(),
@@ -548,15 +551,31 @@ pub fn wrapper_coverage_manifest() -> WrapperCoverageManifestV1 {
             ),
             command(
                 &["resume"],
-                CoverageLevel::Explicit,
-                None,
+                CoverageLevel::IntentionallyUnsupported,
+                Some(RESUME_TUI_NOTE),
                 vec![
-                    flag("--all", CoverageLevel::Explicit),
-                    flag("--last", CoverageLevel::Explicit),
+                    flag_note(
+                        "--all",
+                        CoverageLevel::IntentionallyUnsupported,
+                        RESUME_TUI_NOTE,
+                    ),
+                    flag_note(
+                        "--last",
+                        CoverageLevel::IntentionallyUnsupported,
+                        RESUME_TUI_NOTE,
+                    ),
                 ],
                 vec![
-                    arg("PROMPT", CoverageLevel::Explicit),
-                    arg("SESSION_ID", CoverageLevel::Explicit),
+                    arg_note(
+                        "PROMPT",
+                        CoverageLevel::IntentionallyUnsupported,
+                        RESUME_TUI_NOTE,
+                    ),
+                    arg_note(
+                        "SESSION_ID",
+                        CoverageLevel::IntentionallyUnsupported,
+                        RESUME_TUI_NOTE,
+                    ),
                 ],
             ),
             command(
@@ -120,13 +120,10 @@ async fn exec_resume_diff_apply_live_roundtrip() -> Result<(), Box<dyn std::erro
         .build();
 
     let prompt = "You are running a Codex e2e check. Create hello.txt containing only \"hello world\" using apply_patch. Do not run other shell commands. Stop after writing.";
-    let exec_request = ExecStreamRequest {
-        prompt: prompt.to_string(),
-        idle_timeout: Some(Duration::from_secs(120)),
-        output_last_message: Some(workspace.path.join("exec-last.txt")),
-        output_schema: None,
-        json_event_log: Some(workspace.path.join("exec-events.jsonl")),
-    };
+    let exec_request = ExecStreamRequest::new(prompt)
+        .idle_timeout(Duration::from_secs(120))
+        .output_last_message(workspace.path.join("exec-last.txt"))
+        .json_event_log(workspace.path.join("exec-events.jsonl"));
 
     let mut thread_id = None;
     let exec_stream = match client.stream_exec(exec_request).await {
@@ -171,7 +168,7 @@ async fn exec_resume_diff_apply_live_roundtrip() -> Result<(), Box<dyn std::erro
 
     let diff = match client.diff().await {
         Ok(output) => output,
-        Err(CodexError::NonZeroExit { status, stderr }) => {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
             cli.note_skip(format!("diff returned {}: {}", status, stderr.trim()));
             return Ok(());
         }
@@ -187,7 +184,7 @@ async fn exec_resume_diff_apply_live_roundtrip() -> Result<(), Box<dyn std::erro
 
     let apply = match client.apply().await {
         Ok(output) => output,
-        Err(CodexError::NonZeroExit { status, stderr }) => {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
             cli.note_skip(format!("apply returned {}: {}", status, stderr.trim()));
             return Ok(());
         }
@@ -276,7 +273,7 @@ async fn features_list_prefers_text_when_json_flag_is_missing(
         .await
     {
         Ok(output) => output,
-        Err(CodexError::NonZeroExit { status, stderr }) => {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
             cli.note_skip(format!(
                 "features list failed with {status}: {}",
                 stderr.trim()
@@ -307,7 +304,7 @@ async fn app_server_codegen_generates_schema_bundle() -> Result<(), Box<dyn std:
         .await
     {
         Ok(output) => output,
-        Err(CodexError::NonZeroExit { status, stderr }) => {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
             cli.note_skip(format!(
                 "app-server codegen exited {status}: {}",
                 stderr.trim()
@@ -371,7 +368,7 @@ async fn responses_api_proxy_emits_server_info_and_shuts_down(
         .await
     {
         Ok(handle) => handle,
-        Err(CodexError::NonZeroExit { status, stderr }) => {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
             cli.note_skip(format!(
                 "responses-api-proxy exited {status}: {}",
                 stderr.trim()
@@ -536,7 +533,7 @@ async fn diff_and_apply_report_current_cli_gaps() -> Result<(), Box<dyn std::err
                 ));
             }
         }
-        Err(CodexError::NonZeroExit { status, stderr }) => {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
             cli.note_skip(format!(
                 "codex diff returned {} (likely TTY/task prerequisite): {}",
                 status,
@@ -555,7 +552,7 @@ async fn diff_and_apply_report_current_cli_gaps() -> Result<(), Box<dyn std::err
                 ));
             }
         }
-        Err(CodexError::NonZeroExit { status, stderr }) => {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
             cli.note_skip(format!(
                 "codex apply returned {} (missing task id or flag support): {}",
                 status,
@@ -593,7 +590,7 @@ async fn execpolicy_check_is_missing_in_current_cli() -> Result<(), Box<dyn std:
                 result.status
             );
         }
-        Err(CodexError::NonZeroExit { status, stderr }) => {
+        Err(CodexError::NonZeroExit { status, stderr, .. }) => {
             cli.note_skip(format!(
                 "execpolicy check not supported on this binary ({}): {}",
                 status,
@@ -359,13 +359,7 @@ async fn collect_streaming_events(
         .build();
 
     let mut stream = client
-        .stream_exec(ExecStreamRequest {
-            prompt: "fixture prompt".to_string(),
-            idle_timeout: None,
-            output_last_message: None,
-            output_schema: None,
-            json_event_log: None,
-        })
+        .stream_exec(ExecStreamRequest::new("fixture prompt"))
         .await
         .expect("start exec stream");
 